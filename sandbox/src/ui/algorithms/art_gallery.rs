@@ -0,0 +1,146 @@
+use crate::ui::view::{AlgorithmSection, View};
+
+use eframe::{
+    egui::{
+        self,
+        plot::{Legend, Line, Plot, Points},
+    },
+    epaint::Color32,
+};
+use galmetry::{
+    algorithms::{algorithm::Algorithm, art_gallery::ArtGallery},
+    geometry::{point::Point, points, polygon::Polygon},
+};
+use rand::Rng;
+
+const COLORS: [Color32; 3] = [
+    Color32::from_rgb(255, 80, 80),
+    Color32::from_rgb(80, 255, 80),
+    Color32::from_rgb(80, 160, 255),
+];
+
+pub struct ArtGalleryView {
+    polygon: Polygon,
+    algo: ArtGallery,
+
+    // true while the algorithm is animated, one step for each frame
+    running: bool,
+}
+
+impl ArtGalleryView {
+    /// a random star-shaped polygon, so that it is always simple
+    pub fn random(capacity: usize) -> Self {
+        let mut rng = rand::thread_rng();
+        let mut angles: Vec<f64> = (0..capacity)
+            .map(|_| rng.gen_range(0.0..std::f64::consts::TAU))
+            .collect();
+        angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let vertices: Vec<Point> = angles
+            .iter()
+            .map(|angle| {
+                let radius = rng.gen_range(0.1..0.4);
+                Point::from2d(0.5 + radius * angle.cos(), 0.5 + radius * angle.sin())
+            })
+            .collect();
+
+        let vertices = points::Points::from(vertices);
+        Self {
+            algo: ArtGallery::build(vertices.clone()),
+            polygon: Polygon::new(vertices),
+            running: false,
+        }
+    }
+
+    fn line(&self, a: usize, b: usize) -> Line {
+        let (p, q) = (self.polygon.vertices()[a], self.polygon.vertices()[b]);
+        Line::new(vec![[p.x, p.y], [q.x, q.y]])
+    }
+}
+
+impl View for ArtGalleryView {
+    fn ui(&mut self, ui: &mut eframe::egui::Ui) {
+        let plot = Plot::new("Art Gallery")
+            .legend(Legend::default())
+            .view_aspect(1.0)
+            .allow_boxed_zoom(false)
+            .allow_zoom(false)
+            .allow_drag(false);
+
+        plot.show(ui, |plot_ui| {
+            for t in self.algo.triangles() {
+                for i in 0..3 {
+                    plot_ui.line(
+                        self.line(t[i], t[(i + 1) % 3])
+                            .color(Color32::from_rgb(0, 120, 255)),
+                    );
+                }
+            }
+
+            let n = self.polygon.len();
+            for i in 0..n {
+                plot_ui.line(
+                    self.line(i, (i + 1) % n)
+                        .color(Color32::from_rgb(255, 255, 255)),
+                );
+            }
+
+            for (v, color) in self.algo.colors().iter().enumerate() {
+                if let Some(color) = color {
+                    let p = self.polygon.vertices()[v];
+                    plot_ui.points(
+                        Points::new(vec![[p.x, p.y]])
+                            .radius(3.0)
+                            .color(COLORS[*color]),
+                    );
+                }
+            }
+
+            if self.algo.is_done() {
+                let guards: Vec<[f64; 2]> = self
+                    .algo
+                    .guards()
+                    .iter()
+                    .map(|&v| {
+                        let p = self.polygon.vertices()[v];
+                        [p.x, p.y]
+                    })
+                    .collect();
+                plot_ui.points(
+                    Points::new(guards)
+                        .radius(6.0)
+                        .color(Color32::from_rgb(200, 200, 0))
+                        .shape(eframe::egui::plot::MarkerShape::Square)
+                        .name("Guards"),
+                );
+            }
+        });
+    }
+}
+
+impl AlgorithmSection for ArtGalleryView {
+    fn label(&self) -> &'static str {
+        "Art Gallery"
+    }
+
+    fn calculate(&mut self) {
+        self.algo.reset();
+        self.running = true;
+    }
+
+    fn reset(&mut self) {
+        *self = ArtGalleryView::random(20);
+    }
+
+    fn show(&mut self, ctx: &eframe::egui::Context) {
+        if self.running {
+            self.algo.step();
+            self.running = !self.algo.is_done();
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            self.ui(ui);
+        });
+    }
+}
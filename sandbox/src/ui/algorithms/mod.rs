@@ -1,2 +1,6 @@
+pub mod art_gallery;
 pub mod convex_hull;
 pub mod sweep_plane;
+pub mod trapezoidal_map;
+pub mod triangulation;
+pub mod voronoi;
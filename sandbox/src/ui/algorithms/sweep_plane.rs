@@ -77,7 +77,7 @@ impl AlgorithmSection for SweepPlaneView {
 
     fn calculate(&mut self) {
         let mut algo = SweepPlane::build(self.segments.clone());
-        self.intersection_points = algo.calculate();
+        self.intersection_points = algo.calculate().into_keys().collect();
     }
 
     fn reset(&mut self) {
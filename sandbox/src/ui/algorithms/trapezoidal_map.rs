@@ -0,0 +1,126 @@
+use crate::ui::view::{AlgorithmSection, View};
+
+use eframe::{
+    egui::{
+        self,
+        plot::{Legend, Line, Plot, Points},
+    },
+    epaint::Color32,
+};
+use galmetry::{
+    algorithms::{algorithm::Algorithm, trapezoidal_map::RandomizedTrapezoidalMap},
+    geometry::{point::Point, segment::Segment},
+};
+
+pub struct TrapezoidalMapView {
+    segments: Vec<Segment>,
+    algo: RandomizedTrapezoidalMap,
+
+    // true while the algorithm is animated, one step for each frame
+    running: bool,
+}
+
+impl TrapezoidalMapView {
+    /// random segments, skipping the ones crossing those already taken
+    pub fn random(capacity: usize) -> Self {
+        let mut segments: Vec<Segment> = vec![];
+        for _ in 0..capacity * 10 {
+            if segments.len() == capacity {
+                break;
+            }
+            let (a, b) = (Point::random(0.1..0.9), Point::random(0.1..0.9));
+            let mut candidate = segments.clone();
+            candidate.push(Segment::new([a.x, a.y], [b.x, b.y]));
+            if RandomizedTrapezoidalMap::try_build(candidate.clone()).is_ok() {
+                segments = candidate;
+            }
+        }
+
+        Self {
+            algo: RandomizedTrapezoidalMap::build(segments.clone()),
+            segments,
+            running: false,
+        }
+    }
+
+    /// the y of the segment at x, or the default for no segment
+    fn y_at(&self, segment: Option<usize>, x: f64, default: f64) -> f64 {
+        match segment {
+            Some(s) => {
+                let (p, q) = (self.segments[s].start, self.segments[s].end);
+                match p.x == q.x {
+                    true => p.y,
+                    false => p.y + (q.y - p.y) * (x - p.x) / (q.x - p.x),
+                }
+            }
+            None => default,
+        }
+    }
+}
+
+impl View for TrapezoidalMapView {
+    fn ui(&mut self, ui: &mut eframe::egui::Ui) {
+        let plot = Plot::new("Trapezoidal Map")
+            .legend(Legend::default())
+            .view_aspect(1.0)
+            .allow_boxed_zoom(false)
+            .allow_zoom(false)
+            .allow_drag(false);
+
+        plot.show(ui, |plot_ui| {
+            let map = self.algo.map();
+            for t in map.trapezoids() {
+                let t = map.trapezoid(t);
+                for p in [t.leftp, t.rightp].into_iter().flatten() {
+                    plot_ui.line(
+                        Line::new(vec![
+                            [p.x, self.y_at(t.bottom, p.x, 0.0)],
+                            [p.x, self.y_at(t.top, p.x, 1.0)],
+                        ])
+                        .color(Color32::from_rgb(0, 120, 255)),
+                    );
+                }
+            }
+
+            for &s in self.algo.added() {
+                let s = &self.segments[s];
+                plot_ui.line(
+                    Line::new(vec![[s.start.x, s.start.y], [s.end.x, s.end.y]])
+                        .color(Color32::from_rgb(255, 255, 255)),
+                );
+                plot_ui.points(
+                    Points::new(vec![[s.start.x, s.start.y], [s.end.x, s.end.y]])
+                        .radius(3.0)
+                        .color(Color32::from_rgb(255, 80, 80)),
+                );
+            }
+        });
+    }
+}
+
+impl AlgorithmSection for TrapezoidalMapView {
+    fn label(&self) -> &'static str {
+        "Trapezoidal Map"
+    }
+
+    fn calculate(&mut self) {
+        self.algo.reset();
+        self.running = true;
+    }
+
+    fn reset(&mut self) {
+        *self = TrapezoidalMapView::random(15);
+    }
+
+    fn show(&mut self, ctx: &eframe::egui::Context) {
+        if self.running {
+            self.algo.step();
+            self.running = !self.algo.is_done();
+            ctx.request_repaint_after(std::time::Duration::from_millis(400));
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            self.ui(ui);
+        });
+    }
+}
@@ -0,0 +1,157 @@
+use crate::ui::view::{AlgorithmSection, View};
+
+use eframe::{
+    egui::{
+        self,
+        plot::{Legend, Line, Plot, Points},
+    },
+    epaint::Color32,
+};
+use galmetry::{
+    algorithms::{algorithm::Algorithm, voronoi::FortuneVoronoi},
+    geometry::{point::Point, points},
+};
+
+pub struct VoronoiView {
+    sites: points::Points,
+    algo: FortuneVoronoi,
+
+    // true while the algorithm is animated, one step for each frame
+    running: bool,
+}
+
+impl VoronoiView {
+    pub fn random(capacity: usize) -> Self {
+        let sites: Vec<Point> = (0..capacity)
+            .map(|_| Point::random(0.1..0.9))
+            .map(|p| Point::from2d(p.x, p.y))
+            .collect();
+        let sites = points::Points::from(sites);
+
+        Self {
+            algo: FortuneVoronoi::build(sites.clone()),
+            sites,
+            running: false,
+        }
+    }
+
+    /// the arcs of the beach line, sampled inside the unit square
+    fn beach_line(&self) -> Vec<Line> {
+        let l = self.algo.sweep_y();
+        let arcs = self.algo.beach_line();
+        let breakpoints = self.algo.breakpoints();
+
+        let mut lines = vec![];
+        for (i, site) in arcs.iter().enumerate() {
+            let s = self.sites[*site];
+            if s.y == l {
+                continue;
+            }
+
+            let start = if i == 0 { 0.0 } else { breakpoints[i - 1] }.max(0.0);
+            let end = breakpoints.get(i).copied().unwrap_or(1.0).min(1.0);
+            let samples: Vec<[f64; 2]> = (0..=20)
+                .map(|k| start + (end - start) * k as f64 / 20.0)
+                .map(|x| {
+                    [
+                        x,
+                        ((x - s.x).powi(2) + s.y.powi(2) - l * l) / (2.0 * (s.y - l)),
+                    ]
+                })
+                .collect();
+            lines.push(Line::new(samples));
+        }
+        lines
+    }
+}
+
+impl View for VoronoiView {
+    fn ui(&mut self, ui: &mut eframe::egui::Ui) {
+        let plot = Plot::new("Voronoi")
+            .legend(Legend::default())
+            .view_aspect(1.0)
+            .allow_boxed_zoom(false)
+            .allow_zoom(false)
+            .allow_drag(false);
+
+        let diagram = self.algo.diagram();
+        plot.show(ui, |plot_ui| {
+            for edge in diagram.edges.iter() {
+                let d = diagram.direction(edge);
+                let (a, b) = (diagram.sites[edge.sites[0]], diagram.sites[edge.sites[1]]);
+                let middle = Point::from2d((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+                let far = |p: Point, sign: f64| [p.x + sign * 4.0 * d.x, p.y + sign * 4.0 * d.y];
+
+                let points = match (edge.start, edge.end) {
+                    (Some(s), Some(e)) => {
+                        let (s, e) = (diagram.vertices[s], diagram.vertices[e]);
+                        vec![[s.x, s.y], [e.x, e.y]]
+                    }
+                    (Some(s), None) => {
+                        let s = diagram.vertices[s];
+                        vec![[s.x, s.y], far(s, 1.0)]
+                    }
+                    (None, Some(e)) => {
+                        let e = diagram.vertices[e];
+                        vec![far(e, -1.0), [e.x, e.y]]
+                    }
+                    (None, None) => vec![far(middle, -1.0), far(middle, 1.0)],
+                };
+                plot_ui.line(Line::new(points).color(Color32::from_rgb(0, 120, 255)));
+            }
+
+            if !self.algo.is_done() && self.algo.sweep_y().is_finite() {
+                for arc in self.beach_line() {
+                    plot_ui.line(arc.color(Color32::from_rgb(0, 255, 0)));
+                }
+                plot_ui.hline(
+                    egui::plot::HLine::new(self.algo.sweep_y())
+                        .color(Color32::from_rgb(200, 200, 0)),
+                );
+            }
+
+            let circle_events: Vec<[f64; 2]> =
+                self.algo.circle_events().map(|(p, _)| [p.x, p.y]).collect();
+            plot_ui.points(
+                Points::new(circle_events)
+                    .radius(3.0)
+                    .color(Color32::from_rgb(200, 10, 10)),
+            );
+
+            let sites: Vec<[f64; 2]> = self.sites.iter().map(|p| [p.x, p.y]).collect();
+            plot_ui.points(
+                Points::new(sites)
+                    .radius(3.0)
+                    .color(Color32::from_rgb(255, 255, 255))
+                    .shape(eframe::egui::plot::MarkerShape::Square),
+            );
+        });
+    }
+}
+
+impl AlgorithmSection for VoronoiView {
+    fn label(&self) -> &'static str {
+        "Voronoi"
+    }
+
+    fn calculate(&mut self) {
+        self.algo.reset();
+        self.running = true;
+    }
+
+    fn reset(&mut self) {
+        *self = VoronoiView::random(20);
+    }
+
+    fn show(&mut self, ctx: &eframe::egui::Context) {
+        if self.running {
+            self.algo.step();
+            self.running = !self.algo.is_done();
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            self.ui(ui);
+        });
+    }
+}
@@ -4,7 +4,11 @@ use eframe::{
 };
 
 use super::{
-    algorithms::{convex_hull::ConvexHullView, sweep_plane::SweepPlaneView},
+    algorithms::{
+        art_gallery::ArtGalleryView, convex_hull::ConvexHullView, sweep_plane::SweepPlaneView,
+        trapezoidal_map::TrapezoidalMapView, triangulation::TriangulationView,
+        voronoi::VoronoiView,
+    },
     view::AlgorithmSection,
 };
 
@@ -18,6 +22,10 @@ impl Default for GalmetrySandbox {
         let mut algorithms: Vec<Box<dyn AlgorithmSection>> = vec![];
         algorithms.push(Box::new(ConvexHullView::random(50)));
         algorithms.push(Box::new(SweepPlaneView::random(20)));
+        algorithms.push(Box::new(TriangulationView::random(20)));
+        algorithms.push(Box::new(VoronoiView::random(20)));
+        algorithms.push(Box::new(ArtGalleryView::random(20)));
+        algorithms.push(Box::new(TrapezoidalMapView::random(15)));
 
         let current = algorithms[0].label().to_owned();
         Self {
@@ -1,8 +1,15 @@
+use crate::error::Result;
+
 pub trait Algorithm {
     type Output;
 
     fn calculate(&mut self) -> Self::Output;
 
+    /// like `calculate`, but reports invalid results instead of returning them
+    fn try_calculate(&mut self) -> Result<Self::Output> {
+        Ok(self.calculate())
+    }
+
     fn step(&mut self);
 
     fn reset(&mut self);
@@ -0,0 +1,271 @@
+use super::{algorithm::Algorithm, ear_clipping::EarClipping};
+use crate::{
+    error::{GalmetryError, Result},
+    geometry::{
+        mesh::TriangleMesh,
+        points::Points,
+        polygon::Polygon,
+        scalar::{Field, Scalar},
+    },
+};
+
+/// Guards watching over a simple polygon, at most n/3 of them (Fisk's proof
+/// of the art gallery theorem). The polygon is triangulated by ear clipping,
+/// then the vertexes are colored with 3 colors walking the dual tree of the
+/// triangulation: each triangle shares an edge with one already colored, so
+/// its last vertex takes the color left. Every triangle has a vertex of each
+/// color, and the vertexes of the smallest color class see the whole polygon.
+///
+/// Vertexes are referred by their index in the input. A step colors one
+/// triangle
+pub struct ArtGallery<T = f64> {
+    // the polygon given by the user
+    polygon: Polygon<T>,
+
+    // the triangulation and its dual tree
+    mesh: TriangleMesh,
+
+    // the color of each vertex, None until a triangle of it is colored or for
+    // a collinear vertex left out by the triangulation
+    colors: Vec<Option<usize>>,
+
+    // the colored triangles whose neighbors are still to color
+    stack: Vec<usize>,
+}
+
+impl<T: Scalar> Algorithm for ArtGallery<T> {
+    type Output = Vec<usize>;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while !self.is_done() {
+            self.step();
+        }
+
+        self.guards()
+    }
+
+    /// fails if the polygon has no area to guard
+    fn try_calculate(&mut self) -> Result<Self::Output> {
+        let guards = self.calculate();
+        match self.mesh.is_empty() {
+            true => Err(GalmetryError::DegenerateInput("the polygon has no area")),
+            false => Ok(guards),
+        }
+    }
+
+    fn step(&mut self) {
+        let t = match self.stack.pop() {
+            Some(t) => t,
+            None => return,
+        };
+
+        for i in 0..3 {
+            let neighbor = match self.mesh.neighbor(t, i) {
+                Some(neighbor) => neighbor,
+                None => continue,
+            };
+            let triangle = self.mesh.triangles()[neighbor];
+            if let Some(&v) = triangle.iter().find(|&&v| self.colors[v].is_none()) {
+                let used: Vec<usize> = triangle.iter().filter_map(|&u| self.colors[u]).collect();
+                self.colors[v] = (0..3).find(|c| !used.contains(c));
+                self.stack.push(neighbor);
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.colors = vec![None; self.polygon.len()];
+        self.stack = vec![];
+
+        let triangles = EarClipping::build(self.polygon.clone(), vec![]).calculate();
+        self.mesh = TriangleMesh::new(triangles);
+        if let Some(first) = self.mesh.triangles().first() {
+            for (color, &v) in first.iter().enumerate() {
+                self.colors[v] = Some(color);
+            }
+            self.stack.push(0);
+        }
+    }
+}
+
+impl<T: Scalar> ArtGallery<T> {
+    pub fn build(vertices: Points<T>) -> Self {
+        let mut algo = Self {
+            polygon: Polygon::new(vertices),
+            mesh: TriangleMesh::new(vec![]),
+            colors: vec![],
+            stack: vec![],
+        };
+        algo.reset();
+        algo
+    }
+
+    /// true when every triangle is colored
+    pub fn is_done(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// the triangles of the polygon, each one counterclockwise
+    pub fn triangles(&self) -> &Vec<[usize; 3]> {
+        self.mesh.triangles()
+    }
+
+    /// the color of each vertex, 0, 1 or 2, None if not colored
+    pub fn colors(&self) -> &Vec<Option<usize>> {
+        &self.colors
+    }
+
+    /// the vertexes of the smallest color class found so far, in input order
+    pub fn guards(&self) -> Vec<usize> {
+        let class = |c: usize| -> Vec<usize> {
+            (0..self.colors.len())
+                .filter(|&v| self.colors[v] == Some(c))
+                .collect()
+        };
+        (0..3)
+            .map(class)
+            .min_by_key(|guards| guards.len())
+            .expect("there are three colors")
+    }
+}
+
+impl<T: Field> ArtGallery<T> {
+    /// like `build`, but rejects polygons with NaN or infinite coordinates,
+    /// with less than 3 vertexes or that are not simple
+    pub fn try_build(vertices: Points<T>) -> Result<Self> {
+        let polygon = Polygon::try_new(vertices)?;
+        if !polygon.is_simple() {
+            return Err(GalmetryError::DegenerateInput("the polygon is not simple"));
+        }
+        Ok(Self::build(polygon.vertices().clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArtGallery;
+    use crate::{
+        algorithms::algorithm::Algorithm,
+        error::GalmetryError,
+        geometry::{point::Point, points::Points},
+    };
+    use rand::Rng;
+
+    /// each triangle has a vertex of each color, so a guard in each one
+    fn assert_guarded(algo: &ArtGallery, guards: &[usize]) {
+        for t in algo.triangles() {
+            let mut colors: Vec<usize> = t.iter().map(|&v| algo.colors()[v].unwrap()).collect();
+            colors.sort();
+            assert_eq!(colors, vec![0, 1, 2]);
+            assert!(t.iter().any(|v| guards.contains(v)));
+        }
+        assert!(guards.len() <= algo.colors().len() / 3);
+    }
+
+    /// a random star-shaped polygon, so that it is always simple
+    fn random_star(n: usize) -> Points {
+        let mut rng = rand::thread_rng();
+        let mut angles: Vec<f64> = (0..n)
+            .map(|_| rng.gen_range(0.0..std::f64::consts::TAU))
+            .collect();
+        angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let vertices: Vec<Point> = angles
+            .iter()
+            .map(|angle| {
+                let radius = rng.gen_range(1.0..10.0);
+                Point::from2d(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+        Points::from(vertices)
+    }
+
+    #[test]
+    fn comb_needs_a_guard_per_tooth() {
+        // the comb of Chvátal: 4 teeth on a base, 14 vertexes, 4 guards
+        let mut vertices = vec![];
+        for i in 0..4 {
+            let x = 3.0 * i as f64;
+            vertices.extend([[x, 0.0], [x + 1.0, 3.0], [x + 2.0, 0.0]]);
+        }
+        vertices.extend([[11.0, -1.0], [0.0, -1.0]]);
+        let vertices: Points = vertices.into();
+
+        let mut algo = ArtGallery::build(vertices);
+        let guards = algo.calculate();
+        assert_guarded(&algo, &guards);
+        assert_eq!(guards.len(), 4);
+    }
+
+    #[test]
+    fn convex_polygon() {
+        let square: Points = vec![[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0]].into();
+        let mut algo = ArtGallery::build(square);
+        let guards = algo.calculate();
+        assert_guarded(&algo, &guards);
+        assert_eq!(guards.len(), 1);
+    }
+
+    #[test]
+    fn clockwise_and_collinear_vertexes() {
+        let polygon: Points = vec![
+            [0.0, 0.0],
+            [0.0, 4.0],
+            [2.0, 2.0],
+            [4.0, 4.0],
+            [4.0, 0.0],
+            [2.0, 0.0],
+        ]
+        .into();
+        let mut algo = ArtGallery::build(polygon);
+        let guards = algo.calculate();
+        assert!(!guards.is_empty());
+        assert!(guards.len() <= 2);
+        for t in algo.triangles() {
+            assert!(t.iter().any(|v| guards.contains(v)));
+        }
+    }
+
+    #[test]
+    fn random_polygons() {
+        for _ in 0..20 {
+            let mut algo = ArtGallery::build(random_star(30));
+            let guards = algo.calculate();
+            assert_eq!(algo.triangles().len(), 28);
+            assert_guarded(&algo, &guards);
+        }
+    }
+
+    #[test]
+    fn art_gallery_steps() {
+        let mut algo = ArtGallery::build(random_star(10));
+        let colored = |algo: &ArtGallery| algo.colors().iter().flatten().count();
+        assert_eq!(colored(&algo), 3);
+
+        let mut steps = 0;
+        while !algo.is_done() {
+            algo.step();
+            steps += 1;
+        }
+        assert_eq!(steps, 8);
+        assert_eq!(colored(&algo), 10);
+        assert_eq!(algo.guards(), algo.calculate());
+    }
+
+    #[test]
+    fn try_art_gallery() {
+        let bowtie: Points = vec![[0.0, 0.0], [4.0, 4.0], [4.0, 0.0], [0.0, 4.0]].into();
+        assert_eq!(
+            ArtGallery::try_build(bowtie).err(),
+            Some(GalmetryError::DegenerateInput("the polygon is not simple"))
+        );
+
+        let flat: Points = vec![[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]].into();
+        let result = ArtGallery::build(flat).try_calculate();
+        assert_eq!(
+            result,
+            Err(GalmetryError::DegenerateInput("the polygon has no area"))
+        );
+    }
+}
@@ -0,0 +1,735 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+};
+
+use super::{algorithm::Algorithm, delaunay::DelaunayTriangulation, sweep_plane::SweepPlane};
+use crate::{
+    error::{check_finite, GalmetryError, Result},
+    geometry::{
+        mesh::TriangleMesh,
+        point::Point,
+        points::Points,
+        predicates::Orientation,
+        scalar::{Field, Scalar},
+        segment::Segment,
+    },
+};
+
+/// A triangulation where some edges are forced, and the triangles enclosed by
+/// them are told apart from the others
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstrainedMesh<T = f64> {
+    /// the input points followed by the endpoints and the intersections of the
+    /// constraints that were not input points
+    pub vertices: Points<T>,
+
+    pub mesh: TriangleMesh,
+
+    /// the constrained edges, smaller index first
+    pub constraints: Vec<(usize, usize)>,
+
+    /// for each triangle, true if reaching it from outside the hull crosses an
+    /// odd number of constraints: it is inside a closed loop and not in a hole
+    pub inside: Vec<bool>,
+}
+
+impl<T: Scalar> ConstrainedMesh<T> {
+    /// the triangles inside the constraint loops, holes and outside removed
+    pub fn interior(&self) -> TriangleMesh {
+        TriangleMesh::new(
+            self.mesh
+                .triangles()
+                .iter()
+                .zip(self.inside.iter())
+                .filter(|(_, &inside)| inside)
+                .map(|(t, _)| *t)
+                .collect(),
+        )
+    }
+}
+
+/// Constrained Delaunay triangulation: the Delaunay triangulation of the
+/// points and of the constraint endpoints, where the constraints are then
+/// forced by flipping the edges crossing them (Sloan). The flips restoring the
+/// empty circle property never remove a constraint, so a triangle can have
+/// points in its circle only behind a constraint.
+///
+/// Crossing constraints are split in their intersection, found with
+/// `SweepPlane`, and a constraint passing through a vertex is split there.
+/// A step forces one piece of a constraint
+pub struct ConstrainedDelaunay<T = f64> {
+    // the input, kept to replay the algorithm
+    points: Points<T>,
+    segments: Vec<Segment<T>>,
+
+    // all the vertexes and, for each one, the first vertex in the same place
+    vertices: Points<T>,
+    canonical: Vec<usize>,
+
+    // the triangles, counterclockwise, the triangle of each directed edge and
+    // a triangle of each vertex
+    triangles: Vec<[usize; 3]>,
+    edges: HashMap<(usize, usize), usize>,
+    incident: Vec<Option<usize>>,
+
+    // the constrained edges, smaller index first
+    constrained: BTreeSet<(usize, usize)>,
+
+    // the pieces of constraints still to force, the next one last
+    pending: Vec<(usize, usize)>,
+
+    // the pieces that could not be forced as they cross a constrained edge,
+    // as the rounding of an intersection could leave them
+    unforced: usize,
+}
+
+/// The way from a vertex to another through the triangulation
+enum Walk {
+    /// the edges crossed, in order
+    Crossing(Vec<(usize, usize)>),
+
+    /// a vertex lies on the way
+    Through(usize),
+
+    /// the way was lost, as inconsistent rounded predicates can do
+    Lost,
+}
+
+impl<T: Field> Algorithm for ConstrainedDelaunay<T> {
+    type Output = ConstrainedMesh<T>;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while !self.is_done() {
+            self.step();
+        }
+
+        self.result()
+    }
+
+    /// fails if no triangle exists, or if a constraint crosses another one
+    /// without an intersection vertex, as rounding can do
+    fn try_calculate(&mut self) -> Result<Self::Output> {
+        let result = self.calculate();
+        if result.mesh.is_empty() {
+            return Err(GalmetryError::DegenerateInput(
+                "all the vertexes are collinear",
+            ));
+        }
+        match self.unforced {
+            0 => Ok(result),
+            _ => Err(GalmetryError::NumericFailure(
+                "a constraint crosses another one without a vertex",
+            )),
+        }
+    }
+
+    fn step(&mut self) {
+        if let Some((a, b)) = self.pending.pop() {
+            self.force(a, b);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.vertices = self.points.clone();
+        self.canonical = vec![];
+        self.constrained = BTreeSet::new();
+        self.unforced = 0;
+
+        let mut places: BTreeMap<Point<T>, usize> = BTreeMap::new();
+        for (i, p) in self.points.iter().enumerate() {
+            self.canonical.push(*places.entry(*p).or_insert(i));
+        }
+
+        // each constraint is split in its endpoints and intersections
+        let segments: Vec<Segment<T>> = self
+            .segments
+            .iter()
+            .filter(|seg| seg.start != seg.end)
+            .cloned()
+            .collect();
+        let mut splits: Vec<Vec<Point<T>>> = segments
+            .iter()
+            .map(|seg| vec![seg.start, seg.end])
+            .collect();
+        let intersections = SweepPlane::build(segments.clone()).calculate();
+        for (p, intersection) in intersections.iter() {
+            for i in intersection.segments() {
+                splits[i].push(*p);
+            }
+        }
+
+        let mut pieces = vec![];
+        for (seg, mut split) in segments.iter().zip(splits) {
+            let direction = seg.end - seg.start;
+            let along = |p: &Point<T>| {
+                (p.x - seg.start.x) * direction.x + (p.y - seg.start.y) * direction.y
+            };
+            split.sort_by(|p, q| along(p).partial_cmp(&along(q)).unwrap_or(Ordering::Equal));
+
+            let ids: Vec<usize> = split.iter().map(|p| self.vertex(&mut places, p)).collect();
+            for pair in ids.windows(2) {
+                if pair[0] != pair[1] {
+                    pieces.push((pair[0], pair[1]));
+                }
+            }
+        }
+        pieces.reverse();
+        self.pending = pieces;
+
+        self.triangulate();
+    }
+}
+
+impl<T: Field> ConstrainedDelaunay<T> {
+    pub fn build(points: Points<T>, segments: Vec<Segment<T>>) -> Self {
+        let mut algo = Self {
+            points,
+            segments,
+            vertices: Points::new(),
+            canonical: vec![],
+            triangles: vec![],
+            edges: HashMap::new(),
+            incident: vec![],
+            constrained: BTreeSet::new(),
+            pending: vec![],
+            unforced: 0,
+        };
+        algo.reset();
+        algo
+    }
+
+    /// like `build`, but rejects NaN and infinite coordinates. The index of an
+    /// invalid segment comes after the indexes of the points
+    pub fn try_build(points: Points<T>, segments: Vec<Segment<T>>) -> Result<Self> {
+        check_finite(
+            points
+                .iter()
+                .map(|p| p.is_finite())
+                .chain(segments.iter().map(|seg| seg.is_finite())),
+        )?;
+        Ok(Self::build(points, segments))
+    }
+
+    /// true when all the constraints are forced
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// all the vertexes: the input points, then the new ones of the constraints
+    pub fn vertices(&self) -> &Points<T> {
+        &self.vertices
+    }
+
+    /// the current triangles, each one counterclockwise
+    pub fn triangles(&self) -> &Vec<[usize; 3]> {
+        &self.triangles
+    }
+
+    /// the constraints forced so far, smaller index first
+    pub fn constrained(&self) -> &BTreeSet<(usize, usize)> {
+        &self.constrained
+    }
+
+    /// the current triangulation, with the triangles inside the constraint
+    /// loops marked
+    pub fn result(&self) -> ConstrainedMesh<T> {
+        let mesh = TriangleMesh::new(self.triangles.clone());
+        let inside = self.inside(&mesh);
+        ConstrainedMesh {
+            vertices: self.vertices.clone(),
+            mesh,
+            constraints: self.constrained.iter().copied().collect(),
+            inside,
+        }
+    }
+
+    /// the index of the vertex in p, added if new
+    fn vertex(&mut self, places: &mut BTreeMap<Point<T>, usize>, p: &Point<T>) -> usize {
+        if let Some(&i) = places.get(p) {
+            return i;
+        }
+
+        let i = self.vertices.len();
+        self.vertices.push(*p);
+        self.canonical.push(i);
+        places.insert(*p, i);
+        i
+    }
+
+    /// the Delaunay triangulation of the distinct vertexes
+    fn triangulate(&mut self) {
+        let unique: Vec<usize> = (0..self.vertices.len())
+            .filter(|&i| self.canonical[i] == i)
+            .collect();
+        let points = Points::from(unique.iter().map(|&i| self.vertices[i]).collect::<Vec<_>>());
+        let mesh = DelaunayTriangulation::build(points).calculate();
+
+        self.triangles = vec![];
+        self.edges = HashMap::new();
+        self.incident = vec![None; self.vertices.len()];
+        for t in mesh.triangles() {
+            self.add(t.map(|v| unique[v]));
+        }
+    }
+
+    fn add(&mut self, triangle: [usize; 3]) {
+        let t = self.triangles.len();
+        self.triangles.push(triangle);
+        self.link(t);
+    }
+
+    fn link(&mut self, t: usize) {
+        let [a, b, c] = self.triangles[t];
+        for edge in [(a, b), (b, c), (c, a)] {
+            self.edges.insert(edge, t);
+            self.incident[edge.0] = Some(t);
+        }
+    }
+
+    fn unlink(&mut self, t: usize) {
+        let [a, b, c] = self.triangles[t];
+        for edge in [(a, b), (b, c), (c, a)] {
+            self.edges.remove(&edge);
+        }
+    }
+
+    fn is_constrained(&self, a: usize, b: usize) -> bool {
+        self.constrained.contains(&(a.min(b), a.max(b)))
+    }
+
+    fn orientation(&self, a: usize, b: usize, c: usize) -> Orientation {
+        T::orientation(&self.vertices[a], &self.vertices[b], &self.vertices[c])
+    }
+
+    /// true if the segments a-b and c-d cross in a point inside both
+    fn crosses(&self, (a, b): (usize, usize), (c, d): (usize, usize)) -> bool {
+        let opposite = |o1: Orientation, o2: Orientation| {
+            matches!(
+                (o1, o2),
+                (Orientation::Left, Orientation::Right) | (Orientation::Right, Orientation::Left)
+            )
+        };
+        opposite(self.orientation(a, b, c), self.orientation(a, b, d))
+            && opposite(self.orientation(c, d, a), self.orientation(c, d, b))
+    }
+
+    /// the vertex of the triangle holding the directed edge a-b, other than them
+    fn apex(&self, a: usize, b: usize) -> Option<usize> {
+        let t = *self.edges.get(&(a, b))?;
+        self.triangles[t]
+            .iter()
+            .copied()
+            .find(|&v| v != a && v != b)
+    }
+
+    /// replaces the edge a-b with the one joining the opposite vertexes, which
+    /// is returned
+    fn flip(&mut self, a: usize, b: usize) -> (usize, usize) {
+        let (t, u) = (self.edges[&(a, b)], self.edges[&(b, a)]);
+        let (w, x) = (self.apex(a, b).unwrap(), self.apex(b, a).unwrap());
+
+        // the quadrilateral is a, x, b, w counterclockwise
+        self.unlink(t);
+        self.unlink(u);
+        self.triangles[t] = [w, a, x];
+        self.triangles[u] = [x, b, w];
+        self.link(t);
+        self.link(u);
+        (w, x)
+    }
+
+    /// makes a-b an edge of the triangulation
+    fn force(&mut self, a: usize, b: usize) {
+        if self.edges.contains_key(&(a, b)) || self.edges.contains_key(&(b, a)) {
+            self.constrained.insert((a.min(b), a.max(b)));
+            return;
+        }
+
+        let mut crossing: VecDeque<(usize, usize)> = match self.walk(a, b) {
+            Walk::Crossing(edges) => edges.into(),
+            // a vertex on the constraint splits it
+            Walk::Through(c) => {
+                self.pending.push((c, b));
+                self.pending.push((a, c));
+                return;
+            }
+            Walk::Lost => {
+                self.unforced += 1;
+                return;
+            }
+        };
+        if crossing.iter().any(|&(u, v)| self.is_constrained(u, v)) {
+            self.unforced += 1;
+            return;
+        }
+
+        // flip the crossing edges whose quadrilateral is convex, until none is left
+        let mut created = vec![];
+        let mut attempts = 0;
+        while let Some((u, v)) = crossing.pop_front() {
+            let (w, x) = match (self.apex(u, v), self.apex(v, u)) {
+                (Some(w), Some(x)) => (w, x),
+                _ => continue,
+            };
+            if !self.crosses((u, v), (w, x)) {
+                crossing.push_back((u, v));
+                attempts += 1;
+                // with exact predicates a convex quadrilateral always exists
+                if attempts > crossing.len() {
+                    self.unforced += 1;
+                    return;
+                }
+                continue;
+            }
+
+            attempts = 0;
+            let edge = self.flip(u, v);
+            match self.crosses((a, b), edge) {
+                true => crossing.push_back(edge),
+                false => created.push(edge),
+            }
+        }
+
+        self.constrained.insert((a.min(b), a.max(b)));
+        self.legalize(created);
+    }
+
+    /// The edges crossed by a-b, from a to b, found by walking through the
+    /// triangles it crosses (Sloan). Only the vertexes met on the way are
+    /// checked for lying on a-b
+    fn walk(&self, a: usize, b: usize) -> Walk {
+        // the triangle around a whose angle holds the direction towards b
+        let mut edge = None;
+        for [p, q] in self.star(a) {
+            if let Some(&c) = [p, q].iter().find(|&&c| self.is_between(a, b, c)) {
+                return Walk::Through(c);
+            }
+            if self.orientation(a, p, b) == Orientation::Left
+                && self.orientation(a, q, b) == Orientation::Right
+            {
+                edge = Some((p, q));
+            }
+        }
+
+        // each edge crossed has its first vertex on the right of a-b
+        let mut crossing = vec![];
+        while let Some((u, v)) = edge {
+            crossing.push((u, v));
+            let w = match self.apex(v, u) {
+                Some(w) => w,
+                None => return Walk::Lost,
+            };
+            if w == b {
+                return Walk::Crossing(crossing);
+            }
+            edge = match self.orientation(a, b, w) {
+                Orientation::Left => Some((u, w)),
+                Orientation::Right => Some((w, v)),
+                Orientation::Collinear if self.is_between(a, b, w) => return Walk::Through(w),
+                Orientation::Collinear => None,
+            };
+            if crossing.len() > self.edges.len() {
+                break;
+            }
+        }
+        Walk::Lost
+    }
+
+    /// the other vertexes of each triangle around a, counterclockwise in it
+    fn star(&self, a: usize) -> Vec<[usize; 2]> {
+        let opposite = |t: usize| {
+            let triangle = self.triangles[t];
+            let i = triangle.iter().position(|&v| v == a).unwrap_or(0);
+            [triangle[(i + 1) % 3], triangle[(i + 2) % 3]]
+        };
+        let start = match self.incident.get(a).copied().flatten() {
+            Some(t) => t,
+            None => return vec![],
+        };
+
+        // counterclockwise from the start, then clockwise if a is on the hull
+        let mut star = vec![opposite(start)];
+        let mut t = self.edges.get(&(a, opposite(start)[1])).copied();
+        while let Some(u) = t.filter(|&u| u != start && star.len() < self.triangles.len()) {
+            star.push(opposite(u));
+            t = self.edges.get(&(a, opposite(u)[1])).copied();
+        }
+        if t.is_none() {
+            let mut t = self.edges.get(&(opposite(start)[0], a)).copied();
+            while let Some(u) = t.filter(|_| star.len() < self.triangles.len()) {
+                star.push(opposite(u));
+                t = self.edges.get(&(opposite(u)[0], a)).copied();
+            }
+        }
+        star
+    }
+
+    /// true if c lies on a-b, strictly between a and b
+    fn is_between(&self, a: usize, b: usize, c: usize) -> bool {
+        let (p, q, v) = (&self.vertices[a], &self.vertices[b], &self.vertices[c]);
+        self.orientation(a, b, c) == Orientation::Collinear
+            && (v.x - p.x) * (q.x - v.x) + (v.y - p.y) * (q.y - v.y) > T::zero()
+    }
+
+    /// Lawson flips from the given edges, the constraints are never flipped
+    fn legalize(&mut self, mut stack: Vec<(usize, usize)>) {
+        while let Some((a, b)) = stack.pop() {
+            if self.is_constrained(a, b) {
+                continue;
+            }
+            let (w, x) = match (self.apex(a, b), self.apex(b, a)) {
+                (Some(w), Some(x)) => (w, x),
+                _ => continue,
+            };
+
+            let [pa, pb, pw, px] = [a, b, w, x].map(|v| &self.vertices[v]);
+            if T::incircle(pa, pb, pw, px) == Ordering::Greater {
+                self.flip(a, b);
+                stack.extend([(a, x), (x, b), (b, w), (w, a)]);
+            }
+        }
+    }
+
+    /// the number of constraints crossed to reach each triangle from outside
+    /// the hull is odd inside the constraint loops
+    fn inside(&self, mesh: &TriangleMesh) -> Vec<bool> {
+        let mut depth: Vec<Option<usize>> = vec![None; mesh.len()];
+        let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+
+        let crossing = |t: usize, i: usize| {
+            let triangle = mesh.triangles()[t];
+            self.is_constrained(triangle[(i + 1) % 3], triangle[(i + 2) % 3]) as usize
+        };
+
+        // 0-1 breadth first search from the triangles on the hull: crossing a
+        // constraint costs one
+        for t in 0..mesh.len() {
+            for i in (0..3).filter(|&i| mesh.neighbor(t, i).is_none()) {
+                match crossing(t, i) {
+                    0 => queue.push_front((t, 0)),
+                    _ => queue.push_back((t, 1)),
+                }
+            }
+        }
+
+        while let Some((t, d)) = queue.pop_front() {
+            if depth[t].is_some() {
+                continue;
+            }
+            depth[t] = Some(d);
+
+            for i in 0..3 {
+                if let Some(u) = mesh.neighbor(t, i) {
+                    match crossing(t, i) {
+                        0 => queue.push_front((u, d)),
+                        _ => queue.push_back((u, d + 1)),
+                    }
+                }
+            }
+        }
+
+        depth.iter().map(|d| d.unwrap_or(0) % 2 == 1).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::{ConstrainedDelaunay, ConstrainedMesh};
+    use crate::{
+        algorithms::algorithm::Algorithm,
+        error::GalmetryError,
+        geometry::{
+            point::Point, points::Points, polygon::Polygon, rational::Rational, scalar::Field,
+            segment::Segment,
+        },
+    };
+    use rand::Rng;
+
+    /// the constraints are edges and every other edge is locally Delaunay
+    fn assert_constrained_delaunay<T: Field>(result: &ConstrainedMesh<T>) {
+        let edges = result.mesh.edges();
+        for constraint in result.constraints.iter() {
+            assert!(edges.contains(constraint), "{:?} is missing", constraint);
+        }
+
+        let triangles = result.mesh.triangles();
+        for (t, triangle) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                let (a, b) = (triangle[(i + 1) % 3], triangle[(i + 2) % 3]);
+                let u = match result.mesh.neighbor(t, i) {
+                    Some(u) => u,
+                    None => continue,
+                };
+                if result.constraints.contains(&(a.min(b), a.max(b))) {
+                    continue;
+                }
+
+                let x = *triangles[u].iter().find(|&&v| v != a && v != b).unwrap();
+                let [pa, pb, pc, px] = [a, b, triangle[i], x].map(|v| &result.vertices[v]);
+                assert_ne!(T::incircle(pa, pb, pc, px), Ordering::Greater);
+            }
+        }
+    }
+
+    fn area(result: &ConstrainedMesh, triangles: &[[usize; 3]]) -> f64 {
+        triangles
+            .iter()
+            .map(|t| Polygon::new(Points::from(t.map(|v| result.vertices[v]).to_vec())).area())
+            .sum()
+    }
+
+    fn square(min: f64, max: f64) -> Vec<Segment> {
+        let corners = [[min, min], [max, min], [max, max], [min, max]];
+        (0..4)
+            .map(|i| Segment::new(corners[i], corners[(i + 1) % 4]))
+            .collect()
+    }
+
+    #[test]
+    fn constraint_replaces_delaunay_edge() {
+        let points: Points = vec![[0.0, 0.0], [2.0, -1.0], [4.0, 0.0], [2.0, 1.0]].into();
+        let segments = vec![Segment::new([0.0, 0.0], [4.0, 0.0])];
+        let result = ConstrainedDelaunay::build(points, segments).calculate();
+
+        assert_eq!(result.constraints, vec![(0, 2)]);
+        assert!(!result.mesh.edges().contains(&(1, 3)));
+        assert_constrained_delaunay(&result);
+    }
+
+    #[test]
+    fn crossing_constraints_are_split() {
+        let points: Points = vec![[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0]].into();
+        let segments = vec![
+            Segment::new([0.0, 0.0], [4.0, 4.0]),
+            Segment::new([0.0, 4.0], [4.0, 0.0]),
+        ];
+        let result = ConstrainedDelaunay::build(points, segments).calculate();
+
+        assert_eq!(result.vertices.len(), 5);
+        assert_eq!(result.vertices[4], Point::from2d(2.0, 2.0));
+        assert_eq!(result.constraints, vec![(0, 4), (1, 4), (2, 4), (3, 4)]);
+        assert_eq!(result.mesh.len(), 4);
+        assert_constrained_delaunay(&result);
+    }
+
+    #[test]
+    fn constraint_through_a_vertex() {
+        let points: Points = vec![[0.0, 1.0], [2.0, 0.0], [4.0, 1.0], [2.0, 3.0]].into();
+        let segments = vec![Segment::new([0.0, 0.0], [4.0, 0.0])];
+        let result = ConstrainedDelaunay::build(points, segments).calculate();
+
+        // the endpoints are new vertexes and the point (2, 0) splits the constraint
+        assert_eq!(result.vertices.len(), 6);
+        assert_eq!(result.constraints, vec![(1, 4), (1, 5)]);
+        assert_constrained_delaunay(&result);
+    }
+
+    #[test]
+    fn loops_mark_the_inside() {
+        let mut segments = square(0.0, 10.0);
+        segments.extend(square(4.0, 6.0));
+        let mut points = Points::random(100, -2.0..12.0);
+        for i in 0..points.len() {
+            points[i].z = 0.0;
+        }
+
+        let result = ConstrainedDelaunay::build(points, segments).calculate();
+        assert_constrained_delaunay(&result);
+        assert!((area(&result, result.interior().triangles()) - 96.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn random_constraints() {
+        for _ in 0..20 {
+            let points = Points::random(40, 0.0..10.0);
+            let segments: Vec<Segment> = (0..8)
+                .map(|_| {
+                    let (a, b) = (Point::random(0.0..10.0), Point::random(0.0..10.0));
+                    Segment::new([a.x, a.y], [b.x, b.y])
+                })
+                .collect();
+
+            let mut algo = ConstrainedDelaunay::build(points, segments);
+            while !algo.is_done() {
+                algo.step();
+            }
+            assert_constrained_delaunay(&algo.result());
+
+            // each constraint is made of one piece at least
+            assert!(algo.constrained().len() >= 8);
+        }
+    }
+
+    #[test]
+    fn grid_constraints() {
+        // many vertexes lie on the constraints and on the hull
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let mut point = || {
+                let mut coordinate = || Rational::from(rng.gen_range(0..6));
+                Point::from2d(coordinate(), coordinate())
+            };
+            let points = Points::from((0..15).map(|_| point()).collect::<Vec<_>>());
+            let segments: Vec<Segment<Rational>> =
+                (0..6).map(|_| Segment::new(point(), point())).collect();
+
+            let result = ConstrainedDelaunay::build(points, segments.clone())
+                .try_calculate()
+                .unwrap();
+            assert_constrained_delaunay(&result);
+
+            // the pieces of each constraint cover it
+            for segment in segments.iter().filter(|s| s.start != s.end) {
+                let length: f64 = result
+                    .constraints
+                    .iter()
+                    .map(|&(a, b)| (result.vertices[a], result.vertices[b]))
+                    .filter(|(p, q)| segment.contains(p) && segment.contains(q))
+                    .map(|(p, q)| p.distance_from(&q))
+                    .sum();
+                assert!((length - segment.start.distance_from(&segment.end)).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn rational_constraints() {
+        let r = |x: i64, y: i64| Point::from2d(Rational::from(x), Rational::from(y));
+        let points = Points::from(vec![r(0, 0), r(3, 0), r(3, 3), r(0, 3)]);
+        let segments = vec![
+            Segment::new(r(0, 0), r(3, 2)),
+            Segment::new(r(0, 2), r(3, 0)),
+        ];
+        let result = ConstrainedDelaunay::build(points, segments).calculate();
+
+        let crossing_x = Rational::new(3, 2);
+        assert!(result
+            .vertices
+            .iter()
+            .any(|p| *p == Point::from2d(crossing_x, Rational::from(1))));
+        assert_eq!(result.constraints.len(), 4);
+        assert_constrained_delaunay(&result);
+    }
+
+    #[test]
+    fn try_constrained_delaunay() {
+        let points: Points = vec![[0.0, 0.0], [1.0, 1.0]].into();
+        let segments = vec![Segment::new([2.0, 2.0], [3.0, 3.0])];
+        assert_eq!(
+            ConstrainedDelaunay::build(points.clone(), segments).try_calculate(),
+            Err(GalmetryError::DegenerateInput(
+                "all the vertexes are collinear"
+            ))
+        );
+
+        let segments = vec![Segment::new([0.0, 0.0], [f64::NAN, 1.0])];
+        assert!(matches!(
+            ConstrainedDelaunay::try_build(points, segments),
+            Err(GalmetryError::NonFiniteCoordinate { index: 2 })
+        ));
+    }
+}
@@ -1,59 +1,191 @@
-use crate::geometry::{point::Point, points::Points};
+use crate::{
+    error::{check_finite, GalmetryError, Result},
+    geometry::{point::Point, points::Points, scalar::Scalar},
+};
 
 use super::algorithm::Algorithm;
 
-pub struct MonotoneConvexHull {
-    points: Points,
+/// The chain the algorithm is currently building
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HullPhase {
+    Upper,
+    Lower,
+    Done,
 }
 
-impl Algorithm for MonotoneConvexHull {
-    type Output = Points;
+/// Andrew's monotone chain, for any coordinate type with an exact orientation test
+pub struct MonotoneConvexHull<T = f64> {
+    // the points given by the user, kept to replay the algorithm
+    input: Points<T>,
 
-    fn calculate(&mut self) -> Self::Output {
-        let mut upper_l = Points::new();
-        let mut lower_l = Points::new();
+    // the input points sorted in lexicographic order
+    points: Points<T>,
 
-        self.points.lexicograph_sort();
+    // upper chain built so far (left to right)
+    upper: Points<T>,
 
-        upper_l.push(self.points[0]);
-        upper_l.push(self.points[1]);
-        for i in 0..self.points.len() {
-            upper_l.push(self.points[i]);
-            upper_l = self.assure_make_turn_right(upper_l);
-        }
-        upper_l.pop();
+    // lower chain built so far (right to left)
+    lower: Points<T>,
+
+    // the last point pushed on the current chain
+    candidate: Option<Point<T>>,
+
+    // the points removed from the chain during the last step
+    discarded: Points<T>,
+
+    phase: HullPhase,
 
-        lower_l.push(self.points[self.points.len() - 1]);
-        lower_l.push(self.points[self.points.len() - 2]);
-        for i in (0..(self.points.len() - 2)).rev() {
-            lower_l.push(self.points[i]);
-            lower_l = self.assure_make_turn_right(lower_l);
+    // index of the next point to process
+    next: usize,
+}
+
+impl<T: Scalar> Algorithm for MonotoneConvexHull<T> {
+    type Output = Points<T>;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while self.phase != HullPhase::Done {
+            self.step();
         }
-        lower_l.pop();
 
-        let mut convex_hull = Points::new();
-        convex_hull.append(&mut upper_l);
-        convex_hull.append(&mut lower_l);
+        self.convex_hull()
+    }
 
-        convex_hull
+    /// fails if all the points are collinear, as the hull has no area
+    fn try_calculate(&mut self) -> Result<Self::Output> {
+        let convex_hull = self.calculate();
+        match convex_hull.len() {
+            0..=2 => Err(GalmetryError::DegenerateInput(
+                "all the points are collinear",
+            )),
+            _ => Ok(convex_hull),
+        }
     }
 
     fn step(&mut self) {
-        todo!();
+        self.discarded = Points::new();
+
+        match self.phase {
+            HullPhase::Upper => {
+                let p = self.points[self.next];
+                self.candidate = Some(p);
+                self.upper.push(p);
+                Self::assure_make_turn_right(&mut self.upper, &mut self.discarded);
+
+                self.next += 1;
+                if self.next == self.points.len() {
+                    self.phase = HullPhase::Lower;
+                }
+            }
+            HullPhase::Lower => {
+                self.next -= 1;
+                let p = self.points[self.next];
+                self.candidate = Some(p);
+                self.lower.push(p);
+                Self::assure_make_turn_right(&mut self.lower, &mut self.discarded);
+
+                if self.next == 0 {
+                    self.phase = HullPhase::Done;
+                    self.candidate = None;
+                }
+            }
+            HullPhase::Done => {}
+        }
     }
 
     fn reset(&mut self) {
-        todo!()
+        self.points = self.input.clone();
+        self.points.lexicograph_sort();
+
+        self.upper = Points::new();
+        self.lower = Points::new();
+        self.candidate = None;
+        self.discarded = Points::new();
+        self.next = 0;
+        self.phase = match self.points.len() {
+            0 => HullPhase::Done,
+            _ => HullPhase::Upper,
+        };
     }
 }
 
-impl MonotoneConvexHull {
-    pub fn build(points: Points) -> Self {
-        Self { points }
+impl<T: Scalar> MonotoneConvexHull<T> {
+    pub fn build(points: Points<T>) -> Self {
+        let mut algo = Self {
+            input: points,
+            points: Points::new(),
+            upper: Points::new(),
+            lower: Points::new(),
+            candidate: None,
+            discarded: Points::new(),
+            phase: HullPhase::Done,
+            next: 0,
+        };
+        algo.reset();
+        algo
     }
 
-    /// this method takes a list of point and it assure that they turn right
-    fn assure_make_turn_right(&self, mut half_l: Points) -> Points {
+    /// like `build`, but rejects NaN and infinite coordinates and fewer than 3 points
+    pub fn try_build(points: Points<T>) -> Result<Self> {
+        check_finite(points.iter().map(|p| p.is_finite()))?;
+        if points.len() < 3 {
+            return Err(GalmetryError::InsufficientInput {
+                required: 3,
+                found: points.len(),
+            });
+        }
+        Ok(Self::build(points))
+    }
+
+    /// upper chain built so far, from the leftmost point
+    pub fn upper(&self) -> &Points<T> {
+        &self.upper
+    }
+
+    /// lower chain built so far, from the rightmost point
+    pub fn lower(&self) -> &Points<T> {
+        &self.lower
+    }
+
+    /// the point pushed during the last step, tested by `assure_make_turn_right`
+    pub fn candidate(&self) -> Option<Point<T>> {
+        self.candidate
+    }
+
+    /// the points popped from the chain during the last step
+    pub fn discarded(&self) -> &Points<T> {
+        &self.discarded
+    }
+
+    pub fn phase(&self) -> HullPhase {
+        self.phase
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase == HullPhase::Done
+    }
+
+    /// join the upper and the lower chain, dropping the shared extremes. The
+    /// hull of a single point is the point itself
+    pub fn convex_hull(&self) -> Points<T> {
+        if self.is_done() && self.points.len() == 1 {
+            return self.points.clone();
+        }
+
+        let mut upper_l = self.upper.clone();
+        let mut lower_l = self.lower.clone();
+        upper_l.pop();
+        lower_l.pop();
+
+        let mut convex_hull = Points::new();
+        convex_hull.append(&mut upper_l);
+        convex_hull.append(&mut lower_l);
+        convex_hull
+    }
+
+    /// this method takes a list of point and it assure that they turn right,
+    /// the removed points are pushed in discarded
+    fn assure_make_turn_right(half_l: &mut Points<T>, discarded: &mut Points<T>) {
         let mut half_l_len = half_l.len();
         while half_l_len > 2
             && !Point::make_right_turn(
@@ -62,18 +194,22 @@ impl MonotoneConvexHull {
                 &half_l[half_l_len - 1],
             )
         {
+            discarded.push(half_l[half_l_len - 2]);
             half_l[half_l_len - 2] = half_l[half_l_len - 1];
             half_l.pop();
             half_l_len = half_l.len();
         }
-        half_l
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::MonotoneConvexHull;
-    use crate::{algorithms::algorithm::Algorithm, geometry::points::Points};
+    use super::{HullPhase, MonotoneConvexHull};
+    use crate::{
+        algorithms::algorithm::Algorithm,
+        error::GalmetryError,
+        geometry::{point::Point, points::Points},
+    };
 
     #[test]
     fn convex_hull() {
@@ -90,4 +226,128 @@ mod tests {
 
         assert_eq!(assert_points, convex_hull);
     }
+
+    #[test]
+    fn convex_hull_nearly_collinear() {
+        let mut points = Points::new();
+        for i in 0..50 {
+            let t = 0.5 + i as f64 * 0.1;
+            points.push(Point::from2d(t, t));
+            points.push(Point::from2d(t + f64::EPSILON, t));
+        }
+        points.push(Point::from2d(3.0, 0.0));
+
+        let mut algo = MonotoneConvexHull::build(points);
+        let convex_hull = algo.calculate();
+
+        let n = convex_hull.len();
+        for i in 0..n {
+            assert!(Point::make_right_turn(
+                &convex_hull[i],
+                &convex_hull[(i + 1) % n],
+                &convex_hull[(i + 2) % n]
+            ));
+        }
+    }
+
+    #[test]
+    fn convex_hull_step_pops_left_turn() {
+        let points: Points = vec![[0.0, 0.0], [1.0, 2.0], [2.0, 1.0], [3.0, 3.0]].into();
+        let mut algo = MonotoneConvexHull::build(points);
+
+        algo.step();
+        algo.step();
+        algo.step();
+        assert_eq!(algo.upper().len(), 3);
+        assert_eq!(algo.candidate(), Some(Point::from2d(2.0, 1.0)));
+
+        // (1, 2) -> (2, 1) -> (3, 3) is a left turn, so (2, 1) is popped
+        algo.step();
+        assert_eq!(algo.candidate(), Some(Point::from2d(3.0, 3.0)));
+        assert_eq!(algo.discarded().clone(), vec![[2.0, 1.0]].into());
+        assert_eq!(
+            algo.upper().clone(),
+            vec![[0.0, 0.0], [1.0, 2.0], [3.0, 3.0]].into()
+        );
+        assert_eq!(algo.phase(), HullPhase::Lower);
+    }
+
+    #[test]
+    fn convex_hull_step_matches_calculate() {
+        let points = Points::random(30, 0.0..1.0);
+        let mut algo = MonotoneConvexHull::build(points);
+
+        while !algo.is_done() {
+            algo.step();
+        }
+        let stepped = algo.convex_hull();
+
+        algo.reset();
+        assert!(algo.upper().is_empty());
+        assert_eq!(algo.phase(), HullPhase::Upper);
+
+        assert_eq!(algo.calculate(), stepped);
+    }
+
+    #[test]
+    fn convex_hull_integer_coordinates() {
+        let mut points: Points<i32> = vec![[0, 0], [0, 4], [4, 0], [4, 4], [2, 2], [2, 4]].into();
+        points.push([1, 3]);
+
+        let mut convex_hull = MonotoneConvexHull::build(points).calculate();
+        convex_hull.lexicograph_sort();
+
+        assert_eq!(convex_hull, vec![[0, 0], [0, 4], [4, 0], [4, 4]].into());
+    }
+
+    #[test]
+    fn convex_hull_i64_large_coordinates() {
+        let big = 1i64 << 40;
+        let points: Points<i64> = vec![[0, 0], [big, 1], [2 * big, 2], [big, big]].into();
+
+        let mut convex_hull = MonotoneConvexHull::build(points).calculate();
+        convex_hull.lexicograph_sort();
+
+        // (big, 1) is exactly on the line from (0, 0) to (2 big, 2)
+        assert_eq!(convex_hull, vec![[0, 0], [big, big], [2 * big, 2]].into());
+    }
+
+    #[test]
+    fn convex_hull_try_build_insufficient_input() {
+        let points: Points = vec![[0.0, 0.0], [1.0, 0.0]].into();
+
+        assert_eq!(
+            MonotoneConvexHull::try_build(points).err(),
+            Some(GalmetryError::InsufficientInput {
+                required: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn convex_hull_try_calculate_collinear() {
+        let points: Points = vec![[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]].into();
+        let mut algo = MonotoneConvexHull::try_build(points).unwrap();
+
+        assert!(matches!(
+            algo.try_calculate(),
+            Err(GalmetryError::DegenerateInput(_))
+        ));
+    }
+
+    #[test]
+    fn convex_hull_of_one_and_two_points() {
+        let one: Points = vec![[1.0, 2.0]].into();
+        assert_eq!(MonotoneConvexHull::build(one.clone()).calculate(), one);
+
+        let two: Points = vec![[3.0, 0.0], [1.0, 2.0]].into();
+        let mut hull = MonotoneConvexHull::build(two).calculate();
+        hull.lexicograph_sort();
+        assert_eq!(hull, vec![[1.0, 2.0], [3.0, 0.0]].into());
+
+        assert!(MonotoneConvexHull::<f64>::build(Points::new())
+            .calculate()
+            .is_empty());
+    }
 }
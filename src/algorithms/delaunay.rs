@@ -0,0 +1,546 @@
+use std::cmp::Ordering;
+
+use rand::seq::SliceRandom;
+
+use super::algorithm::Algorithm;
+use crate::{
+    error::{check_finite, GalmetryError, Result},
+    geometry::{
+        mesh::TriangleMesh, point::Point, points::Points, predicates::Orientation, scalar::Scalar,
+    },
+};
+
+// the two symbolic vertexes closing the initial triangle with the highest point:
+// FAR_RIGHT lies far below on the right and FAR_LEFT even farther on the left,
+// slightly above, so that both are outside every circle through input points
+const FAR_LEFT: usize = 0;
+const FAR_RIGHT: usize = 1;
+
+// the vertex of the i-th input point
+const FIRST_POINT: usize = 2;
+
+struct Triangle {
+    // counterclockwise
+    vertices: [usize; 3],
+
+    // the i-th neighbour is across the edge opposite to the i-th vertex
+    neighbors: [Option<usize>; 3],
+
+    // the triangles that replaced this one, empty while it is part of the
+    // triangulation
+    children: Vec<usize>,
+}
+
+/// Randomized incremental Delaunay triangulation (de Berg et al., chapter 9).
+/// The points are inserted in random order inside a triangle made by the
+/// highest point and two symbolic ones, every insertion followed by the edge
+/// flips restoring the empty circle property. The history of the replaced
+/// triangles locates each new point, for O(n log n) expected time.
+///
+/// A step either inserts a point or legalizes one edge, flipping it if needed.
+/// Duplicated points are inserted once: the first copy is used, the later ones
+/// are missing from the output
+pub struct DelaunayTriangulation<T = f64> {
+    points: Points<T>,
+
+    // the insertion order, the highest point excluded
+    order: Vec<usize>,
+    next: usize,
+
+    // all the triangles ever created, the ones without children are the
+    // current triangulation and the others the point location structure
+    triangles: Vec<Triangle>,
+
+    // the edges to legalize, as a triangle and the position of the last
+    // inserted point in it
+    pending: Vec<(usize, usize)>,
+
+    // the last inserted point
+    event_point: Option<usize>,
+}
+
+impl<T: Scalar> Algorithm for DelaunayTriangulation<T> {
+    type Output = TriangleMesh;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while !self.is_done() {
+            self.step();
+        }
+
+        self.mesh()
+    }
+
+    /// fails if all the points are collinear, as no triangle exists
+    fn try_calculate(&mut self) -> Result<Self::Output> {
+        let mesh = self.calculate();
+        match mesh.is_empty() {
+            true => Err(GalmetryError::DegenerateInput(
+                "all the points are collinear",
+            )),
+            false => Ok(mesh),
+        }
+    }
+
+    fn step(&mut self) {
+        while let Some((t, i)) = self.pending.pop() {
+            // a flip already replaced the triangle
+            if self.triangles[t].children.is_empty() {
+                self.legalize(t, i);
+                return;
+            }
+        }
+
+        if let Some(&k) = self.order.get(self.next) {
+            self.next += 1;
+            self.insert(k);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.triangles = vec![];
+        self.pending = vec![];
+        self.next = 0;
+        self.event_point = None;
+
+        // by height, the copies of a point in input order, keeping the first
+        let mut kept: Vec<usize> = (0..self.points.len()).collect();
+        kept.sort_by(|&a, &b| height_cmp(&self.points[a], &self.points[b]));
+        kept.dedup_by(|b, a| height_cmp(&self.points[*a], &self.points[*b]) == Ordering::Equal);
+
+        let highest = match kept.pop() {
+            Some(highest) => highest,
+            None => {
+                self.order = vec![];
+                return;
+            }
+        };
+
+        self.order = kept;
+        self.order.shuffle(&mut rand::thread_rng());
+        self.triangles.push(Triangle {
+            vertices: [FAR_LEFT, FAR_RIGHT, highest + FIRST_POINT],
+            neighbors: [None; 3],
+            children: vec![],
+        });
+    }
+}
+
+impl<T: Scalar> DelaunayTriangulation<T> {
+    pub fn build(points: Points<T>) -> Self {
+        let mut algo = Self {
+            points,
+            order: vec![],
+            next: 0,
+            triangles: vec![],
+            pending: vec![],
+            event_point: None,
+        };
+        algo.reset();
+        algo
+    }
+
+    /// fails with less than 3 points or with NaN or infinite coordinates
+    pub fn try_build(points: Points<T>) -> Result<Self> {
+        if points.len() < 3 {
+            return Err(GalmetryError::InsufficientInput {
+                required: 3,
+                found: points.len(),
+            });
+        }
+        check_finite(points.iter().map(|p| p.is_finite()))?;
+        Ok(Self::build(points))
+    }
+
+    /// true when all the points are inserted and all the edges are legal
+    pub fn is_done(&self) -> bool {
+        self.next == self.order.len() && self.pending.is_empty()
+    }
+
+    /// the last inserted point
+    pub fn event_point(&self) -> Option<usize> {
+        self.event_point
+    }
+
+    /// the current triangles between input points, each one counterclockwise.
+    /// Until the end the ones touching the symbolic vertexes are missing
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        self.triangles
+            .iter()
+            .filter(|t| t.children.is_empty())
+            .filter(|t| t.vertices.iter().all(|&v| v >= FIRST_POINT))
+            .map(|t| t.vertices.map(|v| v - FIRST_POINT))
+            .collect()
+    }
+
+    /// the current triangulation with its adjacency
+    pub fn mesh(&self) -> TriangleMesh {
+        TriangleMesh::new(self.triangles())
+    }
+
+    fn point(&self, v: usize) -> &Point<T> {
+        &self.points[v - FIRST_POINT]
+    }
+
+    fn insert(&mut self, k: usize) {
+        let r = k + FIRST_POINT;
+        let p = self.points[k];
+        let t = self.locate(&p);
+
+        let [a, b, c] = self.triangles[t].vertices;
+        let sides = [
+            self.orientation(b, c, &p),
+            self.orientation(c, a, &p),
+            self.orientation(a, b, &p),
+        ];
+        let on_edges: Vec<usize> = (0..3)
+            .filter(|&i| sides[i] == Orientation::Collinear)
+            .collect();
+
+        match on_edges[..] {
+            [] => self.split_triangle(t, r),
+            [i] => self.split_edge(t, i, r),
+            // p is a vertex of the triangle
+            _ => return,
+        }
+        self.event_point = Some(k);
+    }
+
+    /// the triangle of the current triangulation containing p
+    fn locate(&self, p: &Point<T>) -> usize {
+        let mut t = 0;
+        while !self.triangles[t].children.is_empty() {
+            t = *self.triangles[t]
+                .children
+                .iter()
+                .find(|&&child| self.contains(child, p))
+                .expect("the children of a triangle cover it");
+        }
+        t
+    }
+
+    /// true if p lies inside the triangle t or on its boundary
+    fn contains(&self, t: usize, p: &Point<T>) -> bool {
+        let [a, b, c] = self.triangles[t].vertices;
+        [(a, b), (b, c), (c, a)]
+            .iter()
+            .all(|&(u, v)| self.orientation(u, v, p) != Orientation::Right)
+    }
+
+    /// orientation of p with respect to the line going from the vertex u to the
+    /// vertex v, any of them symbolic
+    fn orientation(&self, u: usize, v: usize, p: &Point<T>) -> Orientation {
+        // the line through a point and a symbolic one is almost horizontal, so
+        // the comparison of heights decides the side
+        let above = |v: usize| Orientation::from_ordering(height_cmp(p, self.point(v)));
+        let below = |v: usize| Orientation::from_ordering(height_cmp(self.point(v), p));
+
+        match (u, v) {
+            (FAR_LEFT, FAR_RIGHT) => Orientation::Left,
+            (FAR_RIGHT, FAR_LEFT) => Orientation::Right,
+            (u, FAR_RIGHT) => above(u),
+            (u, FAR_LEFT) => below(u),
+            (FAR_RIGHT, v) => below(v),
+            (FAR_LEFT, v) => above(v),
+            (u, v) => T::orientation(self.point(u), self.point(v), p),
+        }
+    }
+
+    /// true if p lies strictly inside the circle through the vertexes of the
+    /// triangle t. The symbolic vertexes are so far away that the circle is
+    /// almost a half plane
+    fn in_circle(&self, t: usize, p: &Point<T>) -> bool {
+        let vertices = self.triangles[t].vertices;
+        let symbolic: Vec<usize> = (0..3).filter(|&i| vertices[i] < FIRST_POINT).collect();
+
+        match symbolic[..] {
+            [] => {
+                let [a, b, c] = vertices.map(|v| self.point(v));
+                T::incircle(a, b, c, p) == Ordering::Greater
+            }
+            // the circle through u, v and the symbolic vertex on their left
+            [i] => {
+                let u = self.point(vertices[(i + 1) % 3]);
+                let v = self.point(vertices[(i + 2) % 3]);
+                match T::orientation(u, v, p) {
+                    Orientation::Left => true,
+                    Orientation::Right => false,
+                    Orientation::Collinear => {
+                        p != u && p != v && is_between(p.x, u.x, v.x) && is_between(p.y, u.y, v.y)
+                    }
+                }
+            }
+            // the circle through the two symbolic vertexes and u
+            _ => {
+                let u = vertices.iter().find(|&&v| v >= FIRST_POINT);
+                u.is_some_and(|&u| height_cmp(p, self.point(u)) == Ordering::Less)
+            }
+        }
+    }
+
+    /// flips the edge opposite to the last inserted point r, the i-th vertex of
+    /// the triangle t, if the triangle on the other side has r in its circle
+    fn legalize(&mut self, t: usize, i: usize) {
+        let u = match self.triangles[t].neighbors[i] {
+            Some(u) => u,
+            None => return,
+        };
+        let r = self.triangles[t].vertices[i];
+        if !self.in_circle(u, self.point(r)) {
+            return;
+        }
+
+        // t is (r, a, b) and u is (k, b, a)
+        let [a, b] = [1, 2].map(|d| self.triangles[t].vertices[(i + d) % 3]);
+        let j = (0..3)
+            .find(|&j| !matches!(self.triangles[u].vertices[j], v if v == a || v == b))
+            .expect("a triangle has three distinct vertexes");
+        let k = self.triangles[u].vertices[j];
+
+        let [t_opposite_a, t_opposite_b] = [1, 2].map(|d| self.triangles[t].neighbors[(i + d) % 3]);
+        let [u_opposite_b, u_opposite_a] = [1, 2].map(|d| self.triangles[u].neighbors[(j + d) % 3]);
+
+        let (t1, t2) = (self.triangles.len(), self.triangles.len() + 1);
+        self.add([r, a, k], [u_opposite_b, Some(t2), t_opposite_b]);
+        self.add([r, k, b], [u_opposite_a, t_opposite_a, Some(t1)]);
+        self.replace_neighbor(u_opposite_b, u, t1);
+        self.replace_neighbor(t_opposite_b, t, t1);
+        self.replace_neighbor(u_opposite_a, u, t2);
+        self.replace_neighbor(t_opposite_a, t, t2);
+
+        self.triangles[t].children = vec![t1, t2];
+        self.triangles[u].children = vec![t1, t2];
+        self.pending.extend([(t1, 0), (t2, 0)]);
+    }
+
+    /// replaces the triangle t with three triangles joining its edges to r
+    fn split_triangle(&mut self, t: usize, r: usize) {
+        let [a, b, c] = self.triangles[t].vertices;
+        let [opposite_a, opposite_b, opposite_c] = self.triangles[t].neighbors;
+
+        let n = self.triangles.len();
+        self.add([r, a, b], [opposite_c, Some(n + 1), Some(n + 2)]);
+        self.add([r, b, c], [opposite_a, Some(n + 2), Some(n)]);
+        self.add([r, c, a], [opposite_b, Some(n), Some(n + 1)]);
+        self.replace_neighbor(opposite_c, t, n);
+        self.replace_neighbor(opposite_a, t, n + 1);
+        self.replace_neighbor(opposite_b, t, n + 2);
+
+        self.triangles[t].children = vec![n, n + 1, n + 2];
+        self.pending.extend([(n, 0), (n + 1, 0), (n + 2, 0)]);
+    }
+
+    /// replaces the triangle t and its neighbour across the edge opposite to
+    /// the i-th vertex, with r lying on that edge, by four triangles
+    fn split_edge(&mut self, t: usize, i: usize, r: usize) {
+        // t is (c, a, b) and u is (d, b, a)
+        let [c, a, b] = [0, 1, 2].map(|d| self.triangles[t].vertices[(i + d) % 3]);
+        let [t_opposite_a, t_opposite_b] = [1, 2].map(|d| self.triangles[t].neighbors[(i + d) % 3]);
+        let u = self.triangles[t].neighbors[i].expect("the boundary edges are symbolic");
+        let j = (0..3)
+            .find(|&j| !matches!(self.triangles[u].vertices[j], v if v == a || v == b))
+            .expect("a triangle has three distinct vertexes");
+        let d = self.triangles[u].vertices[j];
+        let [u_opposite_b, u_opposite_a] = [1, 2].map(|k| self.triangles[u].neighbors[(j + k) % 3]);
+
+        let n = self.triangles.len();
+        self.add([r, b, c], [t_opposite_a, Some(n + 1), Some(n + 3)]);
+        self.add([r, c, a], [t_opposite_b, Some(n + 2), Some(n)]);
+        self.add([r, a, d], [u_opposite_b, Some(n + 3), Some(n + 1)]);
+        self.add([r, d, b], [u_opposite_a, Some(n), Some(n + 2)]);
+        self.replace_neighbor(t_opposite_a, t, n);
+        self.replace_neighbor(t_opposite_b, t, n + 1);
+        self.replace_neighbor(u_opposite_b, u, n + 2);
+        self.replace_neighbor(u_opposite_a, u, n + 3);
+
+        self.triangles[t].children = vec![n, n + 1];
+        self.triangles[u].children = vec![n + 2, n + 3];
+        self.pending
+            .extend([(n, 0), (n + 1, 0), (n + 2, 0), (n + 3, 0)]);
+    }
+
+    fn add(&mut self, vertices: [usize; 3], neighbors: [Option<usize>; 3]) {
+        self.triangles.push(Triangle {
+            vertices,
+            neighbors,
+            children: vec![],
+        });
+    }
+
+    /// makes the triangle t point to new instead of old
+    fn replace_neighbor(&mut self, t: Option<usize>, old: usize, new: usize) {
+        if let Some(t) = t {
+            for neighbor in self.triangles[t].neighbors.iter_mut() {
+                if *neighbor == Some(old) {
+                    *neighbor = Some(new);
+                }
+            }
+        }
+    }
+}
+
+/// compares the height of the points, the one on the right is higher on ties
+fn height_cmp<T: Scalar>(p: &Point<T>, q: &Point<T>) -> Ordering {
+    p.y.partial_cmp(&q.y)
+        .and_then(|o| Some(o.then(p.x.partial_cmp(&q.x)?)))
+        .expect("Comparision is impossible with NaN coordinates!")
+}
+
+/// true if v lies between a and b, their order not being known
+fn is_between<T: Scalar>(v: T, a: T, b: T) -> bool {
+    a.min(b) <= v && v <= a.max(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::DelaunayTriangulation;
+    use crate::{
+        algorithms::{algorithm::Algorithm, convex_hull::MonotoneConvexHull},
+        error::GalmetryError,
+        geometry::{
+            mesh::TriangleMesh, point::Point, points::Points, predicates::Orientation,
+            scalar::Scalar,
+        },
+    };
+
+    /// each triangle is counterclockwise with no point inside its circle, the
+    /// adjacency is symmetric and there are 2n - 2 - h triangles, h being the
+    /// number of points on the boundary of the hull
+    fn assert_delaunay<T: Scalar>(points: &Points<T>, mesh: &TriangleMesh, boundary: usize) {
+        assert_eq!(mesh.len(), 2 * points.len() - 2 - boundary);
+
+        for (t, &[a, b, c]) in mesh.triangles().iter().enumerate() {
+            let (a, b, c) = (&points[a], &points[b], &points[c]);
+            assert_eq!(T::orientation(a, b, c), Orientation::Left);
+            for p in points.iter() {
+                assert_ne!(T::incircle(a, b, c, p), Ordering::Greater);
+            }
+
+            for neighbor in mesh.neighbors()[t].iter().flatten() {
+                assert!(mesh.neighbors()[*neighbor].contains(&Some(t)));
+            }
+        }
+    }
+
+    fn sorted(mesh: &TriangleMesh) -> Vec<[usize; 3]> {
+        let mut triangles: Vec<[usize; 3]> = mesh
+            .triangles()
+            .iter()
+            .map(|t| {
+                let first = (0..3).min_by_key(|&i| t[i]).unwrap();
+                [t[first], t[(first + 1) % 3], t[(first + 2) % 3]]
+            })
+            .collect();
+        triangles.sort();
+        triangles
+    }
+
+    #[test]
+    fn delaunay_square() {
+        let points: Points = vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]].into();
+        let mesh = DelaunayTriangulation::build(points.clone()).calculate();
+
+        assert_delaunay(&points, &mesh, 4);
+        assert_eq!(mesh.edges().len(), 5);
+    }
+
+    #[test]
+    fn delaunay_flips_thin_triangles() {
+        // the diagonal 0-2 would make two thin triangles
+        let points: Points = vec![[0.0, 0.0], [2.0, -1.0], [4.0, 0.0], [2.0, 1.0]].into();
+        let mesh = DelaunayTriangulation::build(points.clone()).calculate();
+
+        assert_delaunay(&points, &mesh, 4);
+        assert!(mesh.edges().contains(&(1, 3)));
+    }
+
+    #[test]
+    fn delaunay_random_points() {
+        for _ in 0..20 {
+            let points = Points::random(60, 0.0..10.0);
+            let hull = MonotoneConvexHull::build(points.clone()).calculate();
+            let mesh = DelaunayTriangulation::build(points.clone()).calculate();
+
+            assert_delaunay(&points, &mesh, hull.len());
+        }
+    }
+
+    #[test]
+    fn delaunay_grid() {
+        // plenty of cocircular and collinear points
+        let mut points: Points = Points::new();
+        for x in 0..6 {
+            for y in 0..5 {
+                points.push(Point::from2d(x as f64, y as f64));
+            }
+        }
+
+        for _ in 0..10 {
+            let mesh = DelaunayTriangulation::build(points.clone()).calculate();
+            assert_delaunay(&points, &mesh, 18);
+        }
+    }
+
+    #[test]
+    fn delaunay_integer_coordinates() {
+        let points: Points<i32> = vec![[0, 0], [10, 0], [10, 10], [0, 10], [5, 5], [5, 0]].into();
+        let mesh = DelaunayTriangulation::build(points.clone()).calculate();
+
+        assert_delaunay(&points, &mesh, 5);
+    }
+
+    #[test]
+    fn delaunay_steps() {
+        let points = Points::random(30, 0.0..1.0);
+        let mut algo = DelaunayTriangulation::build(points.clone());
+
+        let mut inserted = vec![];
+        while !algo.is_done() {
+            algo.step();
+            if let Some(k) = algo.event_point() {
+                if inserted.last() != Some(&k) {
+                    inserted.push(k);
+                }
+            }
+        }
+
+        // the highest point is part of the initial triangle
+        assert_eq!(inserted.len(), points.len() - 1);
+        assert_eq!(sorted(&algo.mesh()), sorted(&algo.calculate()));
+    }
+
+    #[test]
+    fn delaunay_duplicated_points() {
+        let points: Points =
+            vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 0.0], [0.0, 0.0]].into();
+        let mesh = DelaunayTriangulation::build(points).calculate();
+
+        // the first copies are used
+        assert_eq!(sorted(&mesh), vec![[0, 1, 2]]);
+    }
+
+    #[test]
+    fn delaunay_collinear_points() {
+        let points: Points = vec![[0.0, 0.0], [1.0, 1.0], [2.0, 2.0], [3.0, 3.0]].into();
+
+        assert_eq!(
+            DelaunayTriangulation::build(points).try_calculate(),
+            Err(GalmetryError::DegenerateInput(
+                "all the points are collinear"
+            ))
+        );
+    }
+
+    #[test]
+    fn try_build_delaunay() {
+        assert!(matches!(
+            DelaunayTriangulation::try_build(vec![[0.0, 0.0], [1.0, 0.0]].into()),
+            Err(GalmetryError::InsufficientInput {
+                required: 3,
+                found: 2
+            })
+        ));
+    }
+}
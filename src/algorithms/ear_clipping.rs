@@ -0,0 +1,562 @@
+use super::algorithm::Algorithm;
+use crate::{
+    error::{check_finite, GalmetryError, Result},
+    geometry::{
+        point::Point,
+        points::Points,
+        polygon::Polygon,
+        predicates::Orientation,
+        scalar::{Field, Scalar},
+        segment::Segment,
+    },
+};
+
+/// Ear clipping triangulation of a polygon with holes. Each hole is joined to
+/// the outer ring by a bridge, a pair of opposite edges, then an ear is clipped
+/// at each step. Only the two neighbours of a clipped ear can stop or start
+/// being ears, so it runs in O(n^2) on a simple ring. It tolerates collinear
+/// vertexes.
+///
+/// Vertexes are referred by their index in the input: first the outer polygon,
+/// then each hole in order, so the first vertex of the second hole comes after
+/// all the vertexes of the outer polygon and of the first hole
+pub struct EarClipping<T = f64> {
+    // all the input vertexes, indexed like the output
+    vertices: Vec<Point<T>>,
+
+    // the range of indexes of the outer polygon and of each hole
+    rings: Vec<(usize, usize)>,
+
+    // the ring still to triangulate, counterclockwise, holes included.
+    // The vertexes of a bridge appear twice
+    ring: Vec<usize>,
+
+    // whether each position of the ring is an ear, updated next to each clip
+    ears: Vec<bool>,
+
+    // the holes that could not be joined to the outer ring
+    unbridged: usize,
+
+    // the vertexes dropped without ears nor collinear vertexes left, when the
+    // ring is not simple
+    dropped: usize,
+
+    // the output of the algorithm
+    triangles: Vec<[usize; 3]>,
+}
+
+impl<T: Scalar> Algorithm for EarClipping<T> {
+    type Output = Vec<[usize; 3]>;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while !self.is_done() {
+            self.step();
+        }
+
+        self.triangles.clone()
+    }
+
+    /// fails if a hole could not be joined to the outer ring, as when it is
+    /// outside of it or it crosses another hole, or if a vertex had to be
+    /// dropped without clipping its area, as when the ring is not simple
+    fn try_calculate(&mut self) -> Result<Self::Output> {
+        let triangles = self.calculate();
+        match (self.unbridged, self.dropped) {
+            (0, 0) => Ok(triangles),
+            (0, _) => Err(GalmetryError::DegenerateInput(
+                "a vertex can not be clipped",
+            )),
+            _ => Err(GalmetryError::DegenerateInput(
+                "a hole can not be joined to the outer ring",
+            )),
+        }
+    }
+
+    fn step(&mut self) {
+        if self.is_done() {
+            return;
+        }
+
+        // a clip only removes vertexes from the other triangles, so the ears
+        // away from it stay ears, but a degenerate ring can gain some
+        if !self.ears.contains(&true) {
+            self.ears = (0..self.ring.len()).map(|i| self.is_ear(i)).collect();
+        }
+        let n = self.ring.len();
+        if let Some(i) = self.ears.iter().position(|&ear| ear) {
+            let (prev, next) = (self.ring[(i + n - 1) % n], self.ring[(i + 1) % n]);
+            self.triangles.push([prev, self.ring[i], next]);
+            self.remove(i);
+            return;
+        }
+
+        // without ears the remaining vertexes have no area left to cover: drop a
+        // collinear one, or any if the ring is not simple
+        let i = match (0..n).find(|&i| self.turn(i) == Orientation::Collinear) {
+            Some(i) => i,
+            None => {
+                self.dropped += 1;
+                0
+            }
+        };
+        self.remove(i);
+    }
+
+    fn reset(&mut self) {
+        self.triangles = vec![];
+        self.unbridged = 0;
+        self.dropped = 0;
+
+        let (start, end) = self.rings[0];
+        self.ring = self.oriented(start, end, Orientation::Left);
+
+        // the holes on the right are joined first, so that a bridge never
+        // crosses a hole still to join
+        let mut holes: Vec<(usize, usize)> = self.rings[1..].to_vec();
+        holes.sort_by(|a, b| {
+            let (a, b) = (self.rightmost(a.0, a.1), self.rightmost(b.0, b.1));
+            self.vertices[b].lexicograph_cmp(&self.vertices[a])
+        });
+
+        for (k, &(start, end)) in holes.iter().enumerate() {
+            if !self.bridge(start, end, &holes[k + 1..]) {
+                self.unbridged += 1;
+            }
+        }
+        self.ears = (0..self.ring.len()).map(|i| self.is_ear(i)).collect();
+    }
+}
+
+impl<T: Scalar> EarClipping<T> {
+    pub fn build(polygon: Polygon<T>, holes: Vec<Polygon<T>>) -> Self {
+        let mut vertices: Vec<Point<T>> = vec![];
+        let mut rings = vec![];
+        for ring in [polygon].iter().chain(holes.iter()) {
+            rings.push((vertices.len(), vertices.len() + ring.len()));
+            vertices.extend(ring.vertices().iter().copied());
+        }
+
+        let mut algo = Self {
+            vertices,
+            rings,
+            ring: vec![],
+            ears: vec![],
+            unbridged: 0,
+            dropped: 0,
+            triangles: vec![],
+        };
+        algo.reset();
+        algo
+    }
+
+    /// true when the ring has no area left to triangulate
+    pub fn is_done(&self) -> bool {
+        self.ring.len() < 3
+    }
+
+    /// the vertexes not clipped yet, counterclockwise. The holes are joined to
+    /// the outer ring, so the vertexes of each bridge appear twice
+    pub fn ring(&self) -> &Vec<usize> {
+        &self.ring
+    }
+
+    /// the triangles clipped so far, each one in counterclockwise order
+    pub fn triangles(&self) -> &Vec<[usize; 3]> {
+        &self.triangles
+    }
+
+    fn point(&self, i: usize) -> &Point<T> {
+        &self.vertices[self.ring[i]]
+    }
+
+    /// the turn made by the ring in the i-th position
+    fn turn(&self, i: usize) -> Orientation {
+        let n = self.ring.len();
+        T::orientation(
+            self.point((i + n - 1) % n),
+            self.point(i),
+            self.point((i + 1) % n),
+        )
+    }
+
+    /// removes the i-th position, checking again its neighbours for ears
+    fn remove(&mut self, i: usize) {
+        self.ring.remove(i);
+        self.ears.remove(i);
+        let n = self.ring.len();
+        if n >= 3 {
+            for j in [(i + n - 1) % n, i % n] {
+                self.ears[j] = self.is_ear(j);
+            }
+        }
+    }
+
+    /// true if the triangle made by the i-th position and its neighbours is
+    /// convex and no other vertex of the ring lies in it
+    fn is_ear(&self, i: usize) -> bool {
+        if self.turn(i) != Orientation::Left {
+            return false;
+        }
+
+        let n = self.ring.len();
+        let (a, b, c) = (
+            self.point((i + n - 1) % n),
+            self.point(i),
+            self.point((i + 1) % n),
+        );
+        self.ring.iter().all(|&j| {
+            let p = &self.vertices[j];
+            // the vertexes of a bridge are duplicated
+            if p == a || p == b || p == c {
+                return true;
+            }
+
+            T::orientation(a, b, p) == Orientation::Right
+                || T::orientation(b, c, p) == Orientation::Right
+                || T::orientation(c, a, p) == Orientation::Right
+        })
+    }
+
+    /// the indexes from start to end in the given orientation
+    fn oriented(&self, start: usize, end: usize, orientation: Orientation) -> Vec<usize> {
+        let polygon = Polygon::new(Points::from(self.vertices[start..end].to_vec()));
+        match polygon.orientation() == orientation {
+            true => (start..end).collect(),
+            false => (start..end).rev().collect(),
+        }
+    }
+
+    /// the index with the greatest x, and the greatest y among them
+    fn rightmost(&self, start: usize, end: usize) -> usize {
+        (start..end)
+            .max_by(|&a, &b| self.vertices[a].lexicograph_cmp(&self.vertices[b]))
+            .unwrap_or(start)
+    }
+
+    /// true if the direction from the i-th position towards p enters the interior
+    /// of the ring, that is on the left of the ring
+    fn in_cone(&self, i: usize, p: &Point<T>) -> bool {
+        let n = self.ring.len();
+        let (prev, v, next) = (
+            self.point((i + n - 1) % n),
+            self.point(i),
+            self.point((i + 1) % n),
+        );
+
+        let left_of_prev = T::orientation(prev, v, p) == Orientation::Left;
+        let left_of_next = T::orientation(v, next, p) == Orientation::Left;
+        match T::orientation(prev, v, next) {
+            Orientation::Left => left_of_prev && left_of_next,
+            _ => left_of_prev || left_of_next,
+        }
+    }
+
+    /// true if the segment from a to b crosses none of the edges, touching them
+    /// only in a and b
+    fn is_clear(&self, a: &Point<T>, b: &Point<T>, edges: &[Segment<T>]) -> bool {
+        let bridge = Segment::new(*a, *b);
+        edges.iter().all(|edge| {
+            let shared = [edge.start, edge.end]
+                .iter()
+                .filter(|p| *p == a || *p == b)
+                .count();
+            match shared {
+                0 => !bridge.intersects(edge),
+                // an edge leaving a bridge endpoint must not run along the bridge
+                1 => {
+                    let other = match edge.start == *a || edge.start == *b {
+                        true => edge.end,
+                        false => edge.start,
+                    };
+                    !(bridge.contains(&other) || edge.contains(a) && edge.contains(b))
+                }
+                _ => true,
+            }
+        })
+    }
+
+    /// join the hole to the ring with a bridge from its rightmost vertex to the
+    /// closest visible vertex of the ring. The bridge must not cross the ring,
+    /// the hole or the holes still to join
+    fn bridge(&mut self, start: usize, end: usize, pending: &[(usize, usize)]) -> bool {
+        let hole = self.oriented(start, end, Orientation::Right);
+        let m = self.rightmost(start, end);
+        let m_point = self.vertices[m];
+
+        let mut edges: Vec<Segment<T>> = vec![];
+        let closed = |ring: &[usize]| -> Vec<(usize, usize)> {
+            (0..ring.len())
+                .map(|i| (ring[i], ring[(i + 1) % ring.len()]))
+                .collect()
+        };
+        let mut pairs = closed(&self.ring);
+        pairs.extend(closed(&hole));
+        for &(s, e) in pending {
+            pairs.extend(closed(&(s..e).collect::<Vec<usize>>()));
+        }
+        for (a, b) in pairs {
+            edges.push(Segment::new(self.vertices[a], self.vertices[b]));
+        }
+
+        let hole_position = hole.iter().position(|&v| v == m).unwrap_or(0);
+        let hole_cone = |p: &Point<T>| {
+            let n = hole.len();
+            let prev = &self.vertices[hole[(hole_position + n - 1) % n]];
+            let next = &self.vertices[hole[(hole_position + 1) % n]];
+            // the rightmost vertex of the hole is convex, seen from the hole
+            T::orientation(prev, &m_point, p) == Orientation::Left
+                || T::orientation(&m_point, next, p) == Orientation::Left
+        };
+
+        let candidate = (0..self.ring.len())
+            .filter(|&i| *self.point(i) != m_point)
+            .filter(|&i| self.in_cone(i, &m_point) && hole_cone(self.point(i)))
+            .filter(|&i| self.is_clear(&m_point, self.point(i), &edges))
+            .min_by(|&i, &j| {
+                let (di, dj) = (
+                    m_point.distance_from(self.point(i)),
+                    m_point.distance_from(self.point(j)),
+                );
+                di.partial_cmp(&dj)
+                    .expect("Comparision is impossible with NaN coordinates!")
+            });
+
+        let i = match candidate {
+            Some(i) => i,
+            None => return false,
+        };
+
+        // ring: ..., v, m, hole..., m, v, ...
+        let v = self.ring[i];
+        let mut joined: Vec<usize> = hole[hole_position..]
+            .iter()
+            .chain(hole[..hole_position].iter())
+            .copied()
+            .collect();
+        joined.push(m);
+        joined.push(v);
+        self.ring.splice(i + 1..i + 1, joined);
+        true
+    }
+}
+
+impl<T: Field> EarClipping<T> {
+    /// like `build`, but rejects rings with NaN or infinite coordinates, with
+    /// less than 3 vertexes or that are not simple
+    pub fn try_build(polygon: Polygon<T>, holes: Vec<Polygon<T>>) -> Result<Self> {
+        let rings = [&polygon].into_iter().chain(holes.iter());
+        check_finite(
+            rings
+                .clone()
+                .flat_map(|ring| ring.vertices().iter())
+                .map(|p| p.is_finite()),
+        )?;
+        for ring in rings {
+            Polygon::try_new(ring.vertices().clone())?;
+            if !ring.is_simple() {
+                return Err(GalmetryError::DegenerateInput("a ring is not simple"));
+            }
+        }
+        Ok(Self::build(polygon, holes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EarClipping;
+    use crate::{
+        algorithms::algorithm::Algorithm,
+        error::GalmetryError,
+        geometry::{points::Points, polygon::Polygon},
+    };
+    use rand::Rng;
+
+    fn rectangle(x0: f64, y0: f64, x1: f64, y1: f64) -> Polygon {
+        vec![[x0, y0], [x1, y0], [x1, y1], [x0, y1]].into()
+    }
+
+    /// the triangles are counterclockwise and cover the area of the polygon
+    /// without the holes
+    fn assert_triangulation(polygon: &Polygon, holes: &[Polygon], triangles: &[[usize; 3]]) {
+        let mut vertices = polygon.vertices().clone();
+        for hole in holes {
+            vertices.append(&mut hole.vertices().clone());
+        }
+
+        let expected = polygon.area() - holes.iter().map(|hole| hole.area()).sum::<f64>();
+        let mut area = 0.0;
+        for t in triangles {
+            let triangle = Polygon::new(Points::from(vec![
+                vertices[t[0]],
+                vertices[t[1]],
+                vertices[t[2]],
+            ]));
+            assert!(!triangle.is_cw(), "{:?} is clockwise", t);
+            area += triangle.area();
+        }
+        assert!(
+            (area - expected).abs() <= 1e-9 * expected,
+            "{} != {}",
+            area,
+            expected
+        );
+    }
+
+    #[test]
+    fn ear_clipping_square() {
+        let polygon = rectangle(0.0, 0.0, 4.0, 4.0);
+        let triangles = EarClipping::build(polygon.clone(), vec![]).calculate();
+
+        assert_eq!(triangles.len(), 2);
+        assert_triangulation(&polygon, &[], &triangles);
+    }
+
+    #[test]
+    fn ear_clipping_clockwise_concave() {
+        let polygon: Polygon = vec![
+            [0.0, 4.0],
+            [4.0, 4.0],
+            [4.0, 0.0],
+            [2.0, 0.0],
+            [2.0, 2.0],
+            [0.0, 2.0],
+        ]
+        .into();
+        let triangles = EarClipping::build(polygon.clone(), vec![]).calculate();
+
+        assert_eq!(triangles.len(), 4);
+        assert_triangulation(&polygon, &[], &triangles);
+    }
+
+    #[test]
+    fn ear_clipping_collinear_vertices() {
+        let polygon: Polygon = vec![
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [2.0, 0.0],
+            [3.0, 0.0],
+            [3.0, 3.0],
+            [0.0, 3.0],
+        ]
+        .into();
+        let triangles = EarClipping::build(polygon.clone(), vec![]).calculate();
+
+        assert_eq!(triangles.len(), 4);
+        assert_triangulation(&polygon, &[], &triangles);
+    }
+
+    #[test]
+    fn ear_clipping_one_hole() {
+        let polygon = rectangle(0.0, 0.0, 10.0, 10.0);
+        let holes = vec![rectangle(4.0, 4.0, 6.0, 6.0)];
+        let mut algo = EarClipping::build(polygon.clone(), holes.clone());
+
+        // the hole is joined by a bridge, whose vertexes appear twice
+        assert_eq!(algo.ring().len(), 10);
+
+        let triangles = algo.try_calculate().unwrap();
+        assert_eq!(triangles.len(), 8);
+        assert_triangulation(&polygon, &holes, &triangles);
+        assert!(triangles.iter().flatten().any(|&v| v >= 4));
+    }
+
+    #[test]
+    fn ear_clipping_floor_plan() {
+        // an L shaped floor with two pillars and a shaft aligned with them
+        let polygon: Polygon = vec![
+            [0.0, 0.0],
+            [12.0, 0.0],
+            [12.0, 6.0],
+            [6.0, 6.0],
+            [6.0, 12.0],
+            [0.0, 12.0],
+        ]
+        .into();
+        let holes = vec![
+            rectangle(2.0, 2.0, 3.0, 3.0),
+            rectangle(8.0, 2.0, 9.0, 3.0),
+            rectangle(2.0, 8.0, 4.0, 10.0),
+        ];
+        let triangles = EarClipping::build(polygon.clone(), holes.clone())
+            .try_calculate()
+            .unwrap();
+
+        assert_eq!(triangles.len(), 6 + 3 * 4 + 2 * 3 - 2);
+        assert_triangulation(&polygon, &holes, &triangles);
+    }
+
+    #[test]
+    fn ear_clipping_random_holes() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            // pillars on a grid, so that they never overlap
+            let polygon = rectangle(0.0, 0.0, 20.0, 20.0);
+            let mut holes: Vec<Polygon> = vec![];
+            for k in 0..16 {
+                if rng.gen_bool(0.5) {
+                    let (x, y) = ((k % 4) as f64 * 5.0 + 1.0, (k / 4) as f64 * 5.0 + 1.0);
+                    let (w, h) = (rng.gen_range(1..4) as f64, rng.gen_range(1..4) as f64);
+                    holes.push(rectangle(x, y, x + w, y + h));
+                }
+            }
+
+            let triangles = EarClipping::build(polygon.clone(), holes.clone())
+                .try_calculate()
+                .unwrap();
+            assert_eq!(triangles.len(), 2 + 6 * holes.len());
+            assert_triangulation(&polygon, &holes, &triangles);
+        }
+    }
+
+    #[test]
+    fn ear_clipping_step() {
+        let polygon = rectangle(0.0, 0.0, 10.0, 10.0);
+        let mut algo = EarClipping::build(polygon, vec![rectangle(4.0, 4.0, 6.0, 6.0)]);
+
+        algo.step();
+        assert_eq!(algo.triangles().len(), 1);
+        assert_eq!(algo.ring().len(), 9);
+
+        while !algo.is_done() {
+            algo.step();
+        }
+        let stepped = algo.triangles().clone();
+        assert_eq!(algo.calculate(), stepped);
+    }
+
+    #[test]
+    fn ear_clipping_hole_outside() {
+        let polygon = rectangle(0.0, 0.0, 4.0, 4.0);
+        let mut algo = EarClipping::build(polygon, vec![rectangle(5.0, 5.0, 6.0, 6.0)]);
+
+        assert!(matches!(
+            algo.try_calculate(),
+            Err(GalmetryError::DegenerateInput(_))
+        ));
+    }
+
+    #[test]
+    fn ear_clipping_not_simple() {
+        let bowtie: Polygon = vec![[0.0, 0.0], [2.0, 2.0], [2.0, 0.0], [0.0, 2.0]].into();
+        let mut algo = EarClipping::build(bowtie, vec![]);
+
+        assert_eq!(
+            algo.try_calculate(),
+            Err(GalmetryError::DegenerateInput(
+                "a vertex can not be clipped"
+            ))
+        );
+    }
+
+    #[test]
+    fn ear_clipping_try_build() {
+        let polygon = rectangle(0.0, 0.0, 4.0, 4.0);
+        let nan_hole: Polygon = vec![[1.0, 1.0], [2.0, f64::NAN], [2.0, 2.0]].into();
+
+        assert_eq!(
+            EarClipping::try_build(polygon, vec![nan_hole]).err(),
+            Some(GalmetryError::NonFiniteCoordinate { index: 5 })
+        );
+    }
+}
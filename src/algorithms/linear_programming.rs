@@ -0,0 +1,994 @@
+use std::{cmp::Ordering, collections::VecDeque};
+
+use rand::seq::SliceRandom;
+
+use super::algorithm::Algorithm;
+use crate::{
+    error::{check_finite, Result},
+    geometry::{
+        half_plane::HalfPlane,
+        point::Point,
+        points::Points,
+        polygon::Polygon,
+        scalar::{Field, Scalar},
+    },
+};
+
+/// The outcome of a linear program
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LpResult<T = f64> {
+    /// no point is in all the half-planes
+    Infeasible,
+
+    /// the objective grows without limit in the feasible region
+    Unbounded,
+
+    /// a feasible point where the objective is maximum
+    Optimal(Point<T>),
+}
+
+/// Seidel's randomized incremental linear programming in the plane: the point
+/// in all the half-planes maximizing the dot product with the objective. The
+/// half-planes are added in random order, and the optimum is recomputed only
+/// when it falls outside the new one, by a one-dimensional program on its
+/// boundary line. This takes O(n) expected time.
+///
+/// Unbounded programs are told apart first, as in de Berg et al. chapter 4.4:
+/// either a direction improving the objective stays in every half-plane, or
+/// one or two half-planes bound the objective and start the algorithm. With a
+/// null objective any feasible point is optimal. A step adds one half-plane
+pub struct LinearProgram<T = f64> {
+    // the input, kept to replay the algorithm
+    constraints: Vec<HalfPlane<T>>,
+    objective: Point<T>,
+
+    // the objective used, the normal of a constraint when the input one is null
+    direction: Point<T>,
+
+    // the constraints in the order they are added, the bounding ones first
+    order: Vec<usize>,
+
+    // the number of constraints added so far
+    added: usize,
+
+    // the optimum of the constraints added, or the result once known
+    result: LpResult<T>,
+}
+
+impl<T: Field> Algorithm for LinearProgram<T> {
+    type Output = LpResult<T>;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while !self.is_done() {
+            self.step();
+        }
+
+        self.result
+    }
+
+    fn step(&mut self) {
+        if self.is_done() {
+            return;
+        }
+
+        let h = self.constraints[self.order[self.added]];
+        if let LpResult::Optimal(v) = self.result {
+            if violates(&h, &v) {
+                let start = h
+                    .boundary_point()
+                    .expect("degenerate constraints are skipped");
+                self.result = match self.on_line(start, h.direction(), &self.order[..self.added]) {
+                    Some(v) => LpResult::Optimal(v),
+                    None => LpResult::Infeasible,
+                };
+            }
+        }
+        self.added += 1;
+    }
+
+    fn reset(&mut self) {
+        self.order = vec![];
+        self.added = 0;
+        self.result = LpResult::Optimal(Point::from2d(T::zero(), T::zero()));
+
+        // a degenerate half-plane is the whole plane or nothing
+        if self
+            .constraints
+            .iter()
+            .any(|h| h.is_degenerate() && h.c < T::zero())
+        {
+            self.result = LpResult::Infeasible;
+            return;
+        }
+        let mut active: Vec<usize> = (0..self.constraints.len())
+            .filter(|&i| !self.constraints[i].is_degenerate())
+            .collect();
+
+        self.direction = self.objective;
+        if self.direction == Point::from2d(T::zero(), T::zero()) {
+            match active.first() {
+                Some(&i) => self.direction = self.constraints[i].normal(),
+                None => return,
+            }
+        }
+
+        let start = match self.bounding(&active) {
+            Some(start) => start,
+            None => {
+                self.result = self.unbounded(&active);
+                return;
+            }
+        };
+
+        let v = match start[..] {
+            [i] => self.constraints[i].boundary_point(),
+            [i, j] => self.constraints[i].intersection(&self.constraints[j]),
+            _ => None,
+        };
+        self.result = LpResult::Optimal(v.expect("the bounding constraints meet"));
+
+        active.retain(|i| !start.contains(i));
+        active.shuffle(&mut rand::thread_rng());
+        self.added = start.len();
+        self.order = start;
+        self.order.append(&mut active);
+    }
+}
+
+impl<T: Field> LinearProgram<T> {
+    /// the program maximizing the dot product of the objective with a point
+    /// in all the constraints
+    pub fn build(constraints: Vec<HalfPlane<T>>, objective: impl Into<Point<T>>) -> Self {
+        let objective = objective.into();
+        let mut algo = Self {
+            constraints,
+            objective,
+            direction: objective,
+            order: vec![],
+            added: 0,
+            result: LpResult::Infeasible,
+        };
+        algo.reset();
+        algo
+    }
+
+    /// like `build`, but rejects NaN and infinite coefficients. The objective
+    /// has the index after the last constraint
+    pub fn try_build(
+        constraints: Vec<HalfPlane<T>>,
+        objective: impl Into<Point<T>>,
+    ) -> Result<Self> {
+        let objective = objective.into();
+        check_finite(
+            constraints
+                .iter()
+                .map(|h| h.is_finite())
+                .chain([objective.is_finite()]),
+        )?;
+        Ok(Self::build(constraints, objective))
+    }
+
+    /// true when every constraint is added, or the program is known to be
+    /// infeasible or unbounded
+    pub fn is_done(&self) -> bool {
+        match self.result {
+            LpResult::Optimal(_) => self.added == self.order.len(),
+            _ => true,
+        }
+    }
+
+    /// the optimum of the constraints added so far
+    pub fn result(&self) -> &LpResult<T> {
+        &self.result
+    }
+
+    /// the constraints added so far, by index in the input
+    pub fn added(&self) -> &[usize] {
+        &self.order[..self.added.min(self.order.len())]
+    }
+
+    /// One or two constraints bounding the objective, None if the objective
+    /// can grow along a direction staying in all of them.
+    ///
+    /// The directions improving the objective c are c + t p, p orthogonal to
+    /// c: each constraint with normal n allows them for t (n · p) <= -(n · c),
+    /// a one-dimensional program in t
+    fn bounding(&self, active: &[usize]) -> Option<Vec<usize>> {
+        let c = self.direction;
+        let p = Point::from2d(-c.y, c.x);
+
+        let mut low: Option<(T, usize)> = None;
+        let mut high: Option<(T, usize)> = None;
+        for &i in active {
+            let n = self.constraints[i].normal();
+            let (k, r) = (dot(&n, &p), -dot(&n, &c));
+            match k.partial_cmp(&T::zero()) {
+                Some(Ordering::Equal) if r < T::zero() => return Some(vec![i]),
+                Some(Ordering::Greater) if high.is_none_or(|(t, _)| r / k < t) => {
+                    high = Some((r / k, i))
+                }
+                Some(Ordering::Less) if low.is_none_or(|(t, _)| r / k > t) => {
+                    low = Some((r / k, i))
+                }
+                _ => {}
+            }
+        }
+
+        match (low, high) {
+            (Some((low, i)), Some((high, j))) if low > high => Some(vec![i, j]),
+            _ => None,
+        }
+    }
+
+    /// Unbounded if the constraints are feasible, knowing that a direction
+    /// improving the objective stays in all of them. Moving far enough along
+    /// it, only the constraints parallel to it can be violated: there are
+    /// some only when the directions allowed by the constraints, between low
+    /// and high, reduce to one
+    fn unbounded(&self, active: &[usize]) -> LpResult<T> {
+        let c = self.direction;
+        let p = Point::from2d(-c.y, c.x);
+
+        let (mut low, mut high): (Option<T>, Option<T>) = (None, None);
+        for &i in active {
+            let n = self.constraints[i].normal();
+            let (k, r) = (dot(&n, &p), -dot(&n, &c));
+            if k > T::zero() {
+                high = Some(high.map_or(r / k, |t| t.min(r / k)));
+            } else if k < T::zero() {
+                low = Some(low.map_or(r / k, |t| t.max(r / k)));
+            }
+        }
+        let (low, high) = match (low, high) {
+            (Some(low), Some(high)) => (low, high),
+            _ => return LpResult::Unbounded,
+        };
+        let scale = low.abs().max(high.abs()).max(T::one());
+        if high - low > rounding::<T>() * scale {
+            return LpResult::Unbounded;
+        }
+
+        // the constraints setting low or high, with the same tolerance
+        let t = (low + high) / (T::one() + T::one());
+        let parallel: Vec<usize> = active
+            .iter()
+            .copied()
+            .filter(|&i| {
+                let n = self.constraints[i].normal();
+                let (k, r) = (dot(&n, &p), -dot(&n, &c));
+                k != T::zero() && (r / k - t).abs() <= rounding::<T>() * scale
+            })
+            .collect();
+        let d = Point::from2d(c.x + t * p.x, c.y + t * p.y);
+        let origin = Point::from2d(T::zero(), T::zero());
+        match self.on_line(origin, Point::from2d(-d.y, d.x), &parallel) {
+            Some(_) => LpResult::Unbounded,
+            None => LpResult::Infeasible,
+        }
+    }
+
+    /// The best point start + s u in the constraints, None if there is none.
+    /// When the objective does not change along the line, the point closest
+    /// to start is taken
+    fn on_line(&self, start: Point<T>, u: Point<T>, constraints: &[usize]) -> Option<Point<T>> {
+        let (mut low, mut high): (Option<T>, Option<T>) = (None, None);
+        for &j in constraints {
+            let h = &self.constraints[j];
+            let (k, r) = (dot(&h.normal(), &u), -h.eval(&start));
+            if k > T::zero() {
+                high = Some(high.map_or(r / k, |s| s.min(r / k)));
+            } else if k < T::zero() {
+                low = Some(low.map_or(r / k, |s| s.max(r / k)));
+            } else if violates(h, &start) {
+                return None;
+            }
+        }
+
+        let two = T::one() + T::one();
+        let s = match (low, high) {
+            (Some(low), Some(high)) if low > high => {
+                let scale = low.abs().max(high.abs()).max(T::one());
+                match low - high > rounding::<T>() * scale {
+                    true => return None,
+                    false => (low + high) / two,
+                }
+            }
+            (low, high) => match dot(&self.direction, &u).partial_cmp(&T::zero()) {
+                Some(Ordering::Greater) => high.or(low),
+                Some(Ordering::Less) => low.or(high),
+                _ => match (low, high) {
+                    (Some(low), _) if low > T::zero() => Some(low),
+                    (_, Some(high)) if high < T::zero() => Some(high),
+                    _ => None,
+                },
+            }
+            .unwrap_or(T::zero()),
+        };
+        Some(Point::from2d(start.x + s * u.x, start.y + s * u.y))
+    }
+}
+
+/// The intersection of half-planes
+#[derive(Clone, Debug, PartialEq)]
+pub enum Region<T = f64> {
+    Empty,
+
+    /// a convex polygon, counterclockwise. It has less than three vertexes
+    /// when the region is a segment or a point
+    Bounded(Polygon<T>),
+
+    /// a region going to infinity, bounded by a convex chain: its corners,
+    /// counterclockwise, and the directions of the rays leaving the first and
+    /// the last one. Without corners, as for a half-plane or a strip, the rays
+    /// follow the first and the last boundary line, and they are null for the
+    /// whole plane. Build with `HalfPlaneIntersection::clipped` to get its part
+    /// in a box
+    Unbounded {
+        vertices: Vec<Point<T>>,
+        first_ray: Point<T>,
+        last_ray: Point<T>,
+    },
+}
+
+/// Intersection of half-planes: they are sorted by the angle of their boundary
+/// and added in turn to a deque of the ones bounding the region so far,
+/// removing from both ends the ones made redundant. It runs in O(n log n).
+///
+/// Empty regions are found first with a `LinearProgram`, and unbounded ones
+/// by the normals all lying in a half-plane. Their boundary is a chain from the
+/// half-plane after the gap between the normals to the one before it, so that
+/// only the back of the deque is cut. A step adds one half-plane
+pub struct HalfPlaneIntersection<T = f64> {
+    // the input, kept to replay the algorithm
+    half_planes: Vec<HalfPlane<T>>,
+
+    // the half-planes by angle, only the tightest of the parallel ones
+    sorted: Vec<HalfPlane<T>>,
+
+    // the index in sorted of the next one to add
+    next: usize,
+
+    // true when the normals leave a half turn, so that sorted starts after it
+    // and the boundary is a chain going to infinity at both ends
+    open: bool,
+
+    // the half-planes bounding the region so far, by angle
+    boundary: VecDeque<HalfPlane<T>>,
+
+    // the output of the algorithm, once known
+    region: Option<Region<T>>,
+}
+
+impl<T: Field> Algorithm for HalfPlaneIntersection<T> {
+    type Output = Region<T>;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while !self.is_done() {
+            self.step();
+        }
+
+        self.region.clone().unwrap_or(Region::Empty)
+    }
+
+    fn step(&mut self) {
+        if self.is_done() {
+            return;
+        }
+
+        let h = self.sorted[self.next];
+        while self.boundary.len() >= 2 && self.cuts_back(&h) {
+            self.boundary.pop_back();
+        }
+        while !self.open && self.boundary.len() >= 2 && self.cuts_front(&h) {
+            self.boundary.pop_front();
+        }
+        self.boundary.push_back(h);
+
+        self.next += 1;
+        if self.next == self.sorted.len() {
+            self.close();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.sorted = vec![];
+        self.next = 0;
+        self.open = false;
+        self.boundary = VecDeque::new();
+        self.region = None;
+
+        let zero = Point::from2d(T::zero(), T::zero());
+        if LinearProgram::build(self.half_planes.clone(), zero).calculate() == LpResult::Infeasible
+        {
+            self.region = Some(Region::Empty);
+            return;
+        }
+
+        let mut sorted: Vec<HalfPlane<T>> = self
+            .half_planes
+            .iter()
+            .filter(|h| !h.is_degenerate())
+            .copied()
+            .collect();
+        sorted.sort_by(|h, k| {
+            angle_cmp(&h.direction(), &k.direction()).then_with(|| tightness_cmp(h, k))
+        });
+        sorted.dedup_by(|h, k| angle_cmp(&h.direction(), &k.direction()) == Ordering::Equal);
+
+        // bounded only if no angle between consecutive normals reaches a half turn
+        let n = sorted.len();
+        if n == 0 {
+            let zero = Point::from2d(T::zero(), T::zero());
+            self.region = Some(Region::Unbounded {
+                vertices: vec![],
+                first_ray: zero,
+                last_ray: zero,
+            });
+            return;
+        }
+        let gap = (0..n).find(|&i| {
+            let (u, v) = (sorted[i].normal(), sorted[(i + 1) % n].normal());
+            let cross = u.x * v.y - u.y * v.x;
+            cross < T::zero() || (cross == T::zero() && dot(&u, &v) < T::zero())
+        });
+        self.open = n < 3 || gap.is_some();
+        if let Some(i) = gap {
+            sorted.rotate_left(i + 1);
+        }
+        self.sorted = sorted;
+    }
+}
+
+impl<T: Field> HalfPlaneIntersection<T> {
+    pub fn build(half_planes: Vec<HalfPlane<T>>) -> Self {
+        let mut algo = Self {
+            half_planes,
+            sorted: vec![],
+            next: 0,
+            open: false,
+            boundary: VecDeque::new(),
+            region: None,
+        };
+        algo.reset();
+        algo
+    }
+
+    /// like `build`, but rejects NaN and infinite coefficients
+    pub fn try_build(half_planes: Vec<HalfPlane<T>>) -> Result<Self> {
+        check_finite(half_planes.iter().map(|h| h.is_finite()))?;
+        Ok(Self::build(half_planes))
+    }
+
+    /// The kernel of a polygon: the points seeing all of it, on the left of
+    /// every edge once the polygon is counterclockwise
+    pub fn kernel(polygon: &Polygon<T>) -> Self {
+        let mut polygon = polygon.clone();
+        polygon.make_ccw();
+        let v = polygon.vertices();
+        let half_planes = (0..v.len())
+            .map(|i| HalfPlane::left_of(&v[i], &v[(i + 1) % v.len()]))
+            .collect();
+        Self::build(half_planes)
+    }
+
+    /// The intersection of the half-planes and of the box of the corners min
+    /// and max, never unbounded. It is empty if the region misses the box
+    pub fn clipped(mut half_planes: Vec<HalfPlane<T>>, min: &Point<T>, max: &Point<T>) -> Self {
+        let (zero, one) = (T::zero(), T::one());
+        half_planes.extend([
+            HalfPlane::new(-one, zero, -min.x),
+            HalfPlane::new(one, zero, max.x),
+            HalfPlane::new(zero, -one, -min.y),
+            HalfPlane::new(zero, one, max.y),
+        ]);
+        Self::build(half_planes)
+    }
+
+    /// true when every half-plane is added
+    pub fn is_done(&self) -> bool {
+        self.region.is_some()
+    }
+
+    /// the half-planes bounding the region so far, by angle
+    pub fn boundary(&self) -> &VecDeque<HalfPlane<T>> {
+        &self.boundary
+    }
+
+    /// true if the last corner of the boundary is outside h
+    fn cuts_back(&self, h: &HalfPlane<T>) -> bool {
+        let n = self.boundary.len();
+        self.boundary[n - 2]
+            .intersection(&self.boundary[n - 1])
+            .is_some_and(|p| violates(h, &p))
+    }
+
+    /// true if the first corner of the boundary is outside h
+    fn cuts_front(&self, h: &HalfPlane<T>) -> bool {
+        self.boundary[0]
+            .intersection(&self.boundary[1])
+            .is_some_and(|p| violates(h, &p))
+    }
+
+    /// removes the ends of the boundary cut by the other end, then makes the
+    /// polygon from the corners. An open boundary has no other end, the chain
+    /// of its corners goes to infinity along the first and the last line
+    fn close(&mut self) {
+        if self.open {
+            let n = self.boundary.len();
+            let mut vertices: Vec<Point<T>> = (1..n)
+                .filter_map(|i| self.boundary[i - 1].intersection(&self.boundary[i]))
+                .collect();
+            vertices.dedup();
+            let first_ray = self.boundary[0].direction();
+            self.region = Some(Region::Unbounded {
+                vertices,
+                first_ray: Point::from2d(-first_ray.x, -first_ray.y),
+                last_ray: self.boundary[n - 1].direction(),
+            });
+            return;
+        }
+
+        while self.boundary.len() >= 3 && self.cuts_back(&self.boundary[0]) {
+            self.boundary.pop_back();
+        }
+        while self.boundary.len() >= 3 && self.cuts_front(&self.boundary[self.boundary.len() - 1]) {
+            self.boundary.pop_front();
+        }
+
+        let n = self.boundary.len();
+        let mut corners: Vec<Point<T>> = (0..n)
+            .filter_map(|i| self.boundary[i].intersection(&self.boundary[(i + 1) % n]))
+            .collect();
+        corners.dedup();
+        if corners.len() > 1 && corners.first() == corners.last() {
+            corners.pop();
+        }
+        self.region = Some(Region::Bounded(Polygon::new(Points::from(corners))));
+    }
+}
+
+fn dot<T: Scalar>(u: &Point<T>, v: &Point<T>) -> T {
+    u.x * v.x + u.y * v.y
+}
+
+/// the relative rounding error allowed on a computed point, zero for exact
+/// scalars
+fn rounding<T: Scalar>() -> T {
+    T::from_f64(8.0) * T::epsilon()
+}
+
+/// true if p is outside h, beyond the rounding of its coordinates
+fn violates<T: Scalar>(h: &HalfPlane<T>, p: &Point<T>) -> bool {
+    let scale = (h.a * p.x).abs() + (h.b * p.y).abs() + h.c.abs();
+    h.eval(p) > rounding::<T>() * scale
+}
+
+/// the order of the directions by angle, starting from the positive x axis
+fn angle_cmp<T: Scalar>(u: &Point<T>, v: &Point<T>) -> Ordering {
+    let half = |p: &Point<T>| p.y < T::zero() || (p.y == T::zero() && p.x < T::zero());
+    half(u).cmp(&half(v)).then_with(|| {
+        let cross = u.x * v.y - u.y * v.x;
+        T::zero().partial_cmp(&cross).unwrap_or(Ordering::Equal)
+    })
+}
+
+/// for parallel half-planes, the one inside the other first
+fn tightness_cmp<T: Field>(h: &HalfPlane<T>, k: &HalfPlane<T>) -> Ordering {
+    match (h.boundary_point(), k.boundary_point()) {
+        (Some(p), _) if k.eval(&p) < T::zero() => Ordering::Less,
+        (_, Some(q)) if h.eval(&q) < T::zero() => Ordering::Greater,
+        _ => Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HalfPlaneIntersection, LinearProgram, LpResult, Region};
+    use crate::{
+        algorithms::algorithm::Algorithm,
+        error::GalmetryError,
+        geometry::{half_plane::HalfPlane, point::Point, polygon::Polygon, rational::Rational},
+    };
+    use rand::Rng;
+
+    fn r(v: i64) -> Rational {
+        Rational::from(v)
+    }
+
+    /// 0 <= x <= w and 0 <= y <= h
+    fn rectangle(w: f64, h: f64) -> Vec<HalfPlane> {
+        vec![
+            HalfPlane::new(-1.0, 0.0, 0.0),
+            HalfPlane::new(1.0, 0.0, w),
+            HalfPlane::new(0.0, -1.0, 0.0),
+            HalfPlane::new(0.0, 1.0, h),
+        ]
+    }
+
+    /// half-planes containing the origin, tangent to circles of random radius
+    fn random_half_planes(n: usize) -> Vec<HalfPlane> {
+        let mut rng = rand::thread_rng();
+        (0..n)
+            .map(|_| {
+                let angle: f64 = rng.gen_range(0.0..std::f64::consts::TAU);
+                HalfPlane::new(angle.cos(), angle.sin(), rng.gen_range(1.0..10.0))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn optimal_vertex() {
+        // x + y <= 4, x - y <= 2, x >= 0, y >= 0: maximum of x + 2y in (0, 4)
+        let constraints = vec![
+            HalfPlane::new(r(1), r(1), r(4)),
+            HalfPlane::new(r(1), r(-1), r(2)),
+            HalfPlane::new(r(-1), r(0), r(0)),
+            HalfPlane::new(r(0), r(-1), r(0)),
+        ];
+        let mut lp = LinearProgram::build(constraints.clone(), [r(1), r(2)]);
+        assert_eq!(lp.calculate(), LpResult::Optimal(Point::from2d(r(0), r(4))));
+
+        let mut lp = LinearProgram::build(constraints, [r(2), r(1)]);
+        assert_eq!(lp.calculate(), LpResult::Optimal(Point::from2d(r(3), r(1))));
+    }
+
+    #[test]
+    fn infeasible_and_unbounded() {
+        let infeasible = vec![
+            HalfPlane::new(1.0, 0.0, 0.0),
+            HalfPlane::new(-1.0, 0.0, -1.0),
+        ];
+        let mut lp = LinearProgram::build(infeasible, [0.0, 1.0]);
+        assert_eq!(lp.calculate(), LpResult::Infeasible);
+
+        // a strip going up, maximizing y
+        let strip = vec![
+            HalfPlane::new(1.0, 0.0, 1.0),
+            HalfPlane::new(-1.0, 0.0, 1.0),
+        ];
+        let mut lp = LinearProgram::build(strip.clone(), [0.0, 1.0]);
+        assert_eq!(lp.calculate(), LpResult::Unbounded);
+
+        // the strip is empty
+        let mut empty = strip;
+        empty.push(HalfPlane::new(-1.0, 0.0, -2.0));
+        let mut lp = LinearProgram::build(empty, [0.0, 1.0]);
+        assert_eq!(lp.calculate(), LpResult::Infeasible);
+
+        // two opposite constraints, only the direction between them improves
+        // the objective: rounding must not hide that they exclude each other
+        let opposite = vec![
+            HalfPlane::new(2.0, 3.0, -2.0),
+            HalfPlane::new(-2.0, -3.0, -2.0),
+        ];
+        let mut lp = LinearProgram::build(opposite, [-2.0, 2.0]);
+        assert_eq!(lp.calculate(), LpResult::Infeasible);
+        let line = vec![
+            HalfPlane::new(2.0, 3.0, 2.0),
+            HalfPlane::new(-2.0, -3.0, -2.0),
+        ];
+        let mut lp = LinearProgram::build(line, [-2.0, 2.0]);
+        assert_eq!(lp.calculate(), LpResult::Unbounded);
+
+        let mut lp = LinearProgram::build(vec![HalfPlane::new(0.0, 0.0, -1.0)], [0.0, 0.0]);
+        assert_eq!(lp.calculate(), LpResult::Infeasible);
+        let mut lp = LinearProgram::build(vec![], [1.0, 0.0]);
+        assert_eq!(lp.calculate(), LpResult::Unbounded);
+    }
+
+    #[test]
+    fn bounded_objective_on_an_unbounded_edge() {
+        // y <= 1 and x >= 0: the maximum of y is reached on a whole ray
+        let constraints = vec![
+            HalfPlane::new(0.0, 1.0, 1.0),
+            HalfPlane::new(-1.0, 0.0, 0.0),
+        ];
+        let mut lp = LinearProgram::build(constraints.clone(), [0.0, 1.0]);
+        match lp.calculate() {
+            LpResult::Optimal(p) => {
+                assert_eq!(p.y, 1.0);
+                assert!(constraints.iter().all(|h| h.contains(&p)));
+            }
+            result => panic!("{:?}", result),
+        }
+    }
+
+    #[test]
+    fn null_objective_finds_a_feasible_point() {
+        let mut constraints = rectangle(2.0, 1.0);
+        constraints.push(HalfPlane::new(-1.0, -1.0, -2.5));
+        let mut lp = LinearProgram::build(constraints.clone(), [0.0, 0.0]);
+        match lp.calculate() {
+            LpResult::Optimal(p) => assert!(constraints.iter().all(|h| h.contains(&p))),
+            result => panic!("{:?}", result),
+        }
+    }
+
+    #[test]
+    fn random_programs() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let constraints = random_half_planes(40);
+            let c = Point::from2d(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+            let best = match LinearProgram::build(constraints.clone(), c).calculate() {
+                LpResult::Optimal(p) => p,
+                result => {
+                    // bounded only if the tangents surround the origin
+                    assert_eq!(result, LpResult::Unbounded);
+                    continue;
+                }
+            };
+            let value = c.x * best.x + c.y * best.y;
+            assert!(constraints.iter().all(|h| h.eval(&best) < 1e-9));
+
+            // no vertex of the region is better
+            for (i, h) in constraints.iter().enumerate() {
+                for k in constraints[i + 1..].iter() {
+                    if let Some(p) = h.intersection(k) {
+                        if constraints.iter().all(|h| h.eval(&p) < 1e-9) {
+                            assert!(c.x * p.x + c.y * p.y <= value + 1e-9);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn linear_program_steps() {
+        let mut lp = LinearProgram::build(random_half_planes(20), [1.0, 1.0]);
+        let mut steps = 0;
+        while !lp.is_done() {
+            assert!(!lp.added().is_empty());
+            lp.step();
+            steps += 1;
+        }
+        match lp.result() {
+            LpResult::Optimal(_) => assert_eq!(lp.added().len(), 20),
+            result => assert_eq!(result, &LpResult::Unbounded),
+        }
+        assert!(steps <= 19);
+    }
+
+    #[test]
+    fn rectangle_intersection() {
+        let mut half_planes = rectangle(4.0, 2.0);
+        // redundant and parallel half-planes
+        half_planes.push(HalfPlane::new(1.0, 0.0, 5.0));
+        half_planes.push(HalfPlane::new(1.0, 1.0, 10.0));
+        let region = HalfPlaneIntersection::build(half_planes).calculate();
+
+        let expected: Polygon = vec![[0.0, 0.0], [4.0, 0.0], [4.0, 2.0], [0.0, 2.0]].into();
+        match region {
+            Region::Bounded(polygon) => {
+                assert!(polygon.is_ccw());
+                assert_eq!(polygon.len(), 4);
+                assert_eq!(polygon.area(), 8.0);
+                assert!(expected
+                    .vertices()
+                    .iter()
+                    .all(|v| polygon.vertices().iter().any(|p| p == v)));
+            }
+            region => panic!("{:?}", region),
+        }
+    }
+
+    #[test]
+    fn empty_unbounded_and_degenerate_regions() {
+        let mut empty = rectangle(1.0, 1.0);
+        empty.push(HalfPlane::new(-1.0, -1.0, -3.0));
+        assert_eq!(
+            HalfPlaneIntersection::build(empty).calculate(),
+            Region::Empty
+        );
+
+        // a half-strip going up, down its left side and up its right one
+        let mut open = rectangle(1.0, 1.0);
+        open.pop();
+        assert_eq!(
+            HalfPlaneIntersection::build(open).calculate(),
+            Region::Unbounded {
+                vertices: vec![Point::from2d(0.0, 0.0), Point::from2d(1.0, 0.0)],
+                first_ray: Point::from2d(0.0, 1.0),
+                last_ray: Point::from2d(0.0, 1.0),
+            }
+        );
+
+        // a cup with a wall on its right, and two lines under it
+        let cup = vec![
+            HalfPlane::new(1.0, 0.0, 5.0),
+            HalfPlane::new(0.0, -1.0, 0.0),
+            HalfPlane::new(1.0, -1.0, 1.0),
+            HalfPlane::new(-1.0, -1.0, 10.0),
+            HalfPlane::new(-0.5, -1.0, 10.0),
+            HalfPlane::new(2.0, -1.0, 20.0),
+        ];
+        assert_eq!(
+            HalfPlaneIntersection::build(cup).calculate(),
+            Region::Unbounded {
+                vertices: vec![
+                    Point::from2d(-10.0, 0.0),
+                    Point::from2d(1.0, 0.0),
+                    Point::from2d(5.0, 4.0)
+                ],
+                first_ray: Point::from2d(-1.0, 1.0),
+                last_ray: Point::from2d(0.0, 1.0),
+            }
+        );
+
+        // a half-plane, a strip and the whole plane have no corners
+        assert_eq!(
+            HalfPlaneIntersection::build(vec![HalfPlane::new(0.0, 1.0, 1.0)]).calculate(),
+            Region::Unbounded {
+                vertices: vec![],
+                first_ray: Point::from2d(1.0, 0.0),
+                last_ray: Point::from2d(-1.0, 0.0),
+            }
+        );
+        let strip = vec![
+            HalfPlane::new(0.0, 1.0, 1.0),
+            HalfPlane::new(0.0, -1.0, 1.0),
+        ];
+        match HalfPlaneIntersection::build(strip).calculate() {
+            Region::Unbounded { vertices, .. } => assert!(vertices.is_empty()),
+            region => panic!("{:?}", region),
+        }
+        let zero = Point::from2d(0.0, 0.0);
+        assert_eq!(
+            HalfPlaneIntersection::build(vec![]).calculate(),
+            Region::Unbounded {
+                vertices: vec![],
+                first_ray: zero,
+                last_ray: zero,
+            }
+        );
+
+        // the open region clipped to a box
+        let (min, max) = (Point::from2d(-1.0, -1.0), Point::from2d(3.0, 3.0));
+        let mut open = rectangle(1.0, 1.0);
+        open.pop();
+        match HalfPlaneIntersection::clipped(open, &min, &max).calculate() {
+            Region::Bounded(polygon) => assert_eq!(polygon.area(), 3.0),
+            region => panic!("{:?}", region),
+        }
+        let far = Point::from2d(5.0, 5.0);
+        assert_eq!(
+            HalfPlaneIntersection::clipped(rectangle(1.0, 1.0), &far, &far).calculate(),
+            Region::Empty
+        );
+
+        // a segment from (0, 0) to (1, 0)
+        let segment = rectangle(1.0, 0.0);
+        match HalfPlaneIntersection::build(segment).calculate() {
+            Region::Bounded(polygon) => assert_eq!(polygon.len(), 2),
+            region => panic!("{:?}", region),
+        }
+    }
+
+    #[test]
+    fn polygon_kernel() {
+        // a star of Rational points, with its kernel the central square
+        let star: Polygon<Rational> = Polygon::new(
+            vec![
+                [r(0), r(-3)],
+                [r(1), r(-1)],
+                [r(3), r(0)],
+                [r(1), r(1)],
+                [r(0), r(3)],
+                [r(-1), r(1)],
+                [r(-3), r(0)],
+                [r(-1), r(-1)],
+            ]
+            .into(),
+        );
+        match HalfPlaneIntersection::kernel(&star).calculate() {
+            Region::Bounded(kernel) => {
+                assert!(kernel.is_ccw());
+                assert!(kernel.len() >= 4);
+                assert_eq!(
+                    kernel
+                        .vertices()
+                        .iter()
+                        .filter(|v| v.x == r(1) || v.x == r(-1))
+                        .count(),
+                    4
+                );
+            }
+            region => panic!("{:?}", region),
+        }
+
+        // an L has the corner square as its kernel, a comb has none
+        let l: Polygon = vec![
+            [0.0, 0.0],
+            [2.0, 0.0],
+            [2.0, 1.0],
+            [1.0, 1.0],
+            [1.0, 2.0],
+            [0.0, 2.0],
+        ]
+        .into();
+        match HalfPlaneIntersection::kernel(&l).calculate() {
+            Region::Bounded(kernel) => assert_eq!(kernel.area(), 1.0),
+            region => panic!("{:?}", region),
+        }
+        let comb: Polygon = vec![
+            [0.0, 0.0],
+            [5.0, 0.0],
+            [5.0, 3.0],
+            [4.0, 1.0],
+            [3.0, 3.0],
+            [2.0, 1.0],
+            [1.0, 3.0],
+            [0.0, 1.0],
+        ]
+        .into();
+        assert_eq!(
+            HalfPlaneIntersection::kernel(&comb).calculate(),
+            Region::Empty
+        );
+    }
+
+    #[test]
+    fn random_intersections() {
+        let mut rng = rand::thread_rng();
+        for i in 0..50 {
+            // few half-planes leave an unbounded region more often than not
+            let half_planes = random_half_planes(if i % 2 == 0 { 3 } else { 30 });
+            let polygon = match HalfPlaneIntersection::build(half_planes.clone()).calculate() {
+                Region::Bounded(polygon) => polygon,
+                Region::Unbounded {
+                    vertices,
+                    first_ray,
+                    last_ray,
+                } => {
+                    // the chain and its rays stay in every half-plane
+                    for h in half_planes.iter() {
+                        assert!(vertices.iter().all(|v| h.eval(v) < 1e-9));
+                        let normal = h.normal();
+                        for ray in [first_ray, last_ray] {
+                            assert!(normal.x * ray.x + normal.y * ray.y < 1e-9);
+                        }
+                    }
+                    continue;
+                }
+                region => panic!("{:?}", region),
+            };
+            assert!(polygon.is_ccw());
+            for v in polygon.vertices().iter() {
+                assert!(half_planes.iter().all(|h| h.eval(v) < 1e-9));
+            }
+
+            // the best vertex is the optimum of a linear program
+            let c = Point::from2d(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+            let best = polygon
+                .vertices()
+                .iter()
+                .map(|v| c.x * v.x + c.y * v.y)
+                .fold(f64::MIN, f64::max);
+            match LinearProgram::build(half_planes, c).calculate() {
+                LpResult::Optimal(p) => assert!((c.x * p.x + c.y * p.y - best).abs() < 1e-9),
+                result => panic!("{:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn try_linear_program() {
+        let mut constraints = rectangle(1.0, 1.0);
+        constraints[2].c = f64::NAN;
+        assert_eq!(
+            LinearProgram::try_build(constraints.clone(), [1.0, 0.0]).err(),
+            Some(GalmetryError::NonFiniteCoordinate { index: 2 })
+        );
+        assert_eq!(
+            LinearProgram::try_build(rectangle(1.0, 1.0), [f64::INFINITY, 0.0]).err(),
+            Some(GalmetryError::NonFiniteCoordinate { index: 4 })
+        );
+        assert_eq!(
+            HalfPlaneIntersection::try_build(constraints).err(),
+            Some(GalmetryError::NonFiniteCoordinate { index: 2 })
+        );
+    }
+}
@@ -0,0 +1,422 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+};
+
+use super::{algorithm::Algorithm, sweep_plane::SweepPlane};
+use crate::{
+    error::{check_finite, GalmetryError, Result},
+    geometry::{
+        dcel::Dcel, point::Point, points::Points, polygon::Polygon, scalar::Field, segment::Segment,
+    },
+};
+
+/// The overlay of two planar subdivisions, each given as closed rings of
+/// points bounding a labelled face. A point belongs to the face of the
+/// innermost ring around it, or to no face outside every ring.
+///
+/// The edges of all the rings are split in their intersections, found with
+/// `SweepPlane`, and the pieces make a `Dcel`. Its faces are then labelled
+/// from the unbounded one: crossing an edge enters or leaves the rings it
+/// belongs to, and the label of a face is the one of the smallest ring it is
+/// in, for each subdivision. A step labels the neighbors of one face
+pub struct MapOverlay<T = f64, L = ()> {
+    // the input, kept to replay the algorithm
+    first: Vec<(Points<T>, L)>,
+    second: Vec<(Points<T>, L)>,
+
+    // the overlay, with the labels found so far
+    dcel: Dcel<T, [Option<L>; 2]>,
+
+    // for each edge, smaller vertex first, the rings of each subdivision along it
+    rings: HashMap<(usize, usize), [BTreeSet<usize>; 2]>,
+
+    // the doubled area of each ring, for each subdivision
+    areas: [Vec<T>; 2],
+
+    // for each face reached, the rings of each subdivision around it
+    inside: Vec<Option<[BTreeSet<usize>; 2]>>,
+
+    // the faces reached whose neighbors are still to label
+    queue: VecDeque<usize>,
+}
+
+impl<T: Field, L: Clone> Algorithm for MapOverlay<T, L> {
+    type Output = Dcel<T, [Option<L>; 2]>;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while !self.is_done() {
+            self.step();
+        }
+
+        self.dcel.clone()
+    }
+
+    /// fails if the pieces of the edges do not make a valid subdivision, as
+    /// the rounding of an intersection can do
+    fn try_calculate(&mut self) -> Result<Self::Output> {
+        let overlay = self.calculate();
+        overlay.validate()?;
+        Ok(overlay)
+    }
+
+    fn step(&mut self) {
+        let f = match self.queue.pop_front() {
+            Some(f) => f,
+            None => return,
+        };
+        let inside = self.inside[f].clone().unwrap_or_default();
+
+        let face = self.dcel.face(f);
+        let half_edges: Vec<usize> = face
+            .outer
+            .iter()
+            .chain(face.inner.iter())
+            .flat_map(|&e| self.dcel.cycle(e))
+            .collect();
+        for h in half_edges {
+            let g = self.dcel.half_edge(self.dcel.twin(h)).face;
+            if self.inside[g].is_some() {
+                continue;
+            }
+
+            let rings = &self.rings[&self.edge(h)];
+            let entered = [0, 1].map(|s| {
+                inside[s]
+                    .symmetric_difference(&rings[s])
+                    .copied()
+                    .collect::<BTreeSet<usize>>()
+            });
+            let labels = [0, 1].map(|s| self.label(s, &entered[s]));
+            self.dcel.set_attribute(g, labels);
+            self.inside[g] = Some(entered);
+            self.queue.push_back(g);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.areas = [&self.first, &self.second].map(|subdivision| {
+            subdivision
+                .iter()
+                .map(|(ring, _)| Polygon::new(ring.clone()).doubled_signed_area().abs())
+                .collect()
+        });
+
+        let mut segments = vec![];
+        let mut owners = vec![];
+        for (s, subdivision) in [&self.first, &self.second].iter().enumerate() {
+            for (r, (ring, _)) in subdivision.iter().enumerate() {
+                for i in 0..ring.len() {
+                    let (p, q) = (ring[i], ring[(i + 1) % ring.len()]);
+                    if p != q {
+                        segments.push(Segment::new(p, q));
+                        owners.push((s, r));
+                    }
+                }
+            }
+        }
+
+        // each edge is split in its endpoints and intersections
+        let mut splits: Vec<Vec<Point<T>>> = segments
+            .iter()
+            .map(|seg| vec![seg.start, seg.end])
+            .collect();
+        let intersections = SweepPlane::build(segments.clone()).calculate();
+        for (p, intersection) in intersections.iter() {
+            for i in intersection.segments() {
+                splits[i].push(*p);
+            }
+        }
+
+        let mut points = Points::new();
+        let mut places: BTreeMap<Point<T>, usize> = BTreeMap::new();
+        self.rings = HashMap::new();
+        for ((seg, mut split), (s, r)) in segments.iter().zip(splits).zip(owners) {
+            let direction = seg.end - seg.start;
+            let along = |p: &Point<T>| {
+                (p.x - seg.start.x) * direction.x + (p.y - seg.start.y) * direction.y
+            };
+            split.sort_by(|p, q| along(p).partial_cmp(&along(q)).unwrap_or(Ordering::Equal));
+
+            let ids: Vec<usize> = split
+                .iter()
+                .map(|p| {
+                    *places.entry(*p).or_insert_with(|| {
+                        points.push(*p);
+                        points.len() - 1
+                    })
+                })
+                .collect();
+            for pair in ids.windows(2) {
+                if pair[0] != pair[1] {
+                    let key = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+                    // a ring passing twice along an edge does not cross it
+                    let rings = &mut self.rings.entry(key).or_default()[s];
+                    if !rings.remove(&r) {
+                        rings.insert(r);
+                    }
+                }
+            }
+        }
+
+        let edges: Vec<(usize, usize)> = self.rings.keys().copied().collect();
+        self.dcel = Dcel::from_edges(points, &edges);
+        self.inside = vec![None; self.dcel.faces().len()];
+        self.inside[0] = Some(Default::default());
+        self.queue = VecDeque::from([self.dcel.unbounded_face()]);
+    }
+}
+
+impl<T: Field, L: Clone> MapOverlay<T, L> {
+    pub fn build(first: Vec<(Points<T>, L)>, second: Vec<(Points<T>, L)>) -> Self {
+        let mut algo = Self {
+            first,
+            second,
+            dcel: Dcel::new(),
+            rings: HashMap::new(),
+            areas: [vec![], vec![]],
+            inside: vec![],
+            queue: VecDeque::new(),
+        };
+        algo.reset();
+        algo
+    }
+
+    /// like `build`, but rejects NaN and infinite coordinates and rings of less
+    /// than three points. The index of an invalid point counts the points of
+    /// all the rings, the first subdivision before the second one
+    pub fn try_build(first: Vec<(Points<T>, L)>, second: Vec<(Points<T>, L)>) -> Result<Self> {
+        let rings = first.iter().chain(second.iter()).map(|(ring, _)| ring);
+        if let Some(short) = rings.clone().find(|ring| ring.len() < 3) {
+            return Err(GalmetryError::InsufficientInput {
+                required: 3,
+                found: short.len(),
+            });
+        }
+        check_finite(rings.flat_map(|ring| ring.iter()).map(|p| p.is_finite()))?;
+        Ok(Self::build(first, second))
+    }
+
+    /// true when every face is labelled
+    pub fn is_done(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// the overlay, with the faces not labelled yet having no label
+    pub fn dcel(&self) -> &Dcel<T, [Option<L>; 2]> {
+        &self.dcel
+    }
+
+    /// the faces labelled so far
+    pub fn labelled(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.inside.len()).filter(|&f| self.inside[f].is_some())
+    }
+
+    /// the edge under the half-edge, smaller vertex first
+    fn edge(&self, h: usize) -> (usize, usize) {
+        let a = self.dcel.half_edge(h).origin;
+        let b = self.dcel.half_edge(self.dcel.twin(h)).origin;
+        (a.min(b), a.max(b))
+    }
+
+    /// the label of the smallest of the rings of the subdivision
+    fn label(&self, s: usize, rings: &BTreeSet<usize>) -> Option<L> {
+        let areas = &self.areas[s];
+        rings
+            .iter()
+            .min_by(|&&r, &&q| areas[r].partial_cmp(&areas[q]).unwrap_or(Ordering::Equal))
+            .map(|&r| [&self.first, &self.second][s][r].1.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::MapOverlay;
+    use crate::{
+        algorithms::algorithm::Algorithm,
+        error::GalmetryError,
+        geometry::{
+            dcel::Dcel, point::Point, points::Points, polygon::Polygon, rational::Rational,
+        },
+    };
+
+    fn ring(points: Vec<[f64; 2]>) -> Points {
+        points.into()
+    }
+
+    fn square(min: f64, max: f64) -> Points {
+        ring(vec![[min, min], [max, min], [max, max], [min, max]])
+    }
+
+    // the labels of the bounded faces, with their areas
+    fn areas<L: Clone + Ord>(overlay: &Dcel<f64, [Option<L>; 2]>) -> BTreeMap<[Option<L>; 2], f64> {
+        let mut areas = BTreeMap::new();
+        for f in 1..overlay.faces().len() {
+            let mut area = overlay.boundary(f).unwrap().area();
+            for &hole in overlay.face(f).inner.iter() {
+                let hole: Vec<Point> = overlay
+                    .cycle(hole)
+                    .iter()
+                    .map(|&h| *overlay.origin(h))
+                    .collect();
+                area -= Polygon::new(Points::from(hole)).area();
+            }
+            *areas.entry(overlay.attribute(f).clone()).or_insert(0.0) += area;
+        }
+        areas
+    }
+
+    #[test]
+    fn overlapping_squares() {
+        let overlay = MapOverlay::build(
+            vec![(square(0.0, 2.0), "zone")],
+            vec![(square(1.0, 3.0), "flood")],
+        )
+        .calculate();
+
+        assert_eq!(overlay.validate(), Ok(()));
+        assert_eq!(overlay.vertices().len(), 10);
+        assert_eq!(overlay.faces().len(), 4);
+        assert_eq!(overlay.attribute(0), &[None, None]);
+        assert_eq!(
+            areas(&overlay),
+            BTreeMap::from([
+                ([None, Some("flood")], 3.0),
+                ([Some("zone"), None], 3.0),
+                ([Some("zone"), Some("flood")], 1.0),
+            ])
+        );
+    }
+
+    #[test]
+    fn nested_rings() {
+        // a district with a park inside it, a flood area covering part of both
+        let overlay = MapOverlay::build(
+            vec![(square(0.0, 10.0), "district"), (square(4.0, 6.0), "park")],
+            vec![(
+                ring(vec![[5.0, -1.0], [11.0, -1.0], [11.0, 11.0], [5.0, 11.0]]),
+                "flood",
+            )],
+        )
+        .calculate();
+
+        assert_eq!(overlay.validate(), Ok(()));
+        assert_eq!(
+            areas(&overlay),
+            BTreeMap::from([
+                ([None, Some("flood")], 22.0),
+                ([Some("district"), None], 48.0),
+                ([Some("district"), Some("flood")], 48.0),
+                ([Some("park"), None], 2.0),
+                ([Some("park"), Some("flood")], 2.0),
+            ])
+        );
+    }
+
+    #[test]
+    fn adjacent_faces_and_shared_edges() {
+        // two parcels sharing an edge, overlaid with a map made of the same edges
+        let left = square(0.0, 2.0);
+        let right = ring(vec![[2.0, 0.0], [4.0, 0.0], [4.0, 2.0], [2.0, 2.0]]);
+        let overlay = MapOverlay::build(
+            vec![(left.clone(), 1), (right.clone(), 2)],
+            vec![(left, 3), (right, 4)],
+        )
+        .calculate();
+
+        assert_eq!(overlay.validate(), Ok(()));
+        assert_eq!(overlay.faces().len(), 3);
+        assert_eq!(
+            areas(&overlay),
+            BTreeMap::from([([Some(1), Some(3)], 4.0), ([Some(2), Some(4)], 4.0)])
+        );
+    }
+
+    #[test]
+    fn disjoint_maps() {
+        let overlay = MapOverlay::build(
+            vec![(square(0.0, 1.0), 'a')],
+            vec![(square(2.0, 3.0), 'b'), (square(5.0, 6.0), 'c')],
+        )
+        .calculate();
+
+        assert_eq!(overlay.faces().len(), 4);
+        assert_eq!(overlay.face(0).inner.len(), 3);
+        assert_eq!(
+            areas(&overlay),
+            BTreeMap::from([
+                ([None, Some('b')], 1.0),
+                ([None, Some('c')], 1.0),
+                ([Some('a'), None], 1.0),
+            ])
+        );
+    }
+
+    #[test]
+    fn overlay_steps() {
+        let mut algo = MapOverlay::build(vec![(square(0.0, 2.0), 1)], vec![(square(1.0, 3.0), 2)]);
+        assert_eq!(algo.labelled().count(), 1);
+
+        algo.step();
+        assert_eq!(algo.labelled().count(), 3);
+        assert!(!algo.is_done());
+
+        while !algo.is_done() {
+            algo.step();
+        }
+        assert_eq!(algo.labelled().count(), 4);
+        let overlay = algo.dcel().clone();
+        assert_eq!(overlay, algo.calculate());
+    }
+
+    #[test]
+    fn rational_overlay() {
+        let triangle = |points: [[i64; 2]; 3]| {
+            Points::from(
+                points
+                    .iter()
+                    .map(|p| Point::from2d(Rational::from(p[0]), Rational::from(p[1])))
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let overlay = MapOverlay::build(
+            vec![(triangle([[0, 0], [3, 0], [0, 3]]), "a")],
+            vec![(triangle([[1, -1], [2, 2], [-1, 1]]), "b")],
+        )
+        .try_calculate()
+        .unwrap();
+
+        // the triangles cross in four points and touch in the origin
+        assert_eq!(overlay.vertices().len(), 10);
+        let both = (1..overlay.faces().len())
+            .filter(|&f| overlay.attribute(f) == &[Some("a"), Some("b")])
+            .count();
+        assert_eq!(both, 1);
+        assert_eq!(overlay.faces().len(), 7);
+    }
+
+    #[test]
+    fn try_map_overlay() {
+        assert_eq!(
+            MapOverlay::<f64, ()>::try_build(
+                vec![(square(0.0, 1.0), ())],
+                vec![(ring(vec![[0.0, 0.0], [1.0, 1.0]]), ())],
+            )
+            .err(),
+            Some(GalmetryError::InsufficientInput {
+                required: 3,
+                found: 2
+            })
+        );
+
+        let mut invalid = square(0.0, 1.0);
+        invalid[1].x = f64::NAN;
+        assert_eq!(
+            MapOverlay::try_build(vec![(square(0.0, 1.0), ())], vec![(invalid, ())]).err(),
+            Some(GalmetryError::NonFiniteCoordinate { index: 5 })
+        );
+    }
+}
@@ -1,3 +1,13 @@
 pub mod algorithm;
+pub mod art_gallery;
+pub mod constrained_delaunay;
 pub mod convex_hull;
+pub mod delaunay;
+pub mod ear_clipping;
+pub mod linear_programming;
+pub mod map_overlay;
+pub mod quadtree_mesh;
 pub mod sweep_plane;
+pub mod trapezoidal_map;
+pub mod triangulation;
+pub mod voronoi;
@@ -0,0 +1,514 @@
+use std::collections::BTreeMap;
+
+use super::algorithm::Algorithm;
+use crate::{
+    error::{check_finite, GalmetryError, Result},
+    geometry::{
+        mesh::TriangleMesh,
+        point::Point,
+        points::Points,
+        polygon::Polygon,
+        predicates::Orientation,
+        quadtree::{Direction, Quadtree},
+        scalar::{Field, Scalar},
+        segment::Segment,
+    },
+};
+
+/// A triangular mesh of a square, the triangles inside the polygons told
+/// apart from the others
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptiveMesh<T = f64> {
+    pub vertices: Points<T>,
+
+    pub mesh: TriangleMesh,
+
+    /// for each triangle, true if it is inside an odd number of polygons: in
+    /// a polygon and not in a hole
+    pub inside: Vec<bool>,
+}
+
+impl<T: Scalar> AdaptiveMesh<T> {
+    /// the triangles inside the polygons, the others removed
+    pub fn interior(&self) -> TriangleMesh {
+        TriangleMesh::new(
+            self.mesh
+                .triangles()
+                .iter()
+                .zip(self.inside.iter())
+                .filter(|(_, &inside)| inside)
+                .map(|(t, _)| *t)
+                .collect(),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    Refine,
+    Balance,
+    Triangulate,
+}
+
+/// Non-uniform mesh generation with a quadtree (de Berg et al. chapter 14).
+///
+/// The domain is the square [0, size]², size being a power of 2, holding
+/// polygons with integer coordinates and edges that are axis-parallel or
+/// diagonal. The squares meeting the boundary of a polygon are split down to
+/// unit size, so the mesh is fine near the boundaries and coarse away from
+/// them; the quadtree is then balanced, and each leaf is triangulated. A unit
+/// square crossed by a diagonal edge is split along it, a leaf without
+/// smaller neighbors along a diagonal, and any other leaf around a vertex in
+/// its center, connected to its corners and to the corners of its neighbors on
+/// its sides. The mesh is conforming, with angles of 45 and 90 degrees only,
+/// and the polygon edges are made of mesh edges.
+///
+/// A step splits or triangulates one square
+pub struct QuadtreeMesh<T = f64> {
+    // the input, kept to replay the algorithm
+    polygons: Vec<Polygon<T>>,
+    size: T,
+
+    tree: Quadtree<T>,
+    phase: Phase,
+
+    // the squares still to handle in this phase, the next one last
+    pending: Vec<usize>,
+
+    // the vertexes and the index of each one
+    vertices: Points<T>,
+    indexes: BTreeMap<Point<T>, usize>,
+
+    triangles: Vec<[usize; 3]>,
+}
+
+impl<T: Field> Algorithm for QuadtreeMesh<T> {
+    type Output = AdaptiveMesh<T>;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while !self.is_done() {
+            self.step();
+        }
+
+        self.result()
+    }
+
+    fn step(&mut self) {
+        let Some(node) = self.pending.pop() else {
+            // the next phase
+            match self.phase {
+                Phase::Refine => self.phase = Phase::Balance,
+                Phase::Balance => self.phase = Phase::Triangulate,
+                Phase::Triangulate => return,
+            }
+            self.pending = self.tree.leaves();
+            self.pending.reverse();
+            return;
+        };
+
+        match self.phase {
+            Phase::Refine => {
+                if self.tree.node(node).size > T::one() && self.crossed(node) {
+                    self.pending.extend(self.tree.split(node));
+                }
+            }
+            Phase::Balance => {
+                let pending = self.tree.balance_leaf(node);
+                self.pending.extend(pending);
+            }
+            Phase::Triangulate => self.triangulate(node),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.tree = Quadtree::from_square(Point::from2d(T::zero(), T::zero()), self.size);
+        self.phase = Phase::Refine;
+        self.pending = vec![0];
+        self.vertices = Points::new();
+        self.indexes = BTreeMap::new();
+        self.triangles = vec![];
+    }
+}
+
+impl<T: Field> QuadtreeMesh<T> {
+    pub fn build(polygons: Vec<Polygon<T>>, size: T) -> Self {
+        let mut algo = Self {
+            tree: Quadtree::from_square(Point::from2d(T::zero(), T::zero()), size),
+            polygons,
+            size,
+            phase: Phase::Refine,
+            pending: vec![],
+            vertices: Points::new(),
+            indexes: BTreeMap::new(),
+            triangles: vec![],
+        };
+        algo.reset();
+        algo
+    }
+
+    /// like `build`, but rejects a size that is not a power of 2, polygons
+    /// with fewer than 3 vertexes, NaN and infinite coordinates, vertexes that
+    /// are not integer points of the square, and edges that are neither
+    /// axis-parallel nor diagonal
+    pub fn try_build(polygons: Vec<Polygon<T>>, size: T) -> Result<Self> {
+        let side = size.to_f64();
+        if !(side >= 1.0 && side.fract() == 0.0 && (side as u64).is_power_of_two()) {
+            return Err(GalmetryError::DegenerateInput(
+                "the size is not a power of 2",
+            ));
+        }
+        for polygon in polygons.iter() {
+            if polygon.len() < 3 {
+                return Err(GalmetryError::InsufficientInput {
+                    required: 3,
+                    found: polygon.len(),
+                });
+            }
+        }
+
+        let vertices = polygons
+            .iter()
+            .flat_map(|polygon| polygon.vertices().iter());
+        check_finite(vertices.clone().map(|p| p.is_finite()))?;
+        for p in vertices {
+            let integer = |v: T| T::from_f64(v.to_f64().round()) == v;
+            if !(integer(p.x) && integer(p.y)) {
+                return Err(GalmetryError::DegenerateInput(
+                    "the vertexes are not integer points",
+                ));
+            }
+            let within = |v: T| T::zero() <= v && v <= size;
+            if !(within(p.x) && within(p.y)) {
+                return Err(GalmetryError::DegenerateInput(
+                    "the vertexes are outside the square",
+                ));
+            }
+        }
+
+        for edge in polygons.iter().flat_map(|polygon| polygon.edges()) {
+            let (dx, dy) = (edge.end.x - edge.start.x, edge.end.y - edge.start.y);
+            if !(dx == T::zero() || dy == T::zero() || dx.abs() == dy.abs()) {
+                return Err(GalmetryError::DegenerateInput(
+                    "the edges are neither axis-parallel nor diagonal",
+                ));
+            }
+        }
+        Ok(Self::build(polygons, size))
+    }
+
+    /// true when every leaf is triangulated
+    pub fn is_done(&self) -> bool {
+        self.phase == Phase::Triangulate && self.pending.is_empty()
+    }
+
+    /// the quadtree built so far
+    pub fn quadtree(&self) -> &Quadtree<T> {
+        &self.tree
+    }
+
+    /// the triangles made so far, counterclockwise
+    pub fn triangles(&self) -> &Vec<[usize; 3]> {
+        &self.triangles
+    }
+
+    pub fn vertices(&self) -> &Points<T> {
+        &self.vertices
+    }
+
+    /// the mesh of the triangles made so far
+    pub fn result(&self) -> AdaptiveMesh<T> {
+        let three = T::one() + T::one() + T::one();
+        let inside = self
+            .triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.map(|v| self.vertices[v]);
+                let centroid = Point::from2d((a.x + b.x + c.x) / three, (a.y + b.y + c.y) / three);
+                let count = self
+                    .polygons
+                    .iter()
+                    .filter(|polygon| encloses(polygon, &centroid))
+                    .count();
+                count % 2 == 1
+            })
+            .collect();
+
+        AdaptiveMesh {
+            vertices: self.vertices.clone(),
+            mesh: TriangleMesh::new(self.triangles.clone()),
+            inside,
+        }
+    }
+
+    /// true if the boundary of a polygon meets the closed square
+    fn crossed(&self, node: usize) -> bool {
+        let square = self.tree.node(node);
+        let corners = square.corners();
+        let sides: Vec<Segment<T>> = (0..4)
+            .map(|i| Segment::new(corners[i], corners[(i + 1) % 4]))
+            .collect();
+
+        self.polygons
+            .iter()
+            .flat_map(|polygon| polygon.edges())
+            .any(|edge| {
+                square.contains(&edge.start) || sides.iter().any(|side| side.intersects(&edge))
+            })
+    }
+
+    fn triangulate(&mut self, node: usize) {
+        let square = self.tree.node(node);
+        let corners = square.corners();
+        let [sw, se, ne, nw] = corners;
+
+        // a diagonal edge of a polygon across a unit square
+        let diagonal = |a: &Point<T>, b: &Point<T>| {
+            self.polygons
+                .iter()
+                .flat_map(|polygon| polygon.edges())
+                .any(|edge| edge.contains(a) && edge.contains(b))
+        };
+        let half = square.size / (T::one() + T::one());
+        let center = Point::from2d(square.min.x + half, square.min.y + half);
+
+        // the corners and, on a side with smaller neighbors, its middle
+        let mut boundary = vec![];
+        for (i, direction) in [
+            Direction::South,
+            Direction::East,
+            Direction::North,
+            Direction::West,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            boundary.push(corners[i]);
+            if self.tree.leaf_neighbors(node, direction).len() > 1 {
+                let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                boundary.push(Point::from2d(
+                    (a.x + b.x) / (T::one() + T::one()),
+                    (a.y + b.y) / (T::one() + T::one()),
+                ));
+            }
+        }
+
+        let triangles = if boundary.len() == 4 && diagonal(&se, &nw) && !diagonal(&sw, &ne) {
+            vec![[sw, se, nw], [se, ne, nw]]
+        } else if boundary.len() == 4 {
+            vec![[sw, se, ne], [sw, ne, nw]]
+        } else {
+            (0..boundary.len())
+                .map(|i| [boundary[i], boundary[(i + 1) % boundary.len()], center])
+                .collect()
+        };
+        for triangle in triangles {
+            let triangle = triangle.map(|p| self.vertex(p));
+            self.triangles.push(triangle);
+        }
+    }
+
+    /// the index of the vertex, added if new
+    fn vertex(&mut self, p: Point<T>) -> usize {
+        if let Some(&v) = self.indexes.get(&p) {
+            return v;
+        }
+
+        let v = self.vertices.len();
+        self.vertices.push(p);
+        self.indexes.insert(p, v);
+        v
+    }
+}
+
+/// true if the point is inside the polygon, by the parity of the edges
+/// crossed by a ray to the right. The point is not on the boundary
+fn encloses<T: Scalar>(polygon: &Polygon<T>, p: &Point<T>) -> bool {
+    let mut inside = false;
+    for edge in polygon.edges() {
+        let (a, b) = (edge.start, edge.end);
+        let crosses = match (a.y <= p.y && p.y < b.y, b.y <= p.y && p.y < a.y) {
+            (true, _) => T::orientation(&a, &b, p) == Orientation::Left,
+            (_, true) => T::orientation(&a, &b, p) == Orientation::Right,
+            _ => false,
+        };
+        inside ^= crosses;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdaptiveMesh, QuadtreeMesh};
+    use crate::{
+        algorithms::algorithm::Algorithm,
+        error::GalmetryError,
+        geometry::{point::Point, points::Points, polygon::Polygon, rational::Rational},
+    };
+
+    fn polygon(vertices: Vec<[f64; 2]>) -> Polygon {
+        let vertices: Points = vertices.into();
+        Polygon::new(vertices)
+    }
+
+    fn area(mesh: &AdaptiveMesh, t: usize) -> f64 {
+        let [a, b, c] = mesh.mesh.triangles()[t].map(|v| mesh.vertices[v]);
+        ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)) / 2.0
+    }
+
+    /// checks that the triangles are counterclockwise and cover the square,
+    /// each edge being shared by two of them unless it is on the boundary
+    fn assert_conforming(mesh: &AdaptiveMesh, size: f64) {
+        let mut total = 0.0;
+        for (t, triangle) in mesh.mesh.triangles().iter().enumerate() {
+            assert!(area(mesh, t) > 0.0);
+            total += area(mesh, t);
+            for i in 0..3 {
+                if mesh.mesh.neighbor(t, i).is_none() {
+                    let (a, b) = (
+                        mesh.vertices[triangle[(i + 1) % 3]],
+                        mesh.vertices[triangle[(i + 2) % 3]],
+                    );
+                    let on_side = |u: f64, v: f64| u == v && (u == 0.0 || u == size);
+                    assert!(on_side(a.x, b.x) || on_side(a.y, b.y));
+                }
+            }
+        }
+        assert_eq!(total, size * size);
+    }
+
+    fn inside_area(mesh: &AdaptiveMesh) -> f64 {
+        (0..mesh.mesh.len())
+            .filter(|&t| mesh.inside[t])
+            .map(|t| area(mesh, t))
+            .sum()
+    }
+
+    /// true if the segment from a to b is an edge of the mesh
+    fn has_edge(mesh: &AdaptiveMesh, a: [f64; 2], b: [f64; 2]) -> bool {
+        mesh.mesh.edges().iter().any(|&(u, v)| {
+            let (u, v) = (mesh.vertices[u], mesh.vertices[v]);
+            let (u, v) = ([u.x, u.y], [v.x, v.y]);
+            (u, v) == (a, b) || (u, v) == (b, a)
+        })
+    }
+
+    #[test]
+    fn square_room() {
+        let room = polygon(vec![[2.0, 2.0], [6.0, 2.0], [6.0, 6.0], [2.0, 6.0]]);
+        let mesh = QuadtreeMesh::build(vec![room], 16.0).calculate();
+
+        assert_conforming(&mesh, 16.0);
+        assert_eq!(inside_area(&mesh), 16.0);
+        for i in 2..6 {
+            let i = i as f64;
+            assert!(has_edge(&mesh, [i, 2.0], [i + 1.0, 2.0]));
+            assert!(has_edge(&mesh, [6.0, i], [6.0, i + 1.0]));
+        }
+        // fine near the walls, coarse far from them
+        let areas: Vec<f64> = (0..mesh.mesh.len()).map(|t| area(&mesh, t)).collect();
+        assert_eq!(areas.iter().cloned().fold(f64::INFINITY, f64::min), 0.5);
+        assert!(areas.iter().cloned().fold(0.0, f64::max) >= 8.0);
+        assert!(mesh.mesh.len() < 2 * 16 * 16);
+    }
+
+    #[test]
+    fn diagonal_edges_and_holes() {
+        let outer = polygon(vec![[1.0, 1.0], [7.0, 1.0], [7.0, 7.0]]);
+        let hole = polygon(vec![[5.0, 2.0], [6.0, 2.0], [6.0, 3.0], [5.0, 3.0]]);
+        let mesh = QuadtreeMesh::build(vec![outer, hole], 8.0).calculate();
+
+        assert_conforming(&mesh, 8.0);
+        assert_eq!(inside_area(&mesh), 18.0 - 1.0);
+        for i in 1..7 {
+            let i = i as f64;
+            assert!(has_edge(&mesh, [i, i], [i + 1.0, i + 1.0]));
+        }
+        assert_eq!(
+            mesh.interior().len(),
+            (0..mesh.mesh.len()).filter(|&t| mesh.inside[t]).count()
+        );
+    }
+
+    #[test]
+    fn balanced_quadtree() {
+        let mut algo = QuadtreeMesh::build(
+            vec![polygon(vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]])],
+            64.0,
+        );
+        algo.calculate();
+        assert!(algo.quadtree().is_balanced());
+        assert_conforming(&algo.result(), 64.0);
+        assert_eq!(inside_area(&algo.result()), 0.5);
+    }
+
+    #[test]
+    fn quadtree_mesh_steps() {
+        let room = polygon(vec![[3.0, 1.0], [5.0, 1.0], [5.0, 3.0], [3.0, 3.0]]);
+        let mut algo = QuadtreeMesh::build(vec![room], 8.0);
+
+        let mut steps = 0;
+        while !algo.is_done() {
+            algo.step();
+            steps += 1;
+        }
+        assert!(steps > 3);
+        let mesh = algo.result();
+        assert_conforming(&mesh, 8.0);
+        assert_eq!(algo.calculate(), mesh);
+    }
+
+    #[test]
+    fn rational_mesh() {
+        let r = |v: i64| Rational::from(v);
+        let room = Polygon::new(Points::from(vec![
+            Point::from2d(r(1), r(1)),
+            Point::from2d(r(3), r(3)),
+            Point::from2d(r(1), r(3)),
+        ]));
+        let mesh = QuadtreeMesh::try_build(vec![room], r(4))
+            .unwrap()
+            .calculate();
+        let inside = mesh.inside.iter().filter(|&&inside| inside).count();
+        assert_eq!(inside, 4);
+    }
+
+    #[test]
+    fn try_quadtree_mesh() {
+        let room = || polygon(vec![[1.0, 1.0], [3.0, 1.0], [3.0, 3.0]]);
+        assert_eq!(
+            QuadtreeMesh::try_build(vec![room()], 6.0).err(),
+            Some(GalmetryError::DegenerateInput(
+                "the size is not a power of 2"
+            ))
+        );
+        assert_eq!(
+            QuadtreeMesh::try_build(vec![room()], 2.0).err(),
+            Some(GalmetryError::DegenerateInput(
+                "the vertexes are outside the square"
+            ))
+        );
+        assert_eq!(
+            QuadtreeMesh::try_build(vec![polygon(vec![[1.0, 1.0], [3.0, 1.0], [2.5, 3.0]])], 4.0)
+                .err(),
+            Some(GalmetryError::DegenerateInput(
+                "the vertexes are not integer points"
+            ))
+        );
+        assert_eq!(
+            QuadtreeMesh::try_build(vec![polygon(vec![[1.0, 1.0], [3.0, 1.0], [2.0, 3.0]])], 4.0)
+                .err(),
+            Some(GalmetryError::DegenerateInput(
+                "the edges are neither axis-parallel nor diagonal"
+            ))
+        );
+        assert_eq!(
+            QuadtreeMesh::try_build(vec![polygon(vec![[1.0, 1.0], [3.0, 1.0]])], 4.0).err(),
+            Some(GalmetryError::InsufficientInput {
+                required: 3,
+                found: 2
+            })
+        );
+        assert!(QuadtreeMesh::try_build(vec![room()], 4.0).is_ok());
+    }
+}
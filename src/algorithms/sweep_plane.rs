@@ -1,210 +1,465 @@
 use std::{
-    cell::RefCell,
+    cmp::Ordering,
     collections::{BTreeMap, BTreeSet},
+    ops::Bound::{Excluded, Unbounded},
+    rc::Rc,
 };
 
-use crate::geometry::{point::Point, segment::Segment};
-use std::ops::Bound::{Excluded, Unbounded};
 use super::algorithm::Algorithm;
+use crate::{
+    error::{check_finite, GalmetryError, Result},
+    geometry::{
+        point::Point,
+        predicates::{orientation, Orientation},
+        scalar::{Field, Scalar},
+        segment::{Segment, SegmentIntersection, Tolerance},
+    },
+};
+
+/// The position of the sweep line used to order the status
+#[derive(Clone)]
+struct SweepLine<T> {
+    // the current event point
+    point: Point<T>,
+
+    // true to order the segments just below the event point, false just above
+    below: bool,
 
-#[derive(PartialEq, Eq, Clone)]
-struct StatusValue(RefCell<Point>, Segment);
+    // the segments passing through the event point: U(p), C(p) and L(p)
+    through: Rc<BTreeSet<Segment<T>>>,
+}
 
-impl PartialOrd for StatusValue {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        let event_point = self.0.borrow();
-        let sweep_line = Segment::new([-1000.0, event_point.y], [1000.0, event_point.y]);
+#[derive(Clone)]
+struct StatusValue<T>(SweepLine<T>, Segment<T>);
+
+impl<T: Field> StatusValue<T> {
+    /// x coordinate where the segment meets the sweep line. Horizontal segments
+    /// meet the sweep line in the event point
+    fn sweep_x(&self, sweep_line: &SweepLine<T>) -> T {
+        let seg = &self.1;
+        let p = sweep_line.point;
+        let (min_x, max_x) = (seg.start.x.min(seg.end.x), seg.start.x.max(seg.end.x));
+        if sweep_line.through.contains(seg) || seg.start.y == seg.end.y {
+            return p.x.max(min_x).min(max_x);
+        }
 
-        println!("{} with {}", self.1, other.1);
+        if p.y >= seg.start.y {
+            return seg.start.x;
+        }
+        if p.y <= seg.end.y {
+            return seg.end.x;
+        }
 
-        if self.1 == other.1 { return Some(std::cmp::Ordering::Equal) };
+        let t = (seg.start.y - p.y) / (seg.start.y - seg.end.y);
+        let x = seg.start.x + t * (seg.end.x - seg.start.x);
+        x.max(min_x).min(max_x)
+    }
 
-        let self_intersection = Segment::find_intersection(&self.1, &sweep_line).unwrap();
-        let other_intersection = Segment::find_intersection(&other.1, &sweep_line).unwrap();
+    /// order of two segments meeting the sweep line in the same point: just below
+    /// it the horizontal segments come last, just above they come first
+    fn tie_break(&self, other: &Self, sweep_line: &SweepLine<T>) -> Ordering {
+        let (a, b) = (&self.1, &other.1);
+        let a_horizontal = a.start.y == a.end.y;
+        let b_horizontal = b.start.y == b.end.y;
+
+        let horizontal_last = match sweep_line.below {
+            true => Ordering::Greater,
+            false => Ordering::Less,
+        };
+
+        match (a_horizontal, b_horizontal) {
+            (true, true) => return a.cmp(b),
+            (true, false) => return horizontal_last,
+            (false, true) => return horizontal_last.reverse(),
+            (false, false) => {}
+        }
 
-        Some(self_intersection.sweep_plane_cmp(&other_intersection))
+        // b goes downward, so its left side is the right one of the sweep line
+        let a_side = match sweep_line.below {
+            true => a.end,
+            false => a.start,
+        };
+        match orientation(&b.start, &b.end, &a_side) {
+            Orientation::Left => Ordering::Greater,
+            Orientation::Right => Ordering::Less,
+            Orientation::Collinear => a.cmp(b),
+        }
     }
 }
 
-impl Ord for StatusValue {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+impl<T: Field> PartialEq for StatusValue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-pub struct SweepPlane {
-    // this queue contains as K the event Point and as value the U(p)
-    queue: BTreeMap<Point, BTreeSet<Segment>>,
+impl<T: Field> Eq for StatusValue<T> {}
 
-    // current status of the algorithm
-    status: BTreeSet<StatusValue>,
+impl<T: Field> PartialOrd for StatusValue<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Field> Ord for StatusValue<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.1 == other.1 {
+            return Ordering::Equal;
+        }
+
+        let sweep_line = &self.0;
+        let self_x = self.sweep_x(sweep_line);
+        let other_x = other.sweep_x(sweep_line);
+
+        match self_x.partial_cmp(&other_x) {
+            Some(Ordering::Equal) | None => self.tie_break(other, sweep_line),
+            Some(ord) => ord,
+        }
+    }
+}
+
+/// The segments meeting in an intersection point, identified by their index
+/// in the vector given to `SweepPlane::build`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Intersection {
+    /// segments having the intersection point as an endpoint
+    pub endpoint: BTreeSet<usize>,
+
+    /// segments passing through the intersection point
+    pub interior: BTreeSet<usize>,
+}
+
+impl Intersection {
+    /// all the segments meeting in the intersection point
+    pub fn segments(&self) -> BTreeSet<usize> {
+        self.endpoint.union(&self.interior).copied().collect()
+    }
+
+    /// true if no segment ends in the intersection point
+    pub fn is_proper_crossing(&self) -> bool {
+        self.endpoint.is_empty()
+    }
+
+    /// true if at least one segment ends in the intersection point
+    pub fn is_endpoint_touch(&self) -> bool {
+        !self.endpoint.is_empty()
+    }
+}
 
-    // the list of segments used by the algorithm
-    segments: BTreeSet<Segment>,
+/// Bentley–Ottmann sweep. With an exact scalar, as `Rational`, the intersections
+/// are exact; with floating points they are rounded
+pub struct SweepPlane<T = f64> {
+    // the segments given by the user, kept to replay the algorithm
+    input: Vec<Segment<T>>,
+
+    // the indexes of the input segments, equal segments share the same entry
+    handles: BTreeMap<Segment<T>, BTreeSet<usize>>,
+
+    // this queue contains as K the event Point<T> and as value the U(p)
+    queue: BTreeMap<Point<T>, BTreeSet<Segment<T>>>,
+
+    // the segments whose intersection generated an event point. The intersection
+    // is rounded, so they could not pass exactly through it
+    crossings: BTreeMap<Point<T>, BTreeSet<Segment<T>>>,
+
+    // current status of the algorithm
+    status: BTreeSet<StatusValue<T>>,
 
     // the output of the algorithm
-    intersections: BTreeSet<Point>,
+    intersections: BTreeMap<Point<T>, Intersection>,
 
     // the reference of the current event_point
-    event_point: Point,
+    event_point: Option<Point<T>>,
+
+    // true if a computed intersection was not finite, it has been skipped
+    overflow: bool,
 }
 
-impl Algorithm for SweepPlane {
-    type Output = BTreeSet<Point>;
+impl<T: Field> Algorithm for SweepPlane<T> {
+    type Output = BTreeMap<Point<T>, Intersection>;
 
     fn calculate(&mut self) -> Self::Output {
-        while let Some(segment) = self.segments.pop_first() {
-            let mut l_p = BTreeSet::<Segment>::new();
-            l_p.insert(segment.clone());
-            self.queue.insert(segment.start.clone(), l_p);
-            self.queue.insert(segment.end.clone(), BTreeSet::<Segment>::new());
-        }
-
-        while self.queue.is_empty() == false {
+        while !self.is_done() {
             self.step();
         }
 
         self.intersections.clone()
     }
 
+    /// fails if an intersection point overflows the coordinate type
+    fn try_calculate(&mut self) -> Result<Self::Output> {
+        let intersections = self.calculate();
+        match self.overflow {
+            true => Err(GalmetryError::NumericFailure(
+                "an intersection point is not finite",
+            )),
+            false => Ok(intersections),
+        }
+    }
+
     fn step(&mut self) {
+        if self.is_done() {
+            return;
+        }
         self.handle_event_point();
     }
 
     fn reset(&mut self) {
-        todo!()
+        self.queue = BTreeMap::new();
+        self.crossings = BTreeMap::new();
+        self.status = BTreeSet::new();
+        self.intersections = BTreeMap::new();
+        self.event_point = None;
+        self.overflow = false;
+        self.handles = BTreeMap::new();
+
+        for (i, segment) in self.input.iter().enumerate() {
+            self.handles.entry(segment.clone()).or_default().insert(i);
+            self.queue
+                .entry(segment.start)
+                .or_default()
+                .insert(segment.clone());
+            self.queue.entry(segment.end).or_default();
+        }
     }
 }
 
-impl SweepPlane {
-    pub fn build(segments: Vec<Segment>) -> Self {
-        let mut segs = BTreeSet::<Segment>::new();
-        for seg in segments {
-            segs.insert(seg);
-        }
-
-        Self {
-            segments: segs,
-            event_point: Point::random(0.0..1.0),
+impl<T: Field> SweepPlane<T> {
+    pub fn build(segments: Vec<Segment<T>>) -> Self {
+        let mut algo = Self {
+            input: segments,
+            event_point: None,
             queue: BTreeMap::new(),
+            handles: BTreeMap::new(),
+            crossings: BTreeMap::new(),
             status: BTreeSet::new(),
-            intersections: BTreeSet::new(),
-        }
+            intersections: BTreeMap::new(),
+            overflow: false,
+        };
+        algo.reset();
+        algo
     }
 
-    fn handle_event_point(&mut self) {
+    /// like `build`, but rejects segments with NaN or infinite coordinates
+    pub fn try_build(segments: Vec<Segment<T>>) -> Result<Self> {
+        check_finite(segments.iter().map(|seg| seg.is_finite()))?;
+        Ok(Self::build(segments))
+    }
 
-        let (event_point, u_p) = self.queue.pop_first().unwrap();
-        let (c_p, l_p) = self.get_contains_and_lower(&event_point);
+    /// true when there are no more event points to handle
+    pub fn is_done(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// the event point handled by the last step
+    pub fn event_point(&self) -> Option<&Point<T>> {
+        self.event_point.as_ref()
+    }
+
+    /// the pending event points, each one with the segments starting there: U(p)
+    pub fn queue(&self) -> &BTreeMap<Point<T>, BTreeSet<Segment<T>>> {
+        &self.queue
+    }
+
+    /// the segments crossed by the sweep line, from left to right
+    pub fn status(&self) -> impl Iterator<Item = &Segment<T>> {
+        self.status.iter().map(|value| &value.1)
+    }
 
-        self.event_point = event_point;
+    /// the intersections found so far
+    pub fn intersections(&self) -> &BTreeMap<Point<T>, Intersection> {
+        &self.intersections
+    }
 
-        println!("{}", event_point);
-        if u_p.len() + c_p.len() + l_p.len() > 1 {
-            self.intersections.insert(event_point.clone());
+    fn handle_event_point(&mut self) {
+        let (event_point, u_p) = match self.queue.pop_first() {
+            Some(event) => event,
+            None => return,
+        };
+        let (c_p, l_p) = self.get_contains_and_lower(&event_point, &u_p);
+
+        self.event_point = Some(event_point);
+
+        let mut through = u_p.clone();
+        through.extend(c_p.iter().cloned());
+        through.extend(l_p.iter().cloned());
+
+        let intersection = Intersection {
+            endpoint: self.handles_of(u_p.union(&l_p)),
+            interior: self.handles_of(c_p.iter()),
+        };
+        if intersection.endpoint.len() + intersection.interior.len() > 1 {
+            self.intersections.insert(event_point, intersection);
         }
 
+        let above = SweepLine {
+            point: event_point,
+            below: false,
+            through: Rc::new(through),
+        };
+        let below = SweepLine {
+            below: true,
+            ..above.clone()
+        };
+
         for seg in l_p.union(&c_p) {
-            self.status
-                .remove(&StatusValue(RefCell::new(self.event_point), seg.clone()));
+            self.status.remove(&StatusValue(above.clone(), seg.clone()));
         }
 
-        for seg in u_p.union(&c_p) {
-            self.status
-                .insert(StatusValue(RefCell::new(self.event_point), seg.clone()));
+        // a segment made by a single point never crosses the sweep line
+        let u_c: Vec<StatusValue<T>> = u_p
+            .union(&c_p)
+            .filter(|seg| seg.start != seg.end)
+            .map(|seg| StatusValue(below.clone(), seg.clone()))
+            .collect();
+
+        for value in u_c.iter() {
+            self.status.insert(value.clone());
         }
 
-        if u_p.len() + c_p.len() == 0 {
-
-            let status = self.status.clone();
-            let left = status
-                .range((
-                    Unbounded,
-                    Excluded(&StatusValue(
-                        RefCell::new(self.event_point.clone()),
-                        Segment::new(event_point, event_point),
-                    )),
-                ))
-                .last()
-                .unwrap();
-
-            let right = status
-                .range((
-                    Excluded(&StatusValue(
-                        RefCell::new(self.event_point.clone()),
-                        Segment::new(event_point, event_point),
-                    )),
-                    Unbounded,
-                ))
-                .next()
-                .unwrap();
-
-            self.find_new_event(&left.1, &right.1, &event_point);
-        } else {
-            let status = self.status.clone();
-
-            let mut union_u_c = u_p.union(&c_p);
-
-            let leftmost = union_u_c.next().unwrap();
-            let left_leftmost = status.range((
-                Unbounded,
-                Excluded(&StatusValue(
-                        RefCell::new(self.event_point.clone()),
-                        leftmost.clone(),
-                    )))
-            ).last();
-            if left_leftmost.is_none() {return;}
-
-            self.find_new_event(&left_leftmost.unwrap().1, leftmost, &event_point);
-
-            let rightmost = union_u_c.last().unwrap_or(leftmost);
-            let right_rightmost = status
-                .range((
-                    Excluded(&StatusValue(
-                        RefCell::new(self.event_point.clone()),
-                        rightmost.clone(),
-                    )),
-                    Unbounded,
-                )).next();
-
-            if right_rightmost.is_none() {return;}
-            self.find_new_event(rightmost, &right_rightmost.unwrap().1, &event_point);
+        match (u_c.iter().min(), u_c.iter().max()) {
+            (Some(leftmost), Some(rightmost)) => {
+                let left = self.left_neighbor(leftmost);
+                let right = self.right_neighbor(rightmost);
+
+                if let Some(left) = left {
+                    self.find_new_event(&left, &leftmost.1, &event_point);
+                }
+                if let Some(right) = right {
+                    self.find_new_event(&rightmost.1, &right, &event_point);
+                }
+            }
+            _ => {
+                let probe = StatusValue(below, Segment::new(event_point, event_point));
+                let left = self.left_neighbor(&probe);
+                let right = self.right_neighbor(&probe);
+
+                if let (Some(left), Some(right)) = (left, right) {
+                    self.find_new_event(&left, &right, &event_point);
+                }
+            }
         }
     }
 
-    fn get_contains_and_lower(&mut self, event_point: &Point) -> (BTreeSet<Segment>, BTreeSet<Segment>) {
-        let mut c_p = BTreeSet::<Segment>::new();
-        let mut l_p = BTreeSet::<Segment>::new();
+    fn handles_of<'a>(&self, segments: impl Iterator<Item = &'a Segment<T>>) -> BTreeSet<usize>
+    where
+        T: 'a,
+    {
+        segments
+            .flat_map(|seg| self.handles[seg].iter().copied())
+            .collect()
+    }
 
-        for seg in self.status.clone().into_iter() {
-            if seg.1.end == *event_point {
-                l_p.insert(seg.1.clone());
-            }
-            if seg.1.contains(event_point) {
-                c_p.insert(seg.1.clone());
+    fn left_neighbor(&self, value: &StatusValue<T>) -> Option<Segment<T>> {
+        self.status
+            .range((Unbounded, Excluded(value)))
+            .next_back()
+            .map(|left| left.1.clone())
+    }
+
+    fn right_neighbor(&self, value: &StatusValue<T>) -> Option<Segment<T>> {
+        self.status
+            .range((Excluded(value), Unbounded))
+            .next()
+            .map(|right| right.1.clone())
+    }
+
+    /// C(p) and L(p): the segments of the status containing the event point
+    /// in their interior and the ones ending there
+    fn get_contains_and_lower(
+        &mut self,
+        event_point: &Point<T>,
+        u_p: &BTreeSet<Segment<T>>,
+    ) -> (BTreeSet<Segment<T>>, BTreeSet<Segment<T>>) {
+        let mut c_p = BTreeSet::<Segment<T>>::new();
+        let mut l_p = BTreeSet::<Segment<T>>::new();
+
+        let crossing = self.crossings.remove(event_point).unwrap_or_default();
+
+        for value in self.status.iter() {
+            let seg = &value.1;
+            if seg.end == *event_point {
+                l_p.insert(seg.clone());
+            } else if seg.start != *event_point
+                && (crossing.contains(seg) || passes_through(seg, event_point))
+            {
+                c_p.insert(seg.clone());
             }
         }
 
+        // a segment made by a single point starts and ends in the event point
+        for seg in u_p.iter().filter(|seg| seg.start == seg.end) {
+            l_p.insert(seg.clone());
+        }
+
         (c_p, l_p)
     }
 
-    fn find_new_event(&mut self, seg_left: &Segment, seg_right: &Segment, event_point: &Point) {
-        match Segment::find_intersection(seg_left, seg_right) {
-            Some(point) => {
-                // intersect where?
-                // below the seep line
-                if point.y < event_point.y
-                    // on the right of the event point
-                    || point.x > event_point.x
-                    // on the event point
-                    || (point == event_point.clone())
-                {
-                    self.queue.entry(point).or_insert(BTreeSet::new());
-                }
+    fn find_new_event(
+        &mut self,
+        seg_left: &Segment<T>,
+        seg_right: &Segment<T>,
+        event_point: &Point<T>,
+    ) {
+        let point = match seg_left.intersection(seg_right) {
+            SegmentIntersection::Point(point) if !point.is_finite() => {
+                self.overflow = true;
+                return;
             }
-            _ => return,
+            SegmentIntersection::Point(point) => self.snap(point),
+            // an overlap starts and ends in segment endpoints, already in the queue
+            SegmentIntersection::Overlap(_) | SegmentIntersection::Disjoint => return,
+        };
+
+        // below the sweep line or on it, on the right of the event point
+        if event_point.sweep_plane_cmp(&point) == Ordering::Less
+            && !(close(point.x, event_point.x, event_point)
+                && close(point.y, event_point.y, event_point))
+        {
+            self.queue.entry(point).or_default();
+            let crossing = self.crossings.entry(point).or_default();
+            crossing.insert(seg_left.clone());
+            crossing.insert(seg_right.clone());
         }
     }
+
+    /// a computed intersection is rounded, so the same crossing of three or more
+    /// segments can come out as slightly different points: they are merged
+    /// into the event point already in the queue
+    fn snap(&self, point: Point<T>) -> Point<T> {
+        let tolerance = tolerance(&point);
+        let from = Point::from2d(point.x - tolerance, point.y + tolerance);
+        let to = Point::from2d(point.x + tolerance, point.y - tolerance);
+
+        self.queue
+            .range(from..=to)
+            .map(|(event, _)| *event)
+            .find(|event| close(event.x, point.x, &point) && close(event.y, point.y, &point))
+            .unwrap_or(point)
+    }
+}
+
+/// the relative rounding error allowed on a computed intersection, zero for
+/// exact scalars
+fn rounding<T: Scalar>() -> T {
+    T::from_f64(8.0) * T::epsilon()
+}
+
+/// the rounding error allowed on a computed intersection near p
+fn tolerance<T: Scalar>(p: &Point<T>) -> T {
+    rounding::<T>() * p.x.abs().max(p.y.abs()).max(T::one())
+}
+
+fn close<T: Scalar>(a: T, b: T, p: &Point<T>) -> bool {
+    (a - b).abs() <= tolerance(p)
+}
+
+/// true if p lies on the segment, up to the rounding of the computed intersections
+fn passes_through<T: Scalar>(seg: &Segment<T>, p: &Point<T>) -> bool {
+    match T::epsilon() == T::zero() {
+        true => seg.contains(p),
+        false => seg.contains_with_tolerance(p, Tolerance::Relative(rounding::<T>().to_f64())),
+    }
 }
 
 #[cfg(test)]
@@ -212,26 +467,33 @@ mod tests {
 
     use crate::{
         algorithms::algorithm::Algorithm,
-        geometry::{point::Point, points::Points, segment::Segment},
+        error::GalmetryError,
+        geometry::{
+            point::Point,
+            points::Points,
+            rational::Rational,
+            scalar::Field,
+            segment::{Segment, SegmentIntersection},
+        },
     };
 
     use super::SweepPlane;
+    use rand::Rng;
+    use std::collections::BTreeSet;
 
     #[test]
     fn test_sweep_plane() {
         let s1 = Segment::new([4.0, 4.0], [0.0, 0.0]);
         let s2 = Segment::new([0.0, 2.0], [2.0, 0.0]);
 
-        let mut segments = Vec::<Segment>::new();
-        segments.push(s1);
-        segments.push(s2);
+        let segments = vec![s1, s2];
 
         let mut algo = SweepPlane::build(segments);
-        let res: Vec<Point> = algo.calculate().into_iter().collect();
+        let res = algo.calculate();
 
         let assert_res: Points = vec![[1.0, 1.0]].into();
         for p in assert_res {
-            assert!(res.contains(&p));
+            assert!(res.contains_key(&p));
         }
     }
 
@@ -242,14 +504,14 @@ mod tests {
         let s3 = Segment::new([0.0, 4.0], [4.0, 0.0]);
         let s4 = Segment::new([1.0, -1.0], [3.0, 1.0]);
 
-        let segments = vec![s1,s2,s3,s4];
+        let segments = vec![s1, s2, s3, s4];
 
         let mut algo = SweepPlane::build(segments);
         let res = algo.calculate();
 
         let assert_res: Points = vec![[1.0, 1.0], [2.0, 2.0], [3.0, 1.0], [2.0, 0.0]].into();
         for p in assert_res {
-            assert!(res.contains(&p));
+            assert!(res.contains_key(&p));
         }
     }
 
@@ -261,13 +523,356 @@ mod tests {
         let s4 = Segment::new([0.32, 0.74], [0.41, 0.51]);
         let s5 = Segment::new([0.75, 0.61], [0.36, 0.54]);
 
-        let segments = vec![s1,s2,s3,s4,s5];
+        let segments = vec![s1, s2, s3, s4, s5];
 
         let mut algo = SweepPlane::build(segments);
         let res = algo.calculate();
-        for p in res.clone() {
+        for p in res.keys() {
             println!("{}", p)
         }
+        // only s4 and s5 cross, the other segments are disjoint
+        assert_eq!(res.len(), 1);
+    }
+
+    #[test]
+    fn test_sweep_plane_step() {
+        let s1 = Segment::new([4.0, 4.0], [0.0, 0.0]);
+        let s2 = Segment::new([0.0, 2.0], [2.0, 0.0]);
+
+        let mut algo = SweepPlane::build(vec![s1.clone(), s2]);
+        assert_eq!(algo.queue().len(), 4);
+        assert!(algo.event_point().is_none());
+
+        algo.step();
+        assert_eq!(algo.event_point(), Some(&Point::from2d(4.0, 4.0)));
+        assert_eq!(algo.status().collect::<Vec<_>>(), vec![&s1]);
+        assert!(algo.intersections().is_empty());
+
+        while !algo.is_done() {
+            algo.step();
+        }
+        assert!(algo.intersections().contains_key(&Point::from2d(1.0, 1.0)));
+        let stepped = algo.intersections().clone();
+
+        algo.reset();
+        assert_eq!(algo.queue().len(), 4);
+        assert!(algo.intersections().is_empty());
+        assert_eq!(algo.calculate(), stepped);
+    }
+
+    fn intersections_of(segments: Vec<Segment>) -> Vec<Point> {
+        let mut algo = SweepPlane::build(segments);
+        algo.calculate().into_keys().collect()
+    }
+
+    fn points(data: Vec<[f64; 2]>) -> Vec<Point> {
+        data.into_iter().map(|p| p.into()).collect()
+    }
+
+    /// every intersection computed comparing each pair of segments
+    fn brute_force<T: Field>(segments: &[Segment<T>]) -> Vec<Point<T>> {
+        let mut res = Vec::new();
+        for (i, s1) in segments.iter().enumerate() {
+            for s2 in segments.iter().skip(i + 1) {
+                match s1.intersection(s2) {
+                    SegmentIntersection::Point(p) => res.push(p),
+                    SegmentIntersection::Overlap(overlap) => {
+                        res.push(overlap.start);
+                        res.push(overlap.end);
+                    }
+                    SegmentIntersection::Disjoint => {}
+                }
+            }
+        }
+        res
+    }
+
+    fn assert_same_points(res: &[Point], expected: &[Point]) {
+        let close =
+            |p: &Point, q: &Point| p.distance_from(q) < 1e-9 * q.x.abs().max(q.y.abs()).max(1.0);
+        for p in res {
+            assert!(expected.iter().any(|q| close(p, q)), "unexpected {}", p);
+        }
+        for q in expected {
+            assert!(res.iter().any(|p| close(p, q)), "missing {}", q);
+        }
+    }
+
+    #[test]
+    fn test_sweep_plane_horizontal() {
+        let s1 = Segment::new([0.0, 2.0], [4.0, 2.0]);
+        let s2 = Segment::new([1.0, 4.0], [1.0, 0.0]);
+        let s3 = Segment::new([2.0, 4.0], [4.0, 0.0]);
+        let s4 = Segment::new([0.0, 0.0], [4.0, 0.0]);
+
+        let res = intersections_of(vec![s1, s2, s3, s4]);
+        assert_same_points(
+            &res,
+            &points(vec![[1.0, 2.0], [3.0, 2.0], [1.0, 0.0], [4.0, 0.0]]),
+        );
+    }
+
+    #[test]
+    fn test_sweep_plane_horizontal_through_endpoint() {
+        // the horizontal segment touches the lower endpoint of s2 and the upper one of s3
+        let s1 = Segment::new([0.0, 2.0], [4.0, 2.0]);
+        let s2 = Segment::new([1.0, 4.0], [1.0, 2.0]);
+        let s3 = Segment::new([3.0, 2.0], [3.0, 0.0]);
+
+        let res = intersections_of(vec![s1, s2, s3]);
+        assert_same_points(&res, &points(vec![[1.0, 2.0], [3.0, 2.0]]));
+    }
+
+    #[test]
+    fn test_sweep_plane_shared_endpoints() {
+        let s1 = Segment::new([0.0, 0.0], [2.0, 2.0]);
+        let s2 = Segment::new([2.0, 2.0], [4.0, 0.0]);
+        let s3 = Segment::new([2.0, 2.0], [2.0, 4.0]);
+        let s4 = Segment::new([0.0, 0.0], [4.0, 0.0]);
+
+        let res = intersections_of(vec![s1, s2, s3, s4]);
+        assert_same_points(&res, &points(vec![[2.0, 2.0], [0.0, 0.0], [4.0, 0.0]]));
+    }
+
+    #[test]
+    fn test_sweep_plane_many_through_one_point() {
+        let s1 = Segment::new([0.0, 0.0], [4.0, 4.0]);
+        let s2 = Segment::new([0.0, 4.0], [4.0, 0.0]);
+        let s3 = Segment::new([2.0, 0.0], [2.0, 4.0]);
+        let s4 = Segment::new([0.0, 2.0], [4.0, 2.0]);
+        let s5 = Segment::new([1.0, 0.0], [3.0, 4.0]);
+
+        let res = intersections_of(vec![s1, s2, s3, s4, s5]);
+        assert_same_points(&res, &points(vec![[2.0, 2.0]]));
+    }
+
+    #[test]
+    fn test_sweep_plane_collinear_overlap() {
+        let s1 = Segment::new([0.0, 0.0], [4.0, 4.0]);
+        let s2 = Segment::new([2.0, 2.0], [6.0, 6.0]);
+        let s3 = Segment::new([0.0, 1.0], [6.0, 1.0]);
+        let s4 = Segment::new([2.0, 1.0], [3.0, 1.0]);
+
+        let res = intersections_of(vec![s1, s2, s3, s4]);
+        assert_same_points(
+            &res,
+            &points(vec![
+                [2.0, 2.0],
+                [4.0, 4.0],
+                [1.0, 1.0],
+                [2.0, 1.0],
+                [3.0, 1.0],
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_sweep_plane_duplicated_segments() {
+        let s1 = Segment::new([0.0, 0.0], [4.0, 4.0]);
+        let s2 = Segment::new([4.0, 4.0], [0.0, 0.0]);
+
+        let mut algo = SweepPlane::build(vec![s1, s2]);
+        let res = algo.calculate();
         assert_eq!(res.len(), 2);
+        for intersection in res.values() {
+            assert_eq!(intersection.endpoint, BTreeSet::from([0, 1]));
+        }
+    }
+
+    #[test]
+    fn test_sweep_plane_handles() {
+        let s0 = Segment::new([0.0, 0.0], [4.0, 4.0]);
+        let s1 = Segment::new([0.0, 4.0], [4.0, 0.0]);
+        let s2 = Segment::new([5.0, 0.0], [5.0, 4.0]);
+        let s3 = Segment::new([4.0, 4.0], [5.0, 4.0]);
+
+        let mut algo = SweepPlane::build(vec![s0, s1, s2, s3]);
+        let res = algo.calculate();
+        assert_eq!(res.len(), 3);
+
+        let crossing = &res[&Point::from2d(2.0, 2.0)];
+        assert!(crossing.is_proper_crossing());
+        assert_eq!(crossing.interior, BTreeSet::from([0, 1]));
+
+        let corner = &res[&Point::from2d(5.0, 4.0)];
+        assert!(corner.is_endpoint_touch());
+        assert_eq!(corner.segments(), BTreeSet::from([2, 3]));
+
+        let touch = &res[&Point::from2d(4.0, 4.0)];
+        assert_eq!(touch.endpoint, BTreeSet::from([0, 3]));
+        assert!(touch.interior.is_empty());
+    }
+
+    #[test]
+    fn test_sweep_plane_t_junction() {
+        let s0 = Segment::new([0.0, 2.0], [4.0, 2.0]);
+        let s1 = Segment::new([2.0, 2.0], [2.0, 0.0]);
+
+        let mut algo = SweepPlane::build(vec![s0, s1]);
+        let res = algo.calculate();
+
+        let t = &res[&Point::from2d(2.0, 2.0)];
+        assert!(t.is_endpoint_touch());
+        assert_eq!(t.endpoint, BTreeSet::from([1]));
+        assert_eq!(t.interior, BTreeSet::from([0]));
+    }
+
+    #[test]
+    fn test_sweep_plane_point_segment() {
+        let s1 = Segment::new([0.0, 0.0], [4.0, 4.0]);
+        let s2 = Segment::new([2.0, 2.0], [2.0, 2.0]);
+        let s3 = Segment::new([3.0, 0.0], [3.0, 0.0]);
+
+        let res = intersections_of(vec![s1, s2, s3]);
+        assert_same_points(&res, &points(vec![[2.0, 2.0]]));
+    }
+
+    #[test]
+    fn test_sweep_plane_no_neighbors() {
+        let s1 = Segment::new([0.0, 4.0], [0.0, 0.0]);
+        let s2 = Segment::new([2.0, 4.0], [2.0, 3.0]);
+        let s3 = Segment::new([2.0, 1.0], [2.0, 0.0]);
+
+        assert!(intersections_of(vec![s1, s2, s3]).is_empty());
+    }
+
+    #[test]
+    fn test_sweep_plane_integer_grid() {
+        // small integer grids are full of shared endpoints, overlaps and axis-aligned segments
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let segments: Vec<Segment> = (0..12)
+                .map(|_| {
+                    let mut coord = || rng.gen_range(0..5) as f64;
+                    Segment::new([coord(), coord()], [coord(), coord()])
+                })
+                .collect();
+
+            let res = intersections_of(segments.clone());
+            assert_same_points(&res, &brute_force(&segments));
+        }
+    }
+
+    #[test]
+    fn test_sweep_plane_random() {
+        for _ in 0..50 {
+            let segments: Vec<Segment> = (0..20).map(|_| Segment::random(0.0..1.0)).collect();
+
+            let res = intersections_of(segments.clone());
+            assert_same_points(&res, &brute_force(&segments));
+        }
+    }
+
+    #[test]
+    fn test_sweep_plane_large_coordinates() {
+        let s1 = Segment::new([-5000.0, -5000.0], [5000.0, 5000.0]);
+        let s2 = Segment::new([-5000.0, 5000.0], [5000.0, -5000.0]);
+        let s3 = Segment::new([-8000.0, 0.0], [8000.0, 0.0]);
+
+        let res = intersections_of(vec![s1, s2, s3]);
+        assert_same_points(&res, &points(vec![[0.0, 0.0]]));
+    }
+
+    #[test]
+    fn test_sweep_plane_gis_scale() {
+        // projected coordinates in meters, far from the origin
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let segments: Vec<Segment> = (0..20)
+                .map(|_| {
+                    let mut coord = || rng.gen_range(4_500_000.0..4_501_000.0);
+                    Segment::new([coord(), coord()], [coord(), coord()])
+                })
+                .collect();
+
+            let res = intersections_of(segments.clone());
+            assert_same_points(&res, &brute_force(&segments));
+        }
+    }
+
+    #[test]
+    fn test_sweep_plane_gis_scale_grid() {
+        // axis-aligned and diagonal streets on a meter grid
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let segments: Vec<Segment> = (0..15)
+                .map(|_| {
+                    let mut coord = || 1_200_000.0 + rng.gen_range(0..6) as f64 * 250.0;
+                    Segment::new([coord(), coord()], [coord(), coord()])
+                })
+                .collect();
+
+            let res = intersections_of(segments.clone());
+            assert_same_points(&res, &brute_force(&segments));
+        }
+    }
+
+    fn rational(x: i64, y: i64) -> Point<Rational> {
+        Point::from2d(Rational::from(x), Rational::from(y))
+    }
+
+    #[test]
+    fn test_sweep_plane_rational_concurrent() {
+        // the three segments meet in (1/3, 1/3), not representable as f64
+        let segments = vec![
+            Segment::new(rational(0, 0), rational(1, 1)),
+            Segment::new(rational(0, 1), rational(1, -1)),
+            Segment::new(rational(1, 0), rational(-1, 1)),
+        ];
+
+        let res = SweepPlane::build(segments).calculate();
+
+        let third = Rational::new(1, 3);
+        assert_eq!(res.len(), 1);
+        assert_eq!(
+            res[&Point::from2d(third, third)].interior,
+            BTreeSet::from([0, 1, 2])
+        );
+    }
+
+    #[test]
+    fn test_sweep_plane_rational_grid() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let segments: Vec<Segment<Rational>> = (0..12)
+                .map(|_| {
+                    let mut coord = || rng.gen_range(0..8);
+                    Segment::new(rational(coord(), coord()), rational(coord(), coord()))
+                })
+                .collect();
+
+            let expected: BTreeSet<_> = brute_force(&segments).into_iter().collect();
+            let res = SweepPlane::build(segments).calculate();
+            assert_eq!(res.into_keys().collect::<BTreeSet<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_sweep_plane_f32() {
+        let s1 = Segment::new([4.0f32, 4.0], [0.0, 0.0]);
+        let s2 = Segment::new([0.0f32, 2.0], [2.0, 0.0]);
+
+        let res = SweepPlane::build(vec![s1, s2]).calculate();
+
+        assert_eq!(
+            res.into_keys().collect::<Vec<_>>(),
+            vec![Point::from2d(1.0f32, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_sweep_plane_try_calculate_overflow() {
+        // the coefficients of the supporting lines overflow f64
+        let (p, l) = (1e163, 1e151);
+        let segments = vec![
+            Segment::new([p, p], [p + l, p + l]),
+            Segment::new([p, p + l], [p + l, p]),
+        ];
+
+        let mut algo = SweepPlane::try_build(segments).unwrap();
+        assert!(matches!(
+            algo.try_calculate(),
+            Err(GalmetryError::NumericFailure(_))
+        ));
     }
 }
@@ -0,0 +1,945 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use rand::seq::SliceRandom;
+
+use super::{algorithm::Algorithm, sweep_plane::SweepPlane};
+use crate::{
+    error::{check_finite, GalmetryError, Result},
+    geometry::{
+        dcel::Dcel,
+        point::Point,
+        points::Points,
+        predicates::Orientation,
+        scalar::{Field, Scalar},
+        segment::Segment,
+    },
+};
+
+/// A face of a trapezoidal map: the region between two segments and two
+/// vertical walls through a point each. Points are ordered by x then by y, as
+/// if the plane were slightly sheared, so vertical segments need no special
+/// care and walls through points with the same x never overlap
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trapezoid<T = f64> {
+    /// the index of the segment above, None if unbounded
+    pub top: Option<usize>,
+
+    /// the index of the segment below, None if unbounded
+    pub bottom: Option<usize>,
+
+    /// the point of the left wall, None if unbounded
+    pub leftp: Option<Point<T>>,
+
+    /// the point of the right wall, None if unbounded
+    pub rightp: Option<Point<T>>,
+
+    // the neighbors across the walls, the upper ones share the top and the
+    // lower ones share the bottom
+    upper_left: Option<usize>,
+    lower_left: Option<usize>,
+    upper_right: Option<usize>,
+    lower_right: Option<usize>,
+
+    // the leaf of the search structure
+    node: usize,
+
+    // false once split by a segment
+    alive: bool,
+}
+
+/// A node of the search structure
+#[derive(Clone, Debug, PartialEq)]
+enum Node<T> {
+    /// the points before the point go left, the others right
+    Point {
+        point: Point<T>,
+        left: usize,
+        right: usize,
+    },
+
+    /// the points above the segment go in the first child
+    Segment {
+        segment: usize,
+        above: usize,
+        below: usize,
+    },
+
+    Trapezoid(usize),
+}
+
+/// The trapezoidal map of non-crossing segments and its search structure, a
+/// directed acyclic graph answering point location queries in expected
+/// O(log n) time
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrapezoidalMap<T = f64> {
+    // the endpoints of each segment, the smaller by x then y first
+    segments: Vec<(Point<T>, Point<T>)>,
+
+    // the trapezoids, the ones split by a later segment included
+    trapezoids: Vec<Trapezoid<T>>,
+
+    // the search structure, the root first
+    nodes: Vec<Node<T>>,
+}
+
+impl<T: Scalar> TrapezoidalMap<T> {
+    /// the map of no segment: one trapezoid covering the plane
+    fn new(segments: &[Segment<T>]) -> Self {
+        let segments = segments
+            .iter()
+            .map(|seg| {
+                let (p, q) = (flat(&seg.start), flat(&seg.end));
+                match point_cmp(&p, &q) {
+                    Ordering::Greater => (q, p),
+                    _ => (p, q),
+                }
+            })
+            .collect();
+
+        Self {
+            segments,
+            trapezoids: vec![Trapezoid {
+                top: None,
+                bottom: None,
+                leftp: None,
+                rightp: None,
+                upper_left: None,
+                lower_left: None,
+                upper_right: None,
+                lower_right: None,
+                node: 0,
+                alive: true,
+            }],
+            nodes: vec![Node::Trapezoid(0)],
+        }
+    }
+
+    /// the indexes of the trapezoids of the map
+    pub fn trapezoids(&self) -> Vec<usize> {
+        (0..self.trapezoids.len())
+            .filter(|&t| self.trapezoids[t].alive)
+            .collect()
+    }
+
+    pub fn trapezoid(&self, t: usize) -> &Trapezoid<T> {
+        &self.trapezoids[t]
+    }
+
+    /// the trapezoid containing the point. A point on a segment is located
+    /// below it, a point on a wall right of it
+    pub fn locate(&self, p: &Point<T>) -> usize {
+        self.search(&flat(p), None)
+    }
+
+    /// the index of the segment directly above the point, or through it
+    pub fn segment_above(&self, p: &Point<T>) -> Option<usize> {
+        self.trapezoids[self.locate(p)].top
+    }
+
+    /// the index of the segment directly below the point
+    pub fn segment_below(&self, p: &Point<T>) -> Option<usize> {
+        self.trapezoids[self.locate(p)].bottom
+    }
+
+    /// Walks down the search structure. When p lies on a segment, it is taken
+    /// as the left endpoint of a segment going to q, and the side is the one
+    /// of q
+    fn search(&self, p: &Point<T>, q: Option<&Point<T>>) -> usize {
+        let mut node = 0;
+        loop {
+            node = match &self.nodes[node] {
+                Node::Point { point, left, right } => match point_cmp(p, point) {
+                    Ordering::Less => *left,
+                    _ => *right,
+                },
+                Node::Segment {
+                    segment,
+                    above,
+                    below,
+                } => {
+                    let side = match (self.side(*segment, p), q) {
+                        (Orientation::Collinear, Some(q)) => self.side(*segment, q),
+                        (side, _) => side,
+                    };
+                    match side {
+                        Orientation::Left => *above,
+                        _ => *below,
+                    }
+                }
+                Node::Trapezoid(t) => return *t,
+            }
+        }
+    }
+
+    /// Left if p is above the segment, Right if below
+    fn side(&self, segment: usize, p: &Point<T>) -> Orientation {
+        let (a, b) = &self.segments[segment];
+        T::orientation(a, b, p)
+    }
+
+    /// adds a segment, splitting the trapezoids it crosses
+    fn insert(&mut self, s: usize) {
+        let (p, q) = self.segments[s];
+        if point_cmp(&p, &q) == Ordering::Equal {
+            return;
+        }
+
+        // the trapezoids crossed, from left to right
+        let mut crossed = vec![self.search(&p, Some(&q))];
+        loop {
+            let t = &self.trapezoids[crossed[crossed.len() - 1]];
+            let next = match t.rightp {
+                Some(r) if point_cmp(&r, &q) == Ordering::Less => match self.side(s, &r) {
+                    Orientation::Left => t.lower_right,
+                    _ => t.upper_right,
+                },
+                _ => break,
+            };
+            crossed.push(next.expect("a wall before the end of the segment has a neighbor"));
+        }
+        let (first, last) = (crossed[0], crossed[crossed.len() - 1]);
+
+        // the trapezoids replacing them, above and below the segment
+        let (mut upper, mut lower) = (vec![], vec![]);
+        let mut above = self.add(self.trapezoids[first].top, Some(s), Some(p));
+        let mut below = self.add(Some(s), self.trapezoids[first].bottom, Some(p));
+        for (j, &t) in crossed.iter().enumerate() {
+            upper.push(above);
+            lower.push(below);
+            if let Some(&next) = crossed.get(j + 1) {
+                let r = self.trapezoids[t].rightp;
+                match self.side(s, r.as_ref().expect("the wall is before the end")) {
+                    Orientation::Left => {
+                        self.trapezoids[above].rightp = r;
+                        above = self.add(self.trapezoids[next].top, Some(s), r);
+                    }
+                    _ => {
+                        self.trapezoids[below].rightp = r;
+                        below = self.add(Some(s), self.trapezoids[next].bottom, r);
+                    }
+                }
+            }
+        }
+        self.trapezoids[above].rightp = Some(q);
+        self.trapezoids[below].rightp = Some(q);
+
+        let (f, l) = (&self.trapezoids[first], &self.trapezoids[last]);
+        let left = match f.leftp {
+            Some(leftp) if point_cmp(&leftp, &p) == Ordering::Equal => None,
+            leftp => Some((f.top, f.bottom, leftp)),
+        };
+        let right = match l.rightp {
+            Some(rightp) if point_cmp(&rightp, &q) == Ordering::Equal => None,
+            rightp => Some((l.top, l.bottom, rightp)),
+        };
+        let left = left.map(|(top, bottom, leftp)| {
+            let t = self.add(top, bottom, leftp);
+            self.trapezoids[t].rightp = Some(p);
+            t
+        });
+        let right = right.map(|(top, bottom, rightp)| {
+            let t = self.add(top, bottom, Some(q));
+            self.trapezoids[t].rightp = rightp;
+            t
+        });
+
+        // the leaves of the crossed trapezoids become the subtrees of the new ones
+        for (j, &t) in crossed.iter().enumerate() {
+            let mut root = Node::Segment {
+                segment: s,
+                above: self.trapezoids[upper[j]].node,
+                below: self.trapezoids[lower[j]].node,
+            };
+            if let (Some(right), true) = (right, t == last) {
+                let split = self.nodes.len();
+                self.nodes.push(root);
+                root = Node::Point {
+                    point: q,
+                    left: split,
+                    right: self.trapezoids[right].node,
+                };
+            }
+            if let (Some(left), true) = (left, t == first) {
+                let split = self.nodes.len();
+                self.nodes.push(root);
+                root = Node::Point {
+                    point: p,
+                    left: self.trapezoids[left].node,
+                    right: split,
+                };
+            }
+            let node = self.trapezoids[t].node;
+            self.nodes[node] = root;
+        }
+
+        // the neighbors change only among the new trapezoids and the ones next
+        // to the crossed ones. A new trapezoid over the crossed one j can only
+        // neighbor the new ones over j - 1, j and j + 1 and the old neighbors
+        // of j, so that relinking costs O(k) for k trapezoids crossed
+        let mut over: Vec<Vec<usize>> = (0..crossed.len())
+            .map(|j| vec![upper[j], lower[j]])
+            .collect();
+        over[0].extend(left);
+        over[crossed.len() - 1].extend(right);
+        let around: Vec<Vec<usize>> = crossed
+            .iter()
+            .map(|&t| {
+                let t = &self.trapezoids[t];
+                [t.upper_left, t.lower_left, t.upper_right, t.lower_right]
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            })
+            .collect();
+        for &t in crossed.iter() {
+            self.trapezoids[t].alive = false;
+        }
+        for &t in around.iter().flatten() {
+            let alive = |n: Option<usize>| n.filter(|&n| self.trapezoids[n].alive);
+            let trapezoid = &self.trapezoids[t];
+            let links = [
+                alive(trapezoid.upper_left),
+                alive(trapezoid.lower_left),
+                alive(trapezoid.upper_right),
+                alive(trapezoid.lower_right),
+            ];
+            let trapezoid = &mut self.trapezoids[t];
+            [
+                trapezoid.upper_left,
+                trapezoid.lower_left,
+                trapezoid.upper_right,
+                trapezoid.lower_right,
+            ] = links;
+        }
+        for j in 0..crossed.len() {
+            let mut next_to: Vec<usize> = over[j.saturating_sub(1)..(j + 2).min(crossed.len())]
+                .iter()
+                .flatten()
+                .copied()
+                .collect();
+            next_to.extend(around[j].iter().filter(|&&t| self.trapezoids[t].alive));
+            for &a in over[j].iter() {
+                for &b in next_to.iter() {
+                    self.link(a, b);
+                    self.link(b, a);
+                }
+            }
+        }
+    }
+
+    /// a new trapezoid with its leaf, the right wall still to set
+    fn add(&mut self, top: Option<usize>, bottom: Option<usize>, leftp: Option<Point<T>>) -> usize {
+        let t = self.trapezoids.len();
+        self.trapezoids.push(Trapezoid {
+            top,
+            bottom,
+            leftp,
+            rightp: None,
+            upper_left: None,
+            lower_left: None,
+            upper_right: None,
+            lower_right: None,
+            node: self.nodes.len(),
+            alive: true,
+        });
+        self.nodes.push(Node::Trapezoid(t));
+        t
+    }
+
+    /// Makes b the right neighbor of a if they share a wall. They do when the
+    /// wall of both has a length and they share the top or the bottom
+    fn link(&mut self, a: usize, b: usize) {
+        let (ta, tb) = (&self.trapezoids[a], &self.trapezoids[b]);
+        let wall = match (ta.rightp, tb.leftp) {
+            (Some(r), Some(l)) if point_cmp(&r, &l) == Ordering::Equal => r,
+            _ => return,
+        };
+        if self.closes(ta.top, ta.bottom, &wall) || self.closes(tb.top, tb.bottom, &wall) {
+            return;
+        }
+
+        if ta.top == tb.top {
+            self.trapezoids[a].upper_right = Some(b);
+            self.trapezoids[b].upper_left = Some(a);
+        }
+        let (ta, tb) = (&self.trapezoids[a], &self.trapezoids[b]);
+        if ta.bottom == tb.bottom {
+            self.trapezoids[a].lower_right = Some(b);
+            self.trapezoids[b].lower_left = Some(a);
+        }
+    }
+
+    /// true if both the top and the bottom end in the point of the wall, so
+    /// that the wall has no length
+    fn closes(&self, top: Option<usize>, bottom: Option<usize>, wall: &Point<T>) -> bool {
+        let ends = |s: Option<usize>| {
+            s.is_some_and(|s| {
+                let (p, q) = &self.segments[s];
+                point_cmp(p, wall) == Ordering::Equal || point_cmp(q, wall) == Ordering::Equal
+            })
+        };
+        ends(top) && ends(bottom)
+    }
+}
+
+impl<T: Field> TrapezoidalMap<T> {
+    /// The map as a `Dcel`, clipped to a box around the segments larger by a
+    /// tenth of its size on each side. The attribute of a bounded face is the
+    /// index of its trapezoid. The trapezoids without width, between points
+    /// with the same x, have no face
+    pub fn to_dcel(&self) -> Dcel<T, Option<usize>> {
+        let (min, max) = self.bounds();
+        let n = self.segments.len();
+
+        // the points on each segment, then on the bottom and the top of the box
+        let mut lines: Vec<Vec<Point<T>>> = vec![vec![]; n + 2];
+        // the points on the walls, and the parts of the walls x, lo, hi
+        let mut walls: Vec<Point<T>> = vec![];
+        let mut spans: Vec<(T, T, T)> = vec![];
+
+        for (s, &(p, q)) in self.segments.iter().enumerate() {
+            lines[s].extend([p, q]);
+            walls.extend([p, q]);
+            if p.x == q.x {
+                spans.push((p.x, p.y, q.y));
+            }
+        }
+        let corners = [
+            Point::from2d(min.x, min.y),
+            Point::from2d(max.x, min.y),
+            Point::from2d(min.x, max.y),
+            Point::from2d(max.x, max.y),
+        ];
+        lines[n].extend(&corners[..2]);
+        lines[n + 1].extend(&corners[2..]);
+        walls.extend(corners);
+
+        for t in self.trapezoids() {
+            let trapezoid = &self.trapezoids[t];
+            let left = trapezoid.leftp.map_or(min.x, |p| p.x);
+            let right = trapezoid.rightp.map_or(max.x, |p| p.x);
+            if left >= right {
+                continue;
+            }
+
+            let y = |segment: Option<usize>, x: T, unbounded: T| {
+                segment.map_or(unbounded, |s| self.y_at(s, x))
+            };
+            for x in [left, right] {
+                let (bottom, top) = (y(trapezoid.bottom, x, min.y), y(trapezoid.top, x, max.y));
+                lines[trapezoid.bottom.unwrap_or(n)].push(Point::from2d(x, bottom));
+                lines[trapezoid.top.unwrap_or(n + 1)].push(Point::from2d(x, top));
+                walls.extend([Point::from2d(x, bottom), Point::from2d(x, top)]);
+                spans.push((x, bottom, top));
+            }
+        }
+
+        let mut points = Points::new();
+        let mut places: BTreeMap<Point<T>, usize> = BTreeMap::new();
+        let mut place = |p: &Point<T>| {
+            *places.entry(*p).or_insert_with(|| {
+                points.push(*p);
+                points.len() - 1
+            })
+        };
+        let mut edges = vec![];
+
+        // the segments and the box are covered from end to end
+        for mut line in lines {
+            line.sort_by(point_cmp);
+            line.dedup();
+            for pair in line.windows(2) {
+                edges.push((place(&pair[0]), place(&pair[1])));
+            }
+        }
+
+        // a wall joins two points if a part of it goes from one to the other
+        let cmp = |a: &T, b: &T| a.partial_cmp(b).unwrap_or(Ordering::Equal);
+        spans.sort_by(|a, b| cmp(&a.0, &b.0).then(cmp(&a.1, &b.1)));
+        let mut merged: Vec<(T, T, T)> = vec![];
+        for (x, lo, hi) in spans {
+            match merged.last_mut() {
+                Some(last) if last.0 == x && lo <= last.2 => last.2 = last.2.max(hi),
+                _ => merged.push((x, lo, hi)),
+            }
+        }
+        walls.sort_by(point_cmp);
+        walls.dedup();
+        let mut k = 0;
+        for pair in walls.windows(2) {
+            let (p, q) = (pair[0], pair[1]);
+            if p.x != q.x {
+                continue;
+            }
+            while k < merged.len()
+                && (merged[k].0 < p.x || merged[k].0 == p.x && merged[k].2 <= p.y)
+            {
+                k += 1;
+            }
+            if merged
+                .get(k)
+                .is_some_and(|&(x, lo, hi)| x == p.x && lo <= p.y && q.y <= hi)
+            {
+                edges.push((place(&p), place(&q)));
+            }
+        }
+
+        let mut dcel: Dcel<T, Option<usize>> = Dcel::from_edges(points, &edges);
+        for f in 1..dcel.faces().len() {
+            // the trapezoids are convex, so the mean of the corners is inside
+            let corners = dcel.boundary(f).map(|polygon| polygon.vertices().clone());
+            if let Some(corners) = corners.filter(|c| !c.is_empty()) {
+                let count = T::from_f64(corners.len() as f64);
+                let (x, y) = corners
+                    .iter()
+                    .fold((T::zero(), T::zero()), |(x, y), p| (x + p.x, y + p.y));
+                let t = self.locate(&Point::from2d(x / count, y / count));
+                dcel.set_attribute(f, Some(t));
+            }
+        }
+        dcel
+    }
+
+    /// the corners of the box of the dcel
+    fn bounds(&self) -> (Point<T>, Point<T>) {
+        let points = self.segments.iter().flat_map(|&(p, q)| [p, q]);
+        let (mut min, mut max) = match points.clone().next() {
+            Some(p) => (p, p),
+            None => (
+                Point::from2d(T::zero(), T::zero()),
+                Point::from2d(T::zero(), T::zero()),
+            ),
+        };
+        for p in points {
+            min = Point::from2d(min.x.min(p.x), min.y.min(p.y));
+            max = Point::from2d(max.x.max(p.x), max.y.max(p.y));
+        }
+
+        let size = (max.x - min.x).max(max.y - min.y);
+        let margin = match size > T::zero() {
+            true => size / T::from_f64(10.0),
+            false => T::one(),
+        };
+        (
+            Point::from2d(min.x - margin, min.y - margin),
+            Point::from2d(max.x + margin, max.y + margin),
+        )
+    }
+
+    /// the y of the segment at x, which is not vertical
+    fn y_at(&self, segment: usize, x: T) -> T {
+        let (p, q) = &self.segments[segment];
+        match (x == p.x, x == q.x) {
+            (true, _) => p.y,
+            (_, true) => q.y,
+            _ => p.y + (q.y - p.y) * (x - p.x) / (q.x - p.x),
+        }
+    }
+}
+
+/// Randomized incremental construction of the trapezoidal map of non-crossing
+/// segments, which may share endpoints (de Berg et al. chapter 6). The
+/// segments are added in random order: each one is located with the search
+/// structure, then the trapezoids it crosses are replaced, and their leaves
+/// become small subtrees locating the new trapezoids. The expected time is
+/// O(n log n) and the expected size O(n).
+///
+/// A step adds one segment
+pub struct RandomizedTrapezoidalMap<T = f64> {
+    // the input, kept to replay the algorithm
+    segments: Vec<Segment<T>>,
+
+    // the segments in the order they are added
+    order: Vec<usize>,
+
+    // the number of segments added so far
+    added: usize,
+
+    map: TrapezoidalMap<T>,
+}
+
+impl<T: Scalar> Algorithm for RandomizedTrapezoidalMap<T> {
+    type Output = TrapezoidalMap<T>;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while !self.is_done() {
+            self.step();
+        }
+
+        self.map.clone()
+    }
+
+    fn step(&mut self) {
+        if let Some(&s) = self.order.get(self.added) {
+            self.map.insert(s);
+            self.added += 1;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.map = TrapezoidalMap::new(&self.segments);
+        self.order = (0..self.segments.len()).collect();
+        self.order.shuffle(&mut rand::thread_rng());
+        self.added = 0;
+    }
+}
+
+impl<T: Scalar> RandomizedTrapezoidalMap<T> {
+    pub fn build(segments: Vec<Segment<T>>) -> Self {
+        let mut algo = Self {
+            map: TrapezoidalMap::new(&segments),
+            segments,
+            order: vec![],
+            added: 0,
+        };
+        algo.reset();
+        algo
+    }
+
+    /// true when every segment is added
+    pub fn is_done(&self) -> bool {
+        self.added == self.order.len()
+    }
+
+    /// the segments added so far, by index in the input
+    pub fn added(&self) -> &[usize] {
+        &self.order[..self.added]
+    }
+
+    /// the map of the segments added so far
+    pub fn map(&self) -> &TrapezoidalMap<T> {
+        &self.map
+    }
+}
+
+impl<T: Field> RandomizedTrapezoidalMap<T> {
+    /// like `build`, but rejects NaN and infinite coordinates, repeated
+    /// segments and segments meeting in a point other than an endpoint of both.
+    /// The crossings are found with `SweepPlane`, which needs a field, so maps
+    /// of integer coordinates go through `build` unchecked
+    pub fn try_build(segments: Vec<Segment<T>>) -> Result<Self> {
+        check_finite(segments.iter().map(|s| s.is_finite()))?;
+
+        let mut ends: Vec<_> = segments
+            .iter()
+            .map(|s| {
+                let (p, q) = (flat(&s.start), flat(&s.end));
+                match point_cmp(&p, &q) {
+                    Ordering::Greater => (q, p),
+                    _ => (p, q),
+                }
+            })
+            .collect();
+        ends.sort();
+        ends.dedup();
+        let intersections = SweepPlane::build(segments.clone()).calculate();
+        if ends.len() < segments.len() || intersections.values().any(|i| !i.interior.is_empty()) {
+            return Err(GalmetryError::DegenerateInput(
+                "the segments cross or touch inside",
+            ));
+        }
+        Ok(Self::build(segments))
+    }
+}
+
+/// the point in the plane
+fn flat<T: Scalar>(p: &Point<T>) -> Point<T> {
+    Point::from2d(p.x, p.y)
+}
+
+/// by x, then by y
+fn point_cmp<T: Scalar>(p: &Point<T>, q: &Point<T>) -> Ordering {
+    p.x.partial_cmp(&q.x)
+        .unwrap_or(Ordering::Equal)
+        .then(p.y.partial_cmp(&q.y).unwrap_or(Ordering::Equal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RandomizedTrapezoidalMap, Trapezoid, TrapezoidalMap};
+    use crate::{
+        algorithms::algorithm::Algorithm,
+        error::GalmetryError,
+        geometry::{point::Point, segment::Segment, test_utils::non_crossing},
+    };
+
+    /// the segment directly above p, by brute force
+    fn above(segments: &[Segment], p: &Point) -> Option<usize> {
+        let mut best: Option<(f64, usize)> = None;
+        for (i, s) in segments.iter().enumerate() {
+            let (a, b) = (s.start, s.end);
+            let (a, b) = if a.x < b.x { (a, b) } else { (b, a) };
+            if p.x <= a.x || b.x <= p.x {
+                continue;
+            }
+            let y = a.y + (b.y - a.y) * (p.x - a.x) / (b.x - a.x);
+            if y > p.y && best.is_none_or(|(best, _)| y < best) {
+                best = Some((y, i));
+            }
+        }
+        best.map(|(_, i)| i)
+    }
+
+    /// up to n segments crossing none of the others
+    fn disjoint_segments(n: usize) -> Vec<Segment> {
+        let mut segments: Vec<Segment> = vec![];
+        while segments.len() < n {
+            let mut s = Segment::random(0.0..100.0);
+            s.start.z = 0.0;
+            s.end.z = 0.0;
+            let mut candidate = segments.clone();
+            candidate.push(s.clone());
+            if RandomizedTrapezoidalMap::try_build(candidate).is_ok() {
+                segments.push(s);
+            }
+        }
+        segments
+    }
+
+    #[test]
+    fn single_segment() {
+        let segments = vec![Segment::new([0.0, 0.0], [4.0, 2.0])];
+        let map = RandomizedTrapezoidalMap::build(segments).calculate();
+
+        assert_eq!(map.trapezoids().len(), 4);
+        assert_eq!(map.segment_above(&Point::from2d(2.0, 0.0)), Some(0));
+        assert_eq!(map.segment_below(&Point::from2d(2.0, 0.0)), None);
+        assert_eq!(map.segment_below(&Point::from2d(2.0, 3.0)), Some(0));
+        assert_eq!(map.segment_above(&Point::from2d(-1.0, 0.0)), None);
+        assert_eq!(map.segment_above(&Point::from2d(5.0, 0.0)), None);
+        // a point on the segment is below it
+        assert_eq!(map.segment_above(&Point::from2d(2.0, 1.0)), Some(0));
+    }
+
+    #[test]
+    fn segments_above_random_points() {
+        let segments = disjoint_segments(40);
+        let map = RandomizedTrapezoidalMap::build(segments.clone()).calculate();
+
+        // 3n + 1 trapezoids for segments without shared endpoints
+        assert_eq!(map.trapezoids().len(), 3 * segments.len() + 1);
+        for _ in 0..500 {
+            let p = Point::from2d(rand::random::<f64>() * 100.0, rand::random::<f64>() * 100.0);
+            assert_eq!(map.segment_above(&p), above(&segments, &p));
+        }
+    }
+
+    #[test]
+    fn floor_map_with_shared_endpoints() {
+        // a room split by a wall, with a corridor on the right
+        let segments: Vec<Segment<i64>> = vec![
+            Segment::new([0, 0], [8, 0]),
+            Segment::new([8, 0], [8, 8]),
+            Segment::new([0, 8], [8, 8]),
+            Segment::new([0, 0], [0, 8]),
+            Segment::new([0, 4], [6, 4]),
+            Segment::new([8, 0], [12, 0]),
+            Segment::new([8, 8], [12, 8]),
+        ];
+        let map = RandomizedTrapezoidalMap::build(segments).calculate();
+
+        assert_eq!(map.segment_above(&Point::from2d(3, 2)), Some(4));
+        assert_eq!(map.segment_below(&Point::from2d(3, 2)), Some(0));
+        assert_eq!(map.segment_above(&Point::from2d(3, 6)), Some(2));
+        assert_eq!(map.segment_below(&Point::from2d(3, 6)), Some(4));
+        assert_eq!(map.segment_above(&Point::from2d(7, 4)), Some(2));
+        assert_eq!(map.segment_below(&Point::from2d(7, 4)), Some(0));
+        assert_eq!(map.segment_above(&Point::from2d(10, 4)), Some(6));
+        assert_eq!(map.segment_below(&Point::from2d(10, 4)), Some(5));
+        assert_eq!(map.segment_above(&Point::from2d(3, 9)), None);
+        assert_eq!(map.segment_below(&Point::from2d(3, 9)), Some(2));
+        assert_eq!(map.segment_above(&Point::from2d(-1, 4)), None);
+
+        // points in the same room share the trapezoid
+        assert_eq!(
+            map.locate(&Point::from2d(1, 1)),
+            map.locate(&Point::from2d(5, 3))
+        );
+        assert_ne!(
+            map.locate(&Point::from2d(1, 1)),
+            map.locate(&Point::from2d(1, 5))
+        );
+    }
+
+    #[test]
+    fn trapezoidal_map_steps() {
+        let segments = disjoint_segments(10);
+        let mut algo = RandomizedTrapezoidalMap::build(segments.clone());
+
+        for i in 0..segments.len() {
+            assert!(!algo.is_done());
+            assert_eq!(algo.map().trapezoids().len(), 3 * i + 1);
+            algo.step();
+            assert_eq!(algo.added().len(), i + 1);
+        }
+        assert!(algo.is_done());
+        let map = algo.map().clone();
+        assert_eq!(map.trapezoids().len(), 3 * segments.len() + 1);
+
+        // the map does not depend on the order of the segments
+        let faces = |map: &TrapezoidalMap| {
+            let mut faces: Vec<_> = map
+                .trapezoids()
+                .iter()
+                .map(|&t| {
+                    let t = map.trapezoid(t);
+                    (t.top, t.bottom, t.leftp, t.rightp)
+                })
+                .collect();
+            faces.sort();
+            faces
+        };
+        assert_eq!(faces(&algo.calculate()), faces(&map));
+    }
+
+    #[test]
+    fn long_segment_under_short_ones() {
+        let n = 50;
+        let mut segments: Vec<Segment> = (0..n)
+            .map(|i| Segment::new([2.0 * i as f64, 1.0], [2.0 * i as f64 + 1.0, 1.0]))
+            .collect();
+        segments.push(Segment::new([-1.0, 0.0], [2.0 * n as f64, 0.0]));
+        // the long segment last, so that it crosses every trapezoid
+        let mut map = TrapezoidalMap::new(&segments);
+        for s in 0..segments.len() {
+            map.insert(s);
+        }
+
+        assert_eq!(map.trapezoids().len(), 3 * segments.len() + 1);
+        type Link = fn(&Trapezoid<f64>) -> Option<usize>;
+        for t in map.trapezoids() {
+            let trapezoid = map.trapezoid(t);
+            // each neighbor links back
+            let back: [(_, Link); 4] = [
+                (trapezoid.upper_left, |n| n.upper_right),
+                (trapezoid.lower_left, |n| n.lower_right),
+                (trapezoid.upper_right, |n| n.upper_left),
+                (trapezoid.lower_right, |n| n.lower_left),
+            ];
+            for (neighbor, link) in back {
+                if let Some(neighbor) = neighbor.map(|n| map.trapezoid(n)) {
+                    assert!(neighbor.alive);
+                    assert_eq!(link(neighbor), Some(t));
+                }
+            }
+        }
+        // a single trapezoid under the long segment
+        let below = map.locate(&Point::from2d(0.0, -1.0));
+        for i in 0..n {
+            let x = 2.0 * i as f64 + 0.5;
+            assert_eq!(map.locate(&Point::from2d(x, -1.0)), below);
+            assert_eq!(map.segment_above(&Point::from2d(x, 0.5)), Some(i));
+            assert_eq!(map.segment_below(&Point::from2d(x, 0.5)), Some(n));
+            assert_eq!(map.segment_below(&Point::from2d(x + 1.0, 0.5)), Some(n));
+        }
+        // the pieces between the short segments link left to right
+        let mut t = map.locate(&Point::from2d(-0.5, 0.5));
+        let mut pieces = 1;
+        while let Some(next) = map.trapezoid(t).lower_right {
+            t = next;
+            pieces += 1;
+        }
+        assert_eq!(pieces, 2 * n + 1);
+    }
+
+    #[test]
+    fn trapezoidal_map_dcel() {
+        // a room split by a wall, with a vertical segment and shared endpoints
+        let segments: Vec<Segment> = vec![
+            Segment::new([0.0, 0.0], [8.0, 0.0]),
+            Segment::new([8.0, 0.0], [8.0, 8.0]),
+            Segment::new([0.0, 8.0], [8.0, 8.0]),
+            Segment::new([0.0, 4.0], [6.0, 4.0]),
+        ];
+        let map = RandomizedTrapezoidalMap::build(segments).calculate();
+        let dcel = map.to_dcel();
+
+        assert_eq!(dcel.validate(), Ok(()));
+        // the box goes from -0.8 to 8.8, each trapezoid with a width has a face
+        let wide: Vec<usize> = map
+            .trapezoids()
+            .into_iter()
+            .filter(|&t| {
+                let t = map.trapezoid(t);
+                t.leftp.zip(t.rightp).is_none_or(|(l, r)| l.x < r.x)
+            })
+            .collect();
+        assert_eq!(dcel.faces().len(), wide.len() + 1);
+        let mut attributes: Vec<usize> = (1..dcel.faces().len())
+            .map(|f| dcel.attribute(f).unwrap())
+            .collect();
+        attributes.sort();
+        assert_eq!(attributes, wide);
+
+        let area: f64 = (1..dcel.faces().len())
+            .map(|f| dcel.boundary(f).unwrap().area())
+            .sum();
+        assert!((area - 9.6 * 9.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn random_trapezoidal_map_dcel() {
+        for _ in 0..20 {
+            let map = RandomizedTrapezoidalMap::build(non_crossing(30, true)).calculate();
+            let dcel = map.to_dcel();
+            assert_eq!(dcel.validate(), Ok(()));
+
+            // the faces are distinct trapezoids, clipped to the box they cover
+            let mut attributes = vec![];
+            let mut area = 0.0;
+            for f in 1..dcel.faces().len() {
+                let trapezoid = map.trapezoid(dcel.attribute(f).unwrap());
+                let polygon = dcel.boundary(f).unwrap();
+                assert!(polygon.vertices().iter().all(|v| {
+                    trapezoid.leftp.is_none_or(|p| p.x <= v.x)
+                        && trapezoid.rightp.is_none_or(|p| v.x <= p.x)
+                }));
+                attributes.push(dcel.attribute(f).unwrap());
+                area += polygon.area();
+            }
+            attributes.sort();
+            attributes.dedup();
+            assert_eq!(attributes.len(), dcel.faces().len() - 1);
+
+            let (min, max) = map.bounds();
+            assert!((area - (max.x - min.x) * (max.y - min.y)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn try_trapezoidal_map() {
+        let crossing = vec![
+            Segment::new([0.0, 0.0], [2.0, 2.0]),
+            Segment::new([0.0, 2.0], [2.0, 0.0]),
+        ];
+        assert_eq!(
+            RandomizedTrapezoidalMap::try_build(crossing).err(),
+            Some(GalmetryError::DegenerateInput(
+                "the segments cross or touch inside"
+            ))
+        );
+
+        let touching = vec![
+            Segment::new([0.0, 0.0], [2.0, 0.0]),
+            Segment::new([1.0, 0.0], [1.0, 2.0]),
+        ];
+        assert!(RandomizedTrapezoidalMap::try_build(touching).is_err());
+
+        // the same segment, from the other end
+        let repeated = vec![
+            Segment::new([0.0, 0.0], [2.0, 0.0]),
+            Segment {
+                start: Point::from2d(2.0, 0.0),
+                end: Point::from2d(0.0, 0.0),
+            },
+        ];
+        assert!(RandomizedTrapezoidalMap::try_build(repeated).is_err());
+
+        let sharing = vec![
+            Segment::new([0.0, 0.0], [2.0, 0.0]),
+            Segment::new([2.0, 0.0], [2.0, 2.0]),
+        ];
+        assert!(RandomizedTrapezoidalMap::try_build(sharing).is_ok());
+    }
+}
@@ -0,0 +1,697 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashSet},
+    ops::Bound::{Excluded, Unbounded},
+};
+
+use super::algorithm::Algorithm;
+use crate::{
+    error::{GalmetryError, Result},
+    geometry::{
+        point::Point,
+        polygon::Polygon,
+        predicates::Orientation,
+        scalar::{Field, Scalar},
+        segment::Segment,
+    },
+};
+
+/// The kind of a polygon vertex with respect to the sweep line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VertexType {
+    /// both neighbours below, interior angle less than pi
+    Start,
+    /// both neighbours below, interior angle greater than pi
+    Split,
+    /// both neighbours above, interior angle less than pi
+    End,
+    /// both neighbours above, interior angle greater than pi
+    Merge,
+    Regular,
+}
+
+/// The stage the algorithm is currently running
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriangulationPhase {
+    /// the sweep adding the diagonals that split the polygon in y-monotone pieces
+    Partition,
+    /// the triangulation of the monotone pieces, one for each step
+    Triangulate,
+    Done,
+}
+
+/// An edge crossed by the sweep line, the interior of the polygon is on its right
+#[derive(Clone)]
+struct StatusEdge<T> {
+    segment: Segment<T>,
+
+    // position of the edge in the counterclockwise ring, `usize::MAX` for a probe
+    edge: usize,
+}
+
+impl<T: Scalar> StatusEdge<T> {
+    /// Less if other lies on the right of self, Greater if it lies on the left,
+    /// None if it can not be decided looking at the endpoints of other
+    fn side_of(&self, other: &Self) -> Option<Ordering> {
+        let seg = &self.segment;
+        // along a horizontal segment left and right are north and south
+        if seg.start.y == seg.end.y {
+            return None;
+        }
+
+        // the segment goes downward, so its left side is the right one of the sweep line
+        let o1 = T::orientation(&seg.start, &seg.end, &other.segment.start);
+        let o2 = T::orientation(&seg.start, &seg.end, &other.segment.end);
+        match (o1, o2) {
+            (Orientation::Left, Orientation::Left | Orientation::Collinear)
+            | (Orientation::Collinear, Orientation::Left) => Some(Ordering::Less),
+            (Orientation::Right, Orientation::Right | Orientation::Collinear)
+            | (Orientation::Collinear, Orientation::Right) => Some(Ordering::Greater),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Scalar> PartialEq for StatusEdge<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Scalar> Eq for StatusEdge<T> {}
+
+impl<T: Scalar> PartialOrd for StatusEdge<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Scalar> Ord for StatusEdge<T> {
+    /// the edges of a simple polygon never cross, so their order along the sweep
+    /// line can be decided by the side where the other edge lies
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.edge == other.edge {
+            return Ordering::Equal;
+        }
+
+        self.side_of(other)
+            .or_else(|| other.side_of(self).map(Ordering::reverse))
+            .unwrap_or_else(|| {
+                self.segment
+                    .start
+                    .sweep_plane_cmp(&other.segment.start)
+                    .then(self.edge.cmp(&other.edge))
+            })
+    }
+}
+
+/// Triangulation of a simple polygon (de Berg et al., chapter 3): a plane sweep
+/// splits the polygon in y-monotone pieces, then each piece is triangulated
+/// in linear time. Vertexes are referred by their index in the polygon
+pub struct MonotoneTriangulation<T = f64> {
+    // the polygon given by the user
+    polygon: Polygon<T>,
+
+    // the polygon indexes in counterclockwise order, the algorithm works on the
+    // positions of this ring
+    ring: Vec<usize>,
+
+    types: Vec<VertexType>,
+
+    // the positions sorted in sweep plane order
+    events: Vec<usize>,
+
+    // the edges crossed by the sweep line with the interior on their right,
+    // the i-th edge goes from the i-th position to the next one
+    status: BTreeSet<StatusEdge<T>>,
+
+    // the helper of each edge in the status
+    helper: Vec<Option<usize>>,
+
+    diagonals: Vec<(usize, usize)>,
+
+    // the y-monotone pieces, as positions in counterclockwise order
+    pieces: Vec<Vec<usize>>,
+
+    // the output of the algorithm
+    triangles: Vec<[usize; 3]>,
+
+    phase: TriangulationPhase,
+
+    // index of the next event or of the next piece
+    next: usize,
+}
+
+impl<T: Scalar> Algorithm for MonotoneTriangulation<T> {
+    type Output = Vec<[usize; 3]>;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while !self.is_done() {
+            self.step();
+        }
+
+        self.triangles.clone()
+    }
+
+    fn step(&mut self) {
+        match self.phase {
+            TriangulationPhase::Partition => {
+                let v = self.events[self.next];
+                self.handle_vertex(v);
+
+                self.next += 1;
+                if self.next == self.events.len() {
+                    self.pieces = self.monotone_pieces();
+                    self.phase = TriangulationPhase::Triangulate;
+                    self.next = 0;
+                }
+            }
+            TriangulationPhase::Triangulate => {
+                let piece = self.pieces[self.next].clone();
+                self.triangulate_monotone(&piece);
+
+                self.next += 1;
+                if self.next == self.pieces.len() {
+                    self.phase = TriangulationPhase::Done;
+                }
+            }
+            TriangulationPhase::Done => {}
+        }
+    }
+
+    fn reset(&mut self) {
+        let n = self.polygon.len();
+        self.ring = match self.polygon.is_cw() {
+            true => (0..n).rev().collect(),
+            false => (0..n).collect(),
+        };
+        self.types = (0..n).map(|v| self.vertex_type_at(v)).collect();
+
+        let mut events: Vec<usize> = (0..n).collect();
+        events.sort_by(|&a, &b| self.point(a).sweep_plane_cmp(&self.point(b)));
+        self.events = events;
+
+        self.status = BTreeSet::new();
+        self.helper = vec![None; n];
+        self.diagonals = vec![];
+        self.pieces = vec![];
+        self.triangles = vec![];
+        self.next = 0;
+        self.phase = match n {
+            0..=2 => TriangulationPhase::Done,
+            _ => TriangulationPhase::Partition,
+        };
+    }
+}
+
+impl<T: Scalar> MonotoneTriangulation<T> {
+    pub fn build(polygon: Polygon<T>) -> Self {
+        let mut algo = Self {
+            polygon,
+            ring: vec![],
+            types: vec![],
+            events: vec![],
+            status: BTreeSet::new(),
+            helper: vec![],
+            diagonals: vec![],
+            pieces: vec![],
+            triangles: vec![],
+            phase: TriangulationPhase::Done,
+            next: 0,
+        };
+        algo.reset();
+        algo
+    }
+
+    pub fn phase(&self) -> TriangulationPhase {
+        self.phase
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase == TriangulationPhase::Done
+    }
+
+    /// the vertex handled by the last step of the partition
+    pub fn event_vertex(&self) -> Option<usize> {
+        match (self.phase, self.next) {
+            (TriangulationPhase::Partition, 0) => None,
+            (TriangulationPhase::Partition, next) => Some(self.ring[self.events[next - 1]]),
+            _ => None,
+        }
+    }
+
+    /// the type of the vertex with the given index
+    pub fn vertex_type(&self, vertex: usize) -> VertexType {
+        let position = self.ring.iter().position(|&v| v == vertex);
+        self.types[position.expect("vertex out of the polygon")]
+    }
+
+    /// the diagonals added so far by the partition
+    pub fn diagonals(&self) -> Vec<(usize, usize)> {
+        self.diagonals
+            .iter()
+            .map(|&(a, b)| (self.ring[a], self.ring[b]))
+            .collect()
+    }
+
+    /// the y-monotone pieces, each one in counterclockwise order
+    pub fn pieces(&self) -> Vec<Vec<usize>> {
+        self.pieces
+            .iter()
+            .map(|piece| piece.iter().map(|&v| self.ring[v]).collect())
+            .collect()
+    }
+
+    /// the triangles found so far, each one in counterclockwise order
+    pub fn triangles(&self) -> &Vec<[usize; 3]> {
+        &self.triangles
+    }
+
+    fn point(&self, v: usize) -> Point<T> {
+        self.polygon.vertices()[self.ring[v]]
+    }
+
+    fn prev(&self, v: usize) -> usize {
+        (v + self.ring.len() - 1) % self.ring.len()
+    }
+
+    fn succ(&self, v: usize) -> usize {
+        (v + 1) % self.ring.len()
+    }
+
+    /// true if a comes after b in the sweep plane order
+    fn below(&self, a: usize, b: usize) -> bool {
+        self.point(a).sweep_plane_cmp(&self.point(b)) == Ordering::Greater
+    }
+
+    fn vertex_type_at(&self, v: usize) -> VertexType {
+        let (prev, succ) = (self.prev(v), self.succ(v));
+        let convex = T::orientation(&self.point(prev), &self.point(v), &self.point(succ))
+            == Orientation::Left;
+
+        match (self.below(prev, v), self.below(succ, v), convex) {
+            (true, true, true) => VertexType::Start,
+            (true, true, false) => VertexType::Split,
+            (false, false, true) => VertexType::End,
+            (false, false, false) => VertexType::Merge,
+            _ => VertexType::Regular,
+        }
+    }
+
+    fn status_edge(&self, edge: usize) -> StatusEdge<T> {
+        StatusEdge {
+            segment: Segment::new(self.point(edge), self.point(self.succ(edge))),
+            edge,
+        }
+    }
+
+    fn insert_edge(&mut self, edge: usize, helper: usize) {
+        self.status.insert(self.status_edge(edge));
+        self.helper[edge] = Some(helper);
+    }
+
+    /// remove the edge, adding a diagonal if its helper is a merge vertex
+    fn remove_edge(&mut self, edge: usize, v: usize) {
+        self.connect_merge_helper(edge, v);
+        self.status.remove(&self.status_edge(edge));
+        self.helper[edge] = None;
+    }
+
+    fn connect_merge_helper(&mut self, edge: usize, v: usize) {
+        if let Some(helper) = self.helper[edge] {
+            if self.types[helper] == VertexType::Merge {
+                self.diagonals.push((v, helper));
+            }
+        }
+    }
+
+    /// the edge of the status directly on the left of v
+    fn left_edge(&self, v: usize) -> Option<usize> {
+        let probe = StatusEdge {
+            segment: Segment::new(self.point(v), self.point(v)),
+            edge: usize::MAX,
+        };
+        self.status
+            .range((Unbounded, Excluded(probe)))
+            .next_back()
+            .map(|left| left.edge)
+    }
+
+    fn handle_vertex(&mut self, v: usize) {
+        let prev_edge = self.prev(v);
+        match self.types[v] {
+            VertexType::Start => self.insert_edge(v, v),
+            VertexType::End => self.remove_edge(prev_edge, v),
+            VertexType::Split => {
+                if let Some(left) = self.left_edge(v) {
+                    if let Some(helper) = self.helper[left] {
+                        self.diagonals.push((v, helper));
+                    }
+                    self.helper[left] = Some(v);
+                }
+                self.insert_edge(v, v);
+            }
+            VertexType::Merge => {
+                self.remove_edge(prev_edge, v);
+                if let Some(left) = self.left_edge(v) {
+                    self.connect_merge_helper(left, v);
+                    self.helper[left] = Some(v);
+                }
+            }
+            // the interior of the polygon is on the right of v
+            VertexType::Regular if self.below(v, self.prev(v)) => {
+                self.remove_edge(prev_edge, v);
+                self.insert_edge(v, v);
+            }
+            VertexType::Regular => {
+                if let Some(left) = self.left_edge(v) {
+                    self.connect_merge_helper(left, v);
+                    self.helper[left] = Some(v);
+                }
+            }
+        }
+    }
+
+    /// order of p and q around center, counterclockwise starting from the east
+    fn angle_cmp(center: &Point<T>, p: &Point<T>, q: &Point<T>) -> Ordering {
+        let lower_half = |r: &Point<T>| r.y < center.y || (r.y == center.y && r.x < center.x);
+
+        match (lower_half(p), lower_half(q)) {
+            (false, true) => Ordering::Less,
+            (true, false) => Ordering::Greater,
+            _ => match T::orientation(center, p, q) {
+                Orientation::Left => Ordering::Less,
+                Orientation::Right => Ordering::Greater,
+                Orientation::Collinear => Ordering::Equal,
+            },
+        }
+    }
+
+    /// the faces of the polygon split by the diagonals. Walking an edge u -> v,
+    /// the face continues with the neighbour of v coming before u counterclockwise
+    fn monotone_pieces(&self) -> Vec<Vec<usize>> {
+        let n = self.ring.len();
+        let mut neighbours: Vec<Vec<usize>> =
+            (0..n).map(|v| vec![self.prev(v), self.succ(v)]).collect();
+        for &(a, b) in self.diagonals.iter() {
+            neighbours[a].push(b);
+            neighbours[b].push(a);
+        }
+        for (v, around) in neighbours.iter_mut().enumerate() {
+            let center = self.point(v);
+            around.sort_by(|&p, &q| Self::angle_cmp(&center, &self.point(p), &self.point(q)));
+        }
+
+        let mut starts: Vec<(usize, usize)> = (0..n).map(|v| (v, self.succ(v))).collect();
+        for &(a, b) in self.diagonals.iter() {
+            starts.push((a, b));
+            starts.push((b, a));
+        }
+
+        let mut visited = HashSet::new();
+        let mut pieces = vec![];
+        for start in starts {
+            let mut piece = vec![];
+            let (mut u, mut v) = start;
+            while visited.insert((u, v)) {
+                piece.push(u);
+                let around = &neighbours[v];
+                let i = around.iter().position(|&w| w == u).unwrap_or(0);
+                (u, v) = (v, around[(i + around.len() - 1) % around.len()]);
+            }
+            if !piece.is_empty() {
+                pieces.push(piece);
+            }
+        }
+        pieces
+    }
+
+    /// triangulate a y-monotone piece given in counterclockwise order
+    fn triangulate_monotone(&mut self, piece: &[usize]) {
+        let n = piece.len();
+        if n < 3 {
+            return;
+        }
+
+        let sweep_cmp = |a: &usize, b: &usize| {
+            self.point(piece[*a])
+                .sweep_plane_cmp(&self.point(piece[*b]))
+        };
+        let top = (0..n).min_by(sweep_cmp).unwrap_or(0);
+        let bottom = (0..n).max_by(sweep_cmp).unwrap_or(0);
+
+        // counterclockwise from the top the left chain goes down to the bottom,
+        // the right chain comes back up: both are walked down from the top and
+        // merged in sweep plane order. The bottom is on the right chain
+        let left: Vec<usize> = (0..n)
+            .map(|i| (top + i) % n)
+            .take_while(|&i| i != bottom)
+            .collect();
+        let right: Vec<usize> = (1..n)
+            .map(|i| (top + n - i) % n)
+            .take_while(|&i| i != bottom)
+            .chain([bottom])
+            .collect();
+
+        let mut sorted: Vec<(usize, bool)> = Vec::with_capacity(n);
+        let (mut l, mut r) = (0, 0);
+        while l < left.len() || r < right.len() {
+            let take_left = r == right.len()
+                || (l < left.len() && sweep_cmp(&left[l], &right[r]) == Ordering::Less);
+            match take_left {
+                true => {
+                    sorted.push((piece[left[l]], true));
+                    l += 1;
+                }
+                false => {
+                    sorted.push((piece[right[r]], false));
+                    r += 1;
+                }
+            }
+        }
+
+        let mut stack = vec![sorted[0], sorted[1]];
+        for &(u, on_left) in sorted.iter().take(n - 1).skip(2) {
+            let (_, top_on_left) = stack[stack.len() - 1];
+            if on_left != top_on_left {
+                for pair in stack.windows(2) {
+                    self.push_triangle(u, pair[0].0, pair[1].0);
+                }
+                let last = stack[stack.len() - 1];
+                stack = vec![last, (u, on_left)];
+            } else {
+                let mut last = stack.pop().expect("the stack has two vertexes");
+                while let Some(&(top, _)) = stack.last() {
+                    let turn =
+                        T::orientation(&self.point(top), &self.point(last.0), &self.point(u));
+                    let inside = match on_left {
+                        true => turn == Orientation::Left,
+                        false => turn == Orientation::Right,
+                    };
+                    if !inside {
+                        break;
+                    }
+                    self.push_triangle(u, last.0, top);
+                    last = stack.pop().expect("the stack is not empty");
+                }
+                stack.push(last);
+                stack.push((u, on_left));
+            }
+        }
+
+        let (u, _) = sorted[n - 1];
+        for pair in stack.windows(2) {
+            self.push_triangle(u, pair[0].0, pair[1].0);
+        }
+    }
+
+    /// add the triangle in counterclockwise order, as polygon indexes
+    fn push_triangle(&mut self, a: usize, b: usize, c: usize) {
+        let triangle = match T::orientation(&self.point(a), &self.point(b), &self.point(c)) {
+            Orientation::Right => [a, c, b],
+            _ => [a, b, c],
+        };
+        self.triangles.push(triangle.map(|v| self.ring[v]));
+    }
+}
+
+impl<T: Field> MonotoneTriangulation<T> {
+    /// like `build`, but rejects polygons that are not simple
+    pub fn try_build(polygon: Polygon<T>) -> Result<Self> {
+        let polygon = Polygon::try_new(polygon.vertices().clone())?;
+        if !polygon.is_simple() {
+            return Err(GalmetryError::DegenerateInput("the polygon is not simple"));
+        }
+        Ok(Self::build(polygon))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MonotoneTriangulation, TriangulationPhase, VertexType};
+    use crate::{
+        algorithms::algorithm::Algorithm,
+        error::GalmetryError,
+        geometry::{point::Point, points::Points, polygon::Polygon},
+    };
+    use rand::Rng;
+
+    /// the triangles cover the polygon: their areas sum up to the polygon one
+    /// and each one is counterclockwise
+    fn assert_triangulation(polygon: &Polygon, triangles: &[[usize; 3]]) {
+        assert_eq!(triangles.len(), polygon.len() - 2);
+
+        let v = polygon.vertices();
+        let mut area = 0.0;
+        for t in triangles {
+            let triangle: Polygon = Polygon::new(Points::from(vec![v[t[0]], v[t[1]], v[t[2]]]));
+            assert!(triangle.is_ccw(), "{:?} is not counterclockwise", t);
+            area += triangle.area();
+        }
+        assert!((area - polygon.area()).abs() <= 1e-9 * polygon.area());
+    }
+
+    /// a random star-shaped polygon around (0, 0)
+    fn random_polygon(n: usize) -> Polygon {
+        let mut rng = rand::thread_rng();
+        let mut angles: Vec<f64> = (0..n)
+            .map(|_| rng.gen_range(0.0..std::f64::consts::TAU))
+            .collect();
+        angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let vertices: Vec<Point> = angles
+            .iter()
+            .map(|angle| {
+                let radius = rng.gen_range(0.2..1.0);
+                Point::from2d(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+        Polygon::new(Points::from(vertices))
+    }
+
+    #[test]
+    fn triangulate_square() {
+        let polygon: Polygon = vec![[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0]].into();
+        let triangles = MonotoneTriangulation::build(polygon.clone()).calculate();
+
+        assert_triangulation(&polygon, &triangles);
+    }
+
+    #[test]
+    fn triangulate_clockwise() {
+        let polygon: Polygon =
+            vec![[0.0, 4.0], [4.0, 4.0], [4.0, 0.0], [0.0, 0.0], [2.0, 2.0]].into();
+        let triangles = MonotoneTriangulation::build(polygon.clone()).calculate();
+
+        assert_triangulation(&polygon, &triangles);
+    }
+
+    #[test]
+    fn triangulate_split_and_merge() {
+        // a split vertex in (2, 1) and merge vertexes in (3, 3) and (1, 3)
+        let polygon: Polygon = vec![
+            [0.0, 0.0],
+            [2.0, 1.0],
+            [4.0, 0.0],
+            [4.0, 4.0],
+            [3.0, 3.0],
+            [2.0, 4.0],
+            [1.0, 3.0],
+            [0.0, 4.0],
+        ]
+        .into();
+        let mut algo = MonotoneTriangulation::build(polygon.clone());
+
+        assert_eq!(algo.vertex_type(1), VertexType::Split);
+        assert_eq!(algo.vertex_type(4), VertexType::Merge);
+        assert_eq!(algo.vertex_type(6), VertexType::Merge);
+        assert_eq!(algo.vertex_type(3), VertexType::Start);
+        assert_eq!(algo.vertex_type(0), VertexType::End);
+
+        let triangles = algo.calculate();
+        assert_eq!(algo.pieces().len(), algo.diagonals().len() + 1);
+        assert_triangulation(&polygon, &triangles);
+    }
+
+    #[test]
+    fn triangulate_horizontal_edges() {
+        let polygon: Polygon = vec![
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [2.0, 0.0],
+            [2.0, 2.0],
+            [1.0, 1.0],
+            [0.0, 2.0],
+        ]
+        .into();
+        let triangles = MonotoneTriangulation::build(polygon.clone()).calculate();
+
+        assert_triangulation(&polygon, &triangles);
+    }
+
+    #[test]
+    fn triangulate_step() {
+        let polygon = random_polygon(20);
+        let mut algo = MonotoneTriangulation::build(polygon);
+
+        for _ in 0..20 {
+            assert_eq!(algo.phase(), TriangulationPhase::Partition);
+            algo.step();
+        }
+        assert!(algo.event_vertex().is_none());
+        assert_eq!(algo.phase(), TriangulationPhase::Triangulate);
+
+        while !algo.is_done() {
+            algo.step();
+        }
+        let stepped = algo.triangles().clone();
+        assert_eq!(algo.calculate(), stepped);
+    }
+
+    #[test]
+    fn triangulate_random_star_polygons() {
+        for _ in 0..200 {
+            let polygon = random_polygon(30);
+            let triangles = MonotoneTriangulation::build(polygon.clone()).calculate();
+
+            assert_triangulation(&polygon, &triangles);
+        }
+    }
+
+    #[test]
+    fn triangulate_integer_comb() {
+        // each peak of the zigzag bottom is a split vertex
+        let mut vertices: Vec<[i32; 2]> = vec![];
+        for i in 0..10 {
+            vertices.push([2 * i, 0]);
+            vertices.push([2 * i + 1, 5]);
+        }
+        vertices.push([20, 0]);
+        vertices.push([20, 10]);
+        vertices.push([0, 10]);
+        let polygon: Polygon<i32> = vertices.into();
+
+        let triangles = MonotoneTriangulation::build(polygon.clone()).calculate();
+        assert_eq!(triangles.len(), polygon.len() - 2);
+
+        let doubled_area: i32 = triangles
+            .iter()
+            .map(|t| {
+                let v = polygon.vertices();
+                Polygon::new(Points::from(vec![v[t[0]], v[t[1]], v[t[2]]])).doubled_signed_area()
+            })
+            .sum();
+        assert_eq!(doubled_area, polygon.doubled_signed_area());
+    }
+
+    #[test]
+    fn try_build_rejects_bowtie() {
+        let bowtie: Polygon = vec![[0.0, 0.0], [4.0, 4.0], [4.0, 0.0], [0.0, 4.0]].into();
+
+        assert!(matches!(
+            MonotoneTriangulation::try_build(bowtie),
+            Err(GalmetryError::DegenerateInput(_))
+        ));
+    }
+}
@@ -0,0 +1,653 @@
+use std::collections::BTreeMap;
+
+use super::algorithm::Algorithm;
+use crate::{
+    error::{check_finite, GalmetryError, Result},
+    geometry::{
+        point::Point,
+        points::Points,
+        polygon::Polygon,
+        predicates::{orientation, Orientation},
+    },
+};
+
+/// A piece of the bisector of two sites. Walking from start to end the first
+/// site is on the left and the second one on the right
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VoronoiEdge {
+    pub sites: [usize; 2],
+
+    /// index of a Voronoi vertex, None if the edge comes from infinity
+    pub start: Option<usize>,
+
+    /// index of a Voronoi vertex, None if the edge goes to infinity
+    pub end: Option<usize>,
+}
+
+impl VoronoiEdge {
+    /// true if both ends are Voronoi vertexes
+    pub fn is_finite(&self) -> bool {
+        self.start.is_some() && self.end.is_some()
+    }
+}
+
+/// The Voronoi diagram of a set of sites. The cells are unbounded on the
+/// convex hull, so they are given clipped to a box
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VoronoiDiagram {
+    pub sites: Points,
+    pub vertices: Points,
+    pub edges: Vec<VoronoiEdge>,
+}
+
+impl VoronoiDiagram {
+    /// the direction of the edge, from start to end
+    pub fn direction(&self, edge: &VoronoiEdge) -> Point {
+        let (left, right) = (self.sites[edge.sites[0]], self.sites[edge.sites[1]]);
+        Point::from2d(left.y - right.y, right.x - left.x)
+    }
+
+    /// the indexes of the sites sharing an edge with the given one
+    pub fn neighbors(&self, site: usize) -> Vec<usize> {
+        self.edges
+            .iter()
+            .filter_map(|edge| match edge.sites {
+                [a, b] if a == site => Some(b),
+                [a, b] if b == site => Some(a),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// the cell of a site clipped to the box with the given corners, as a
+    /// counterclockwise polygon. It is empty if the cell misses the box or if
+    /// the site repeats a previous one
+    pub fn cell(&self, site: usize, min: Point, max: Point) -> Polygon {
+        let s = self.sites[site];
+        if self
+            .sites
+            .iter()
+            .take(site)
+            .any(|p| p.x == s.x && p.y == s.y)
+        {
+            return Polygon::new(Points::new());
+        }
+
+        let mut cell = vec![
+            min,
+            Point::from2d(max.x, min.y),
+            max,
+            Point::from2d(min.x, max.y),
+        ];
+
+        for neighbor in self.neighbors(site) {
+            let n = self.sites[neighbor];
+            // the points closer to s than to n
+            let closer = |p: &Point| {
+                (p.x - s.x).powi(2) + (p.y - s.y).powi(2)
+                    - (p.x - n.x).powi(2)
+                    - (p.y - n.y).powi(2)
+            };
+            cell = clip(&cell, closer);
+        }
+
+        Polygon::new(Points::from(cell))
+    }
+
+    /// the cell of every site, see `cell`
+    pub fn cells(&self, min: Point, max: Point) -> Vec<Polygon> {
+        (0..self.sites.len())
+            .map(|site| self.cell(site, min, max))
+            .collect()
+    }
+}
+
+/// Sutherland–Hodgman clipping of a convex polygon, keeping the points where
+/// the linear function f is not positive
+fn clip(polygon: &[Point], f: impl Fn(&Point) -> f64) -> Vec<Point> {
+    let mut clipped = vec![];
+    for (i, p) in polygon.iter().enumerate() {
+        let q = &polygon[(i + 1) % polygon.len()];
+        let (fp, fq) = (f(p), f(q));
+        if fp <= 0.0 {
+            clipped.push(*p);
+        }
+        if (fp < 0.0 && fq > 0.0) || (fp > 0.0 && fq < 0.0) {
+            let t = fp / (fp - fq);
+            clipped.push(Point::from2d(p.x + t * (q.x - p.x), p.y + t * (q.y - p.y)));
+        }
+    }
+    clipped
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VoronoiEvent {
+    /// the sweep line reaches the site with the given index
+    Site(usize),
+
+    /// an arc shrinks to a point, the center of the circle becoming a vertex
+    Circle { arc: usize, center: Point },
+}
+
+/// An arc of the beach line
+#[derive(Clone, Debug)]
+struct Arc {
+    // identifies the arc for its circle event
+    id: usize,
+
+    site: usize,
+
+    // the key of the circle event removing the arc
+    circle: Option<(Point, usize)>,
+
+    // the edge traced by the breakpoint on the right of the arc
+    edge: Option<usize>,
+}
+
+/// Fortune's sweep for the Voronoi diagram. The sweep line goes downward like
+/// the one of `SweepPlane`, leaving behind the beach line: the arcs of the
+/// points equally distant from a site and from the sweep line. A site event
+/// splits the arc above the site and a circle event removes an arc, making a
+/// Voronoi vertex. It runs in O(n^2) in the worst case, as the beach line is
+/// kept in a vector, but it is much faster in practice.
+///
+/// The diagram needs square roots, so the coordinates are f64. Duplicated
+/// sites have no cell
+pub struct FortuneVoronoi {
+    sites: Points,
+
+    // the events ordered like the points of `SweepPlane`, the second part of
+    // the key tells apart the events in the same point
+    queue: BTreeMap<(Point, usize), VoronoiEvent>,
+
+    // the arcs from left to right
+    beach_line: Vec<Arc>,
+
+    // the output of the algorithm
+    vertices: Points,
+    edges: Vec<VoronoiEdge>,
+
+    // the last handled event and the position of the sweep line
+    event: Option<VoronoiEvent>,
+    sweep_y: f64,
+
+    // the source of ids for arcs and events
+    counter: usize,
+}
+
+impl Algorithm for FortuneVoronoi {
+    type Output = VoronoiDiagram;
+
+    fn calculate(&mut self) -> Self::Output {
+        self.reset();
+        while !self.is_done() {
+            self.step();
+        }
+
+        self.diagram()
+    }
+
+    /// fails without at least two distinct sites, as there is no edge
+    fn try_calculate(&mut self) -> Result<Self::Output> {
+        let diagram = self.calculate();
+        match diagram.edges.is_empty() {
+            true => Err(GalmetryError::DegenerateInput(
+                "there are not two distinct sites",
+            )),
+            false => Ok(diagram),
+        }
+    }
+
+    fn step(&mut self) {
+        let ((point, _), event) = match self.queue.pop_first() {
+            Some(event) => event,
+            None => return,
+        };
+
+        self.sweep_y = point.y;
+        self.event = Some(event);
+        match event {
+            VoronoiEvent::Site(site) => self.handle_site_event(site),
+            VoronoiEvent::Circle { arc, center } => self.handle_circle_event(arc, center),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.queue = BTreeMap::new();
+        self.beach_line = vec![];
+        self.vertices = Points::new();
+        self.edges = vec![];
+        self.event = None;
+        self.sweep_y = f64::INFINITY;
+        self.counter = self.sites.len();
+
+        for (i, site) in self.sites.iter().enumerate() {
+            self.queue
+                .entry((*site, 0))
+                .or_insert(VoronoiEvent::Site(i));
+        }
+    }
+}
+
+impl FortuneVoronoi {
+    pub fn build(sites: Points) -> Self {
+        let mut algo = Self {
+            sites,
+            queue: BTreeMap::new(),
+            beach_line: vec![],
+            vertices: Points::new(),
+            edges: vec![],
+            event: None,
+            sweep_y: f64::INFINITY,
+            counter: 0,
+        };
+        algo.reset();
+        algo
+    }
+
+    /// like `build`, but rejects sites with NaN or infinite coordinates
+    pub fn try_build(sites: Points) -> Result<Self> {
+        check_finite(sites.iter().map(|p| p.is_finite()))?;
+        Ok(Self::build(sites))
+    }
+
+    /// true when there are no more events to handle
+    pub fn is_done(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// the event handled by the last step
+    pub fn event(&self) -> Option<&VoronoiEvent> {
+        self.event.as_ref()
+    }
+
+    /// the height of the sweep line, infinite before the first step
+    pub fn sweep_y(&self) -> f64 {
+        self.sweep_y
+    }
+
+    /// the sites of the arcs of the beach line, from left to right
+    pub fn beach_line(&self) -> Vec<usize> {
+        self.beach_line.iter().map(|arc| arc.site).collect()
+    }
+
+    /// the x coordinates where consecutive arcs of the beach line meet
+    pub fn breakpoints(&self) -> Vec<f64> {
+        (1..self.beach_line.len())
+            .map(|i| self.breakpoint(i, self.sweep_y))
+            .collect()
+    }
+
+    /// the pending circle events, each one with the lowest point of its circle
+    pub fn circle_events(&self) -> impl Iterator<Item = (&Point, &VoronoiEvent)> {
+        self.queue
+            .iter()
+            .filter(|(_, event)| matches!(event, VoronoiEvent::Circle { .. }))
+            .map(|((point, _), event)| (point, event))
+    }
+
+    /// the diagram built so far: the edges still traced by the beach line have
+    /// no end yet
+    pub fn diagram(&self) -> VoronoiDiagram {
+        VoronoiDiagram {
+            sites: self.sites.clone(),
+            vertices: self.vertices.clone(),
+            edges: self.edges.clone(),
+        }
+    }
+
+    fn handle_site_event(&mut self, site: usize) {
+        let p = self.sites[site];
+        let id = self.next_id();
+        let arc = Arc {
+            id,
+            site,
+            circle: None,
+            edge: None,
+        };
+
+        if self.beach_line.is_empty() {
+            self.beach_line.push(arc);
+            return;
+        }
+
+        let i = self.arc_above(p.x);
+        let above = self.beach_line[i].site;
+
+        // the sites met first by the sweep line all lie on it: their bisectors
+        // are vertical lines. They come from left to right, so the new arc is
+        // the last one
+        if self.sites[above].y == p.y {
+            let last = self.beach_line.len() - 1;
+            let edge = self.add_edge([site, self.beach_line[last].site]);
+            self.beach_line[last].edge = Some(edge);
+            self.beach_line.push(arc);
+            return;
+        }
+
+        // the arc above is split in two, the new one between them
+        self.remove_circle_event(i);
+        let edge = self.add_edge([above, site]);
+        let right = Arc {
+            id: self.next_id(),
+            circle: None,
+            ..self.beach_line[i].clone()
+        };
+        self.beach_line[i].edge = Some(edge);
+        self.beach_line.insert(
+            i + 1,
+            Arc {
+                edge: Some(edge),
+                ..arc
+            },
+        );
+        self.beach_line.insert(i + 2, right);
+
+        self.add_circle_event(i);
+        self.add_circle_event(i + 2);
+    }
+
+    fn handle_circle_event(&mut self, id: usize, center: Point) {
+        let i = match self.beach_line.iter().position(|arc| arc.id == id) {
+            Some(i) => i,
+            None => return,
+        };
+
+        let vertex = self.vertices.len();
+        self.vertices.push(center);
+
+        let (left, right) = (i - 1, i + 1);
+        self.finish_edge(left, vertex);
+        self.finish_edge(i, vertex);
+
+        self.remove_circle_event(left);
+        self.remove_circle_event(right);
+        self.beach_line.remove(i);
+
+        // the new breakpoint moves downward, with the right site on its left
+        let sites = [self.beach_line[i].site, self.beach_line[left].site];
+        let edge = self.add_edge(sites);
+        self.edges[edge].start = Some(vertex);
+        self.beach_line[left].edge = Some(edge);
+
+        self.add_circle_event(left);
+        self.add_circle_event(i);
+    }
+
+    /// ends the edge traced by the breakpoint on the right of the i-th arc
+    fn finish_edge(&mut self, i: usize, vertex: usize) {
+        let right_site = self.beach_line[i + 1].site;
+        if let Some(edge) = self.beach_line[i].edge {
+            // going downward the site on the right of the breakpoint is on the left
+            match self.edges[edge].sites[0] == right_site {
+                true => self.edges[edge].end = Some(vertex),
+                false => self.edges[edge].start = Some(vertex),
+            }
+        }
+    }
+
+    fn add_edge(&mut self, sites: [usize; 2]) -> usize {
+        self.edges.push(VoronoiEdge {
+            sites,
+            start: None,
+            end: None,
+        });
+        self.edges.len() - 1
+    }
+
+    fn next_id(&mut self) -> usize {
+        self.counter += 1;
+        self.counter
+    }
+
+    /// the index of the arc above the x coordinate of the sweep line
+    fn arc_above(&self, x: f64) -> usize {
+        let (mut low, mut high) = (0, self.beach_line.len() - 1);
+        while low < high {
+            let mid = (low + high) / 2;
+            match self.breakpoint(mid + 1, self.sweep_y) < x {
+                true => low = mid + 1,
+                false => high = mid,
+            }
+        }
+        low
+    }
+
+    /// the x coordinate where the (i-1)-th arc and the i-th one meet when the
+    /// sweep line is at height l
+    fn breakpoint(&self, i: usize, l: f64) -> f64 {
+        let p = self.sites[self.beach_line[i - 1].site];
+        let q = self.sites[self.beach_line[i].site];
+
+        if p.y == q.y {
+            return (p.x + q.x) / 2.0;
+        }
+        // a site on the sweep line has a vertical arc
+        if p.y == l {
+            return p.x;
+        }
+        if q.y == l {
+            return q.x;
+        }
+
+        // the parabola of a site is y = (x^2 - 2 s.x x + s.x^2 + s.y^2 - l^2) / 2 (s.y - l)
+        let (dp, dq) = (2.0 * (p.y - l), 2.0 * (q.y - l));
+        let a = 1.0 / dp - 1.0 / dq;
+        let b = -2.0 * (p.x / dp - q.x / dq);
+        let c = (p.x * p.x + p.y * p.y - l * l) / dp - (q.x * q.x + q.y * q.y - l * l) / dq;
+        let sqrt = (b * b - 4.0 * a * c).max(0.0).sqrt();
+        let (x1, x2) = ((-b - sqrt) / (2.0 * a), (-b + sqrt) / (2.0 * a));
+
+        // the narrower arc, closer to the sweep line, lies between the two
+        // intersections
+        match p.y < q.y {
+            true => x1.max(x2),
+            false => x1.min(x2),
+        }
+    }
+
+    /// queues the circle event of the i-th arc, if its breakpoints converge
+    fn add_circle_event(&mut self, i: usize) {
+        if i == 0 || i + 1 >= self.beach_line.len() {
+            return;
+        }
+
+        let [a, b, c] = [i - 1, i, i + 1].map(|k| self.sites[self.beach_line[k].site]);
+        if orientation(&a, &b, &c) != Orientation::Right {
+            return;
+        }
+
+        let (bx, by, cx, cy) = (b.x - a.x, b.y - a.y, c.x - a.x, c.y - a.y);
+        let d = 2.0 * (bx * cy - by * cx);
+        let ux = (cy * (bx * bx + by * by) - by * (cx * cx + cy * cy)) / d;
+        let uy = (bx * (cx * cx + cy * cy) - cx * (bx * bx + by * by)) / d;
+        let center = Point::from2d(a.x + ux, a.y + uy);
+        if !center.is_finite() {
+            return;
+        }
+
+        // the event can not be above the sweep line, where it would be missed
+        let bottom = (center.y - ux.hypot(uy)).min(self.sweep_y);
+        let key = (Point::from2d(center.x, bottom), self.next_id());
+        let arc = self.beach_line[i].id;
+        self.queue.insert(key, VoronoiEvent::Circle { arc, center });
+        self.beach_line[i].circle = Some(key);
+    }
+
+    /// drops the circle event of the i-th arc, a false alarm
+    fn remove_circle_event(&mut self, i: usize) {
+        if let Some(key) = self.beach_line[i].circle.take() {
+            self.queue.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FortuneVoronoi, VoronoiDiagram, VoronoiEvent};
+    use crate::{
+        algorithms::algorithm::Algorithm,
+        error::GalmetryError,
+        geometry::{point::Point, points::Points},
+    };
+
+    /// random points on the plane, as the distances would count z too
+    fn random_sites(n: usize, r: std::ops::Range<f64>) -> Points {
+        Points::from(
+            (0..n)
+                .map(|_| Point::random(r.clone()))
+                .map(|p| Point::from2d(p.x, p.y))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn close(p: &Point, q: &Point) -> bool {
+        (p.x - q.x).abs() < 1e-9 && (p.y - q.y).abs() < 1e-9
+    }
+
+    /// every vertex is equally distant from the sites of its edges, and no site
+    /// is closer to it
+    fn assert_voronoi(diagram: &VoronoiDiagram) {
+        for edge in diagram.edges.iter() {
+            for vertex in [edge.start, edge.end].iter().flatten() {
+                let v = diagram.vertices[*vertex];
+                let r0 = v.distance_from(&diagram.sites[edge.sites[0]]);
+                let r1 = v.distance_from(&diagram.sites[edge.sites[1]]);
+                assert!((r0 - r1).abs() < 1e-9);
+                for site in diagram.sites.iter() {
+                    assert!(v.distance_from(site) > r0 - 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn voronoi_triangle() {
+        let sites: Points = vec![[0.0, 0.0], [4.0, 0.0], [2.0, 4.0]].into();
+        let diagram = FortuneVoronoi::build(sites).calculate();
+
+        assert_eq!(diagram.vertices.len(), 1);
+        assert!(close(&diagram.vertices[0], &Point::from2d(2.0, 1.5)));
+        assert_eq!(diagram.edges.len(), 3);
+        assert_voronoi(&diagram);
+
+        // each edge is a ray leaving the vertex away from the opposite site
+        for edge in diagram.edges.iter() {
+            let opposite = (0..3).find(|s| !edge.sites.contains(s)).unwrap();
+            let o = diagram.sites[opposite];
+            let d = diagram.direction(edge);
+            let outward = match (edge.start, edge.end) {
+                (Some(0), None) => 1.0,
+                (None, Some(0)) => -1.0,
+                _ => panic!("{:?} is not a ray", edge),
+            };
+            assert!(outward * (d.x * (o.x - 2.0) + d.y * (o.y - 1.5)) < 0.0);
+        }
+    }
+
+    #[test]
+    fn voronoi_two_sites() {
+        let sites: Points = vec![[0.0, 0.0], [2.0, 2.0]].into();
+        let diagram = FortuneVoronoi::build(sites).calculate();
+
+        assert!(diagram.vertices.is_empty());
+        assert_eq!(diagram.edges.len(), 1);
+        assert!(!diagram.edges[0].is_finite());
+    }
+
+    #[test]
+    fn voronoi_horizontal_sites() {
+        let sites: Points = vec![[2.0, 0.0], [0.0, 0.0], [1.0, 0.0], [1.0, -1.0]].into();
+        let diagram = FortuneVoronoi::build(sites).calculate();
+
+        assert_voronoi(&diagram);
+        assert_eq!(diagram.vertices.len(), 2);
+        assert_eq!(diagram.edges.len(), 5);
+    }
+
+    #[test]
+    fn voronoi_random_sites() {
+        for _ in 0..20 {
+            let sites = random_sites(50, 0.0..10.0);
+            let diagram = FortuneVoronoi::build(sites.clone()).calculate();
+            assert_voronoi(&diagram);
+
+            // in general position the diagram has 2n - 2 - h vertexes and
+            // 3n - 3 - h edges, like the dual Delaunay triangulation
+            let finite = diagram.edges.iter().filter(|e| e.is_finite()).count();
+            let h = diagram.edges.len() - finite;
+            assert_eq!(diagram.vertices.len(), 2 * sites.len() - 2 - h);
+            assert_eq!(diagram.edges.len(), 3 * sites.len() - 3 - h);
+        }
+    }
+
+    #[test]
+    fn voronoi_cells_cover_the_box() {
+        let sites = random_sites(40, 1.0..9.0);
+        let diagram = FortuneVoronoi::build(sites.clone()).calculate();
+        let (min, max) = (Point::from2d(0.0, 0.0), Point::from2d(10.0, 10.0));
+
+        let cells = diagram.cells(min, max);
+        let area: f64 = cells.iter().map(|cell| cell.area()).sum();
+        assert!((area - 100.0).abs() < 1e-6);
+
+        // every point of the box is in the cell of its closest site
+        for _ in 0..100 {
+            let p = random_sites(1, 0.0..10.0)[0];
+            let closest = (0..sites.len())
+                .min_by(|&a, &b| {
+                    p.distance_from(&sites[a])
+                        .total_cmp(&p.distance_from(&sites[b]))
+                })
+                .unwrap();
+            let cell = &cells[closest];
+            assert!(cell.is_ccw());
+            let v = cell.vertices();
+            assert!((0..v.len()).all(|i| {
+                let (a, b) = (v[i], v[(i + 1) % v.len()]);
+                (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x) >= -1e-9
+            }));
+        }
+    }
+
+    #[test]
+    fn voronoi_steps() {
+        let sites: Points = vec![[0.0, 0.0], [4.0, 0.0], [2.0, 4.0]].into();
+        let mut algo = FortuneVoronoi::build(sites);
+
+        algo.step();
+        assert_eq!(algo.event(), Some(&VoronoiEvent::Site(2)));
+        assert_eq!(algo.beach_line(), vec![2]);
+
+        algo.step();
+        algo.step();
+        assert_eq!(algo.sweep_y(), 0.0);
+        assert_eq!(algo.beach_line(), vec![2, 0, 2, 1, 2]);
+        assert_eq!(algo.circle_events().count(), 1);
+
+        algo.step();
+        assert!(matches!(algo.event(), Some(VoronoiEvent::Circle { .. })));
+        assert_eq!(algo.beach_line(), vec![2, 0, 1, 2]);
+        assert!(algo.is_done());
+    }
+
+    #[test]
+    fn voronoi_duplicated_sites() {
+        let sites: Points = vec![[0.0, 0.0], [1.0, 0.0], [0.0, 0.0]].into();
+        let diagram = FortuneVoronoi::build(sites).calculate();
+
+        assert_eq!(diagram.edges.len(), 1);
+        assert_eq!(diagram.neighbors(2), vec![]);
+    }
+
+    #[test]
+    fn try_voronoi() {
+        let single: Points = vec![[1.0, 1.0]].into();
+        assert_eq!(
+            FortuneVoronoi::build(single).try_calculate(),
+            Err(GalmetryError::DegenerateInput(
+                "there are not two distinct sites"
+            ))
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use std::{error::Error, fmt::Display};
+
+/// The errors returned by the fallible entry points of the algorithms
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GalmetryError {
+    /// the input element at index has a NaN or infinite coordinate
+    NonFiniteCoordinate { index: usize },
+
+    /// the algorithm needs at least `required` input elements
+    InsufficientInput { required: usize, found: usize },
+
+    /// the input has no solution in general position, e.g. all points collinear
+    DegenerateInput(&'static str),
+
+    /// a computed value is not representable by the scalar type
+    NumericFailure(&'static str),
+
+    /// a structure given by parts breaks one of its invariants
+    InvalidStructure(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, GalmetryError>;
+
+/// a `NonFiniteCoordinate` error for the first element that is not finite,
+/// given in input order whether each one is
+pub(crate) fn check_finite(finite: impl IntoIterator<Item = bool>) -> Result<()> {
+    match finite.into_iter().position(|finite| !finite) {
+        Some(index) => Err(GalmetryError::NonFiniteCoordinate { index }),
+        None => Ok(()),
+    }
+}
+
+impl Display for GalmetryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GalmetryError::NonFiniteCoordinate { index } => {
+                write!(
+                    f,
+                    "input element {} has a NaN or infinite coordinate",
+                    index
+                )
+            }
+            GalmetryError::InsufficientInput { required, found } => write!(
+                f,
+                "at least {} input elements are required, {} found",
+                required, found
+            ),
+            GalmetryError::DegenerateInput(reason) => write!(f, "degenerate input: {}", reason),
+            GalmetryError::NumericFailure(reason) => write!(f, "numeric failure: {}", reason),
+            GalmetryError::InvalidStructure(reason) => write!(f, "invalid structure: {}", reason),
+        }
+    }
+}
+
+impl Error for GalmetryError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_finite, GalmetryError};
+    use crate::geometry::{point::Point, points::Points};
+
+    #[test]
+    fn first_non_finite_element() {
+        let points: Points = vec![[0.0, 0.0], [1.0, f64::NAN], [f64::INFINITY, 0.0]].into();
+        assert_eq!(
+            check_finite(points.iter().map(|p| p.is_finite())),
+            Err(GalmetryError::NonFiniteCoordinate { index: 1 })
+        );
+        let finite = [Point::from2d(0.0, 0.0), Point::from2d(1.0, -1.0)];
+        assert_eq!(check_finite(finite.iter().map(|p| p.is_finite())), Ok(()));
+        assert_eq!(check_finite([]), Ok(()));
+    }
+}
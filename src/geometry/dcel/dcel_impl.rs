@@ -0,0 +1,417 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use super::{Dcel, Face, HalfEdge, Vertex};
+use crate::{
+    algorithms::{algorithm::Algorithm, sweep_plane::SweepPlane},
+    error::{check_finite, GalmetryError, Result},
+    geometry::{
+        point::Point,
+        points::Points,
+        polygon::Polygon,
+        predicates::Orientation,
+        scalar::{Field, Scalar},
+        segment::Segment,
+    },
+};
+
+impl<T: Scalar, A: Default> Dcel<T, A> {
+    /// the subdivision made only by the unbounded face
+    pub fn new() -> Self {
+        Self {
+            vertices: vec![],
+            half_edges: vec![],
+            faces: vec![Face {
+                outer: None,
+                inner: vec![],
+                attribute: A::default(),
+            }],
+        }
+    }
+
+    /// The subdivision made by edges joining the points by index. The edges
+    /// must meet only in their endpoints: repeated edges and loops are dropped,
+    /// and points used by no edge become isolated vertexes
+    pub fn from_edges(points: Points<T>, edges: &[(usize, usize)]) -> Self {
+        let mut dcel = Self::new();
+        dcel.vertices = points
+            .iter()
+            .map(|p| Vertex {
+                point: *p,
+                incident: None,
+            })
+            .collect();
+
+        let mut unique: Vec<(usize, usize)> = edges
+            .iter()
+            .filter(|(a, b)| a != b)
+            .map(|&(a, b)| (a.min(b), a.max(b)))
+            .collect();
+        unique.sort();
+        unique.dedup();
+
+        let mut outgoing: Vec<Vec<usize>> = vec![vec![]; dcel.vertices.len()];
+        for (a, b) in unique {
+            let h = dcel.half_edges.len();
+            for (origin, twin) in [(a, h + 1), (b, h)] {
+                outgoing[origin].push(dcel.half_edges.len());
+                dcel.half_edges.push(HalfEdge {
+                    origin,
+                    twin,
+                    next: 0,
+                    prev: 0,
+                    face: 0,
+                });
+            }
+        }
+
+        // walking into a vertex the face on the left continues with the
+        // half-edge leaving just clockwise from the one walked
+        for (v, edges) in outgoing.iter_mut().enumerate() {
+            edges.sort_by(|&e1, &e2| dcel.angle_cmp(e1, e2));
+            for (i, &e) in edges.iter().enumerate() {
+                let following = edges[(i + edges.len() - 1) % edges.len()];
+                let incoming = dcel.half_edges[e].twin;
+                dcel.half_edges[incoming].next = following;
+                dcel.half_edges[following].prev = incoming;
+            }
+            dcel.vertices[v].incident = edges.first().copied();
+        }
+
+        dcel.make_faces();
+        dcel
+    }
+
+    /// The subdivision made by segments meeting only in their endpoints, see
+    /// `try_from_segments` to check it. The vertexes are the distinct endpoints
+    pub fn from_segments(segments: &[Segment<T>]) -> Self {
+        let mut points = Points::new();
+        let mut places: BTreeMap<Point<T>, usize> = BTreeMap::new();
+        let mut edges = vec![];
+        for segment in segments {
+            let [a, b] = [segment.start, segment.end].map(|p| {
+                *places.entry(p).or_insert_with(|| {
+                    points.push(p);
+                    points.len() - 1
+                })
+            });
+            edges.push((a, b));
+        }
+
+        Self::from_edges(points, &edges)
+    }
+
+    /// builds the faces from the cycles of half-edges: a counterclockwise cycle
+    /// is the outer boundary of a bounded face, the others bound holes
+    fn make_faces(&mut self) {
+        let mut cycles: Vec<Vec<usize>> = vec![];
+        let mut visited = vec![false; self.half_edges.len()];
+        for e in 0..self.half_edges.len() {
+            if !visited[e] {
+                let cycle = self.cycle(e);
+                cycle.iter().for_each(|&h| visited[h] = true);
+                cycles.push(cycle);
+            }
+        }
+
+        let component = self.components();
+        let (outer, inner): (Vec<&Vec<usize>>, Vec<&Vec<usize>>) = cycles
+            .iter()
+            .partition(|cycle| self.doubled_area(cycle) > T::zero());
+
+        for cycle in outer.iter() {
+            self.faces.push(Face {
+                outer: Some(cycle[0]),
+                inner: vec![],
+                attribute: A::default(),
+            });
+            let face = self.faces.len() - 1;
+            cycle.iter().for_each(|&h| self.half_edges[h].face = face);
+        }
+
+        for cycle in inner {
+            // the innermost face of another component around the cycle
+            let lowest = cycle
+                .iter()
+                .map(|&h| self.half_edges[h].origin)
+                .min_by(|&a, &b| {
+                    self.vertices[a]
+                        .point
+                        .lexicograph_cmp(&self.vertices[b].point)
+                })
+                .expect("a cycle has at least two half-edges");
+            let p = self.vertices[lowest].point;
+
+            let face = (0..outer.len())
+                .filter(|&f| component[self.half_edges[outer[f][0]].origin] != component[lowest])
+                .filter(|&f| self.winds_around(outer[f], &p))
+                .min_by(|&f, &g| {
+                    let (f, g) = (self.doubled_area(outer[f]), self.doubled_area(outer[g]));
+                    f.partial_cmp(&g).unwrap_or(Ordering::Equal)
+                })
+                .map_or(0, |f| f + 1);
+
+            self.faces[face].inner.push(cycle[0]);
+            cycle.iter().for_each(|&h| self.half_edges[h].face = face);
+        }
+    }
+
+    /// for each vertex, the smallest index of the vertexes connected to it
+    fn components(&self) -> Vec<usize> {
+        let mut component: Vec<usize> = (0..self.vertices.len()).collect();
+        let find = |component: &mut Vec<usize>, mut v: usize| {
+            while component[v] != v {
+                component[v] = component[component[v]];
+                v = component[v];
+            }
+            v
+        };
+
+        for h in self.half_edges.iter() {
+            let a = find(&mut component, h.origin);
+            let b = find(&mut component, self.half_edges[h.twin].origin);
+            component[a.max(b)] = a.min(b);
+        }
+        (0..self.vertices.len())
+            .map(|v| find(&mut component, v))
+            .collect()
+    }
+}
+
+impl<T: Field, A: Default> Dcel<T, A> {
+    /// like `from_segments`, but rejects NaN and infinite coordinates and
+    /// segments meeting in a point other than an endpoint of both
+    pub fn try_from_segments(segments: &[Segment<T>]) -> Result<Self> {
+        check_finite(segments.iter().map(|s| s.is_finite()))?;
+
+        let intersections = SweepPlane::build(segments.to_vec()).calculate();
+        if intersections.values().any(|i| !i.interior.is_empty()) {
+            return Err(GalmetryError::DegenerateInput(
+                "the segments cross or touch inside",
+            ));
+        }
+        Ok(Self::from_segments(segments))
+    }
+}
+
+impl<T: Scalar, A> Dcel<T, A> {
+    /// a subdivision given by parts, as built by an algorithm. See `validate`
+    /// to check it
+    pub fn from_parts(
+        vertices: Vec<Vertex<T>>,
+        half_edges: Vec<HalfEdge>,
+        faces: Vec<Face<A>>,
+    ) -> Self {
+        Self {
+            vertices,
+            half_edges,
+            faces,
+        }
+    }
+
+    pub fn vertices(&self) -> &Vec<Vertex<T>> {
+        &self.vertices
+    }
+
+    pub fn half_edges(&self) -> &Vec<HalfEdge> {
+        &self.half_edges
+    }
+
+    pub fn faces(&self) -> &Vec<Face<A>> {
+        &self.faces
+    }
+
+    pub fn vertex(&self, v: usize) -> &Vertex<T> {
+        &self.vertices[v]
+    }
+
+    pub fn half_edge(&self, e: usize) -> &HalfEdge {
+        &self.half_edges[e]
+    }
+
+    pub fn face(&self, f: usize) -> &Face<A> {
+        &self.faces[f]
+    }
+
+    /// the index of the unbounded face
+    pub fn unbounded_face(&self) -> usize {
+        0
+    }
+
+    pub fn twin(&self, e: usize) -> usize {
+        self.half_edges[e].twin
+    }
+
+    pub fn next(&self, e: usize) -> usize {
+        self.half_edges[e].next
+    }
+
+    pub fn prev(&self, e: usize) -> usize {
+        self.half_edges[e].prev
+    }
+
+    pub fn origin(&self, e: usize) -> &Point<T> {
+        &self.vertices[self.half_edges[e].origin].point
+    }
+
+    pub fn destination(&self, e: usize) -> &Point<T> {
+        self.origin(self.twin(e))
+    }
+
+    pub fn attribute(&self, f: usize) -> &A {
+        &self.faces[f].attribute
+    }
+
+    pub fn set_attribute(&mut self, f: usize, attribute: A) {
+        self.faces[f].attribute = attribute;
+    }
+
+    /// the half-edges met following `next` from e, e included
+    pub fn cycle(&self, e: usize) -> Vec<usize> {
+        let mut cycle = vec![e];
+        let mut h = self.next(e);
+        while h != e && cycle.len() <= self.half_edges.len() {
+            cycle.push(h);
+            h = self.next(h);
+        }
+        cycle
+    }
+
+    /// the half-edges leaving the vertex, counterclockwise
+    pub fn outgoing(&self, v: usize) -> Vec<usize> {
+        let first = match self.vertices[v].incident {
+            Some(e) => e,
+            None => return vec![],
+        };
+
+        let mut outgoing = vec![first];
+        let mut h = self.twin(self.prev(first));
+        while h != first && outgoing.len() <= self.half_edges.len() {
+            outgoing.push(h);
+            h = self.twin(self.prev(h));
+        }
+        outgoing
+    }
+
+    /// the outer boundary of a bounded face, counterclockwise
+    pub fn boundary(&self, f: usize) -> Option<Polygon<T>> {
+        let outer = self.faces[f].outer?;
+        let vertices = self
+            .cycle(outer)
+            .iter()
+            .map(|&h| *self.origin(h))
+            .collect::<Vec<_>>();
+        Some(Polygon::new(Points::from(vertices)))
+    }
+
+    /// Checks the invariants of the structure: twins are paired, `next` and
+    /// `prev` are inverse and chain the half-edges of a face head to tail,
+    /// and each cycle is listed once by its face, counterclockwise for outer
+    /// boundaries and not for holes
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason| Err(GalmetryError::InvalidStructure(reason));
+        let (n, m) = (self.half_edges.len(), self.vertices.len());
+
+        if self.faces.first().is_none_or(|face| face.outer.is_some()) {
+            return invalid("the first face is not the unbounded one");
+        }
+
+        for (e, h) in self.half_edges.iter().enumerate() {
+            if h.twin >= n
+                || h.next >= n
+                || h.prev >= n
+                || h.origin >= m
+                || h.face >= self.faces.len()
+            {
+                return invalid("a half-edge refers to a missing element");
+            }
+            if h.twin == e || self.twin(h.twin) != e {
+                return invalid("a half-edge is not the twin of its twin");
+            }
+            if self.prev(h.next) != e || self.next(h.prev) != e {
+                return invalid("next and prev are not inverse");
+            }
+            if self.half_edges[h.next].origin != self.half_edges[h.twin].origin {
+                return invalid("the next half-edge does not start where the half-edge ends");
+            }
+            if self.half_edges[h.next].face != h.face {
+                return invalid("the next half-edge bounds another face");
+            }
+        }
+
+        for (v, vertex) in self.vertices.iter().enumerate() {
+            if vertex
+                .incident
+                .is_some_and(|e| e >= n || self.half_edges[e].origin != v)
+            {
+                return invalid("the incident half-edge of a vertex does not leave it");
+            }
+        }
+
+        let mut listed = vec![false; n];
+        for (f, face) in self.faces.iter().enumerate() {
+            let boundaries = face.outer.iter().map(|&e| (e, true));
+            for (e, outer) in boundaries.chain(face.inner.iter().map(|&e| (e, false))) {
+                if e >= n || self.half_edges[e].face != f {
+                    return invalid("a boundary of a face is made of half-edges of another face");
+                }
+
+                let cycle = self.cycle(e);
+                if cycle.iter().any(|&h| listed[h]) {
+                    return invalid("a cycle of half-edges is listed twice");
+                }
+                cycle.iter().for_each(|&h| listed[h] = true);
+
+                if (self.doubled_area(&cycle) > T::zero()) != outer {
+                    return invalid("a boundary of a face has the wrong orientation");
+                }
+            }
+        }
+        if listed.contains(&false) {
+            return invalid("a cycle of half-edges is listed by no face");
+        }
+
+        Ok(())
+    }
+
+    /// compares the directions of two half-edges with the same origin,
+    /// counterclockwise from the positive x axis
+    fn angle_cmp(&self, e1: usize, e2: usize) -> Ordering {
+        let (o, d1, d2) = (self.origin(e1), self.destination(e1), self.destination(e2));
+        // 0 for the directions in [0, pi), 1 for the ones in [pi, 2pi)
+        let half = |d: &Point<T>| (d.y < o.y || (d.y == o.y && d.x < o.x)) as u8;
+
+        half(d1)
+            .cmp(&half(d2))
+            .then(match T::orientation(o, d1, d2) {
+                Orientation::Left => Ordering::Less,
+                Orientation::Right => Ordering::Greater,
+                Orientation::Collinear => Ordering::Equal,
+            })
+    }
+
+    fn doubled_area(&self, cycle: &[usize]) -> T {
+        cycle.iter().fold(T::zero(), |area, &h| {
+            let (p, q) = (self.origin(h), self.destination(h));
+            area + p.x * q.y - q.x * p.y
+        })
+    }
+
+    /// true if the cycle winds around p, which is not on it
+    fn winds_around(&self, cycle: &[usize], p: &Point<T>) -> bool {
+        let winding = cycle.iter().fold(0i64, |winding, &h| {
+            let (a, b) = (self.origin(h), self.destination(h));
+            match (a.y <= p.y, b.y <= p.y) {
+                (true, false) if T::orientation(a, b, p) == Orientation::Left => winding + 1,
+                (false, true) if T::orientation(a, b, p) == Orientation::Right => winding - 1,
+                _ => winding,
+            }
+        });
+        winding != 0
+    }
+}
+
+impl<T: Scalar, A: Default> Default for Dcel<T, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
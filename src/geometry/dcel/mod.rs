@@ -0,0 +1,53 @@
+use super::point::Point;
+
+/// A vertex of a planar subdivision
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vertex<T = f64> {
+    pub point: Point<T>,
+
+    /// a half-edge leaving the vertex, None if the vertex is isolated
+    pub incident: Option<usize>,
+}
+
+/// One of the two directed copies of an edge, the face it bounds lies on its left
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HalfEdge {
+    pub origin: usize,
+    pub twin: usize,
+
+    /// the following half-edge on the boundary of the same face
+    pub next: usize,
+    pub prev: usize,
+
+    pub face: usize,
+}
+
+/// A face of a planar subdivision
+#[derive(Clone, Debug, PartialEq)]
+pub struct Face<A = ()> {
+    /// a half-edge of the outer boundary, None for the unbounded face
+    pub outer: Option<usize>,
+
+    /// a half-edge of each hole, as well as of each dangling component
+    pub inner: Vec<usize>,
+
+    pub attribute: A,
+}
+
+/// Doubly-connected edge list: a planar subdivision stored as vertexes,
+/// half-edges and faces, every half-edge knowing its twin, the next and the
+/// previous one around its face. Bounded faces are traversed counterclockwise
+/// along their outer boundary and clockwise along their holes.
+///
+/// The first face is always the unbounded one. Each face carries an attribute
+/// of type A, for the data of the application
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dcel<T = f64, A = ()> {
+    vertices: Vec<Vertex<T>>,
+    half_edges: Vec<HalfEdge>,
+    faces: Vec<Face<A>>,
+}
+
+pub mod dcel_impl;
+
+mod tests;
@@ -0,0 +1,199 @@
+#[cfg(test)]
+use crate::{
+    algorithms::{algorithm::Algorithm, delaunay::DelaunayTriangulation},
+    error::GalmetryError,
+    geometry::{
+        dcel::{Dcel, Face, HalfEdge, Vertex},
+        point::Point,
+        points::Points,
+        polygon::Polygon,
+        segment::Segment,
+    },
+};
+
+#[cfg(test)]
+fn square(min: f64, max: f64) -> Vec<Segment> {
+    let corners = [[min, min], [max, min], [max, max], [min, max]];
+    (0..4)
+        .map(|i| Segment::new(corners[i], corners[(i + 1) % 4]))
+        .collect()
+}
+
+#[test]
+fn dcel_square_with_diagonal() {
+    let mut segments = square(0.0, 2.0);
+    segments.push(Segment::new([0.0, 0.0], [2.0, 2.0]));
+    let dcel: Dcel = Dcel::from_segments(&segments);
+
+    assert_eq!(dcel.vertices().len(), 4);
+    assert_eq!(dcel.half_edges().len(), 10);
+    assert_eq!(dcel.faces().len(), 3);
+    assert_eq!(dcel.validate(), Ok(()));
+
+    let unbounded = dcel.face(dcel.unbounded_face());
+    assert_eq!(unbounded.inner.len(), 1);
+    assert_eq!(dcel.cycle(unbounded.inner[0]).len(), 4);
+    for f in 1..3 {
+        let triangle = dcel.boundary(f).unwrap();
+        assert!(triangle.is_ccw());
+        assert_eq!(triangle.area(), 2.0);
+    }
+}
+
+#[test]
+fn dcel_navigation() {
+    let mut segments = square(0.0, 2.0);
+    segments.push(Segment::new([0.0, 0.0], [2.0, 2.0]));
+    let dcel: Dcel = Dcel::from_segments(&segments);
+
+    for e in 0..dcel.half_edges().len() {
+        assert_eq!(dcel.twin(dcel.twin(e)), e);
+        assert_eq!(dcel.destination(e), dcel.origin(dcel.next(e)));
+        assert_eq!(dcel.next(dcel.prev(e)), e);
+    }
+
+    // the corner in the origin has its edges to the right, along the diagonal and up
+    let v = (0..4)
+        .find(|&v| dcel.vertex(v).point == Point::from2d(0.0, 0.0))
+        .unwrap();
+    let around: Vec<Point> = dcel
+        .outgoing(v)
+        .iter()
+        .map(|&e| *dcel.destination(e))
+        .collect();
+    let start = around
+        .iter()
+        .position(|p| *p == Point::from2d(2.0, 0.0))
+        .unwrap();
+    assert_eq!(around[(start + 1) % 3], Point::from2d(2.0, 2.0));
+    assert_eq!(around[(start + 2) % 3], Point::from2d(0.0, 2.0));
+}
+
+#[test]
+fn dcel_with_hole() {
+    let mut segments = square(0.0, 10.0);
+    segments.extend(square(4.0, 6.0));
+    let dcel: Dcel = Dcel::from_segments(&segments);
+
+    assert_eq!(dcel.validate(), Ok(()));
+    assert_eq!(dcel.faces().len(), 3);
+
+    let ring = (1..3)
+        .find(|&f| dcel.boundary(f).unwrap().area() == 100.0)
+        .unwrap();
+    assert_eq!(dcel.face(ring).inner.len(), 1);
+    // the hole is walked clockwise, keeping the ring on the left
+    let hole: Vec<Point> = dcel
+        .cycle(dcel.face(ring).inner[0])
+        .iter()
+        .map(|&e| *dcel.origin(e))
+        .collect();
+    let hole = Polygon::new(Points::from(hole));
+    assert!(hole.is_cw());
+    assert_eq!(hole.area(), 4.0);
+}
+
+#[test]
+fn dcel_dangling_edges() {
+    let segments = vec![
+        Segment::new([0.0, 0.0], [1.0, 0.0]),
+        Segment::new([1.0, 0.0], [2.0, 1.0]),
+    ];
+    let dcel: Dcel = Dcel::from_segments(&segments);
+
+    assert_eq!(dcel.validate(), Ok(()));
+    assert_eq!(dcel.faces().len(), 1);
+    assert_eq!(dcel.cycle(dcel.face(0).inner[0]).len(), 4);
+}
+
+#[test]
+fn dcel_from_delaunay_mesh() {
+    let points = Points::random(50, 0.0..10.0);
+    let mesh = DelaunayTriangulation::build(points.clone()).calculate();
+    let dcel: Dcel = Dcel::from_edges(points, &mesh.edges());
+
+    assert_eq!(dcel.validate(), Ok(()));
+    assert_eq!(dcel.faces().len(), mesh.len() + 1);
+}
+
+#[test]
+fn dcel_face_attributes() {
+    let mut dcel: Dcel<i32, &str> = Dcel::from_segments(&[
+        Segment::new([0, 0], [4, 0]),
+        Segment::new([4, 0], [0, 4]),
+        Segment::new([0, 4], [0, 0]),
+    ]);
+
+    assert_eq!(dcel.attribute(1), &"");
+    dcel.set_attribute(1, "lake");
+    assert_eq!(dcel.attribute(1), &"lake");
+    assert_eq!(dcel.boundary(1).unwrap().doubled_signed_area(), 16);
+}
+
+#[test]
+fn validate_broken_dcel() {
+    let vertices = vec![
+        Vertex {
+            point: Point::from2d(0.0, 0.0),
+            incident: Some(0),
+        },
+        Vertex {
+            point: Point::from2d(1.0, 0.0),
+            incident: Some(1),
+        },
+    ];
+    let mut half_edges = vec![
+        HalfEdge {
+            origin: 0,
+            twin: 1,
+            next: 1,
+            prev: 1,
+            face: 0,
+        },
+        HalfEdge {
+            origin: 1,
+            twin: 0,
+            next: 0,
+            prev: 0,
+            face: 0,
+        },
+    ];
+    let faces = vec![Face {
+        outer: None,
+        inner: vec![0],
+        attribute: (),
+    }];
+
+    let dcel: Dcel = Dcel::from_parts(vertices.clone(), half_edges.clone(), faces.clone());
+    assert_eq!(dcel.validate(), Ok(()));
+
+    half_edges[1].twin = 1;
+    let dcel: Dcel = Dcel::from_parts(vertices, half_edges, faces);
+    assert_eq!(
+        dcel.validate(),
+        Err(GalmetryError::InvalidStructure(
+            "a half-edge is not the twin of its twin"
+        ))
+    );
+}
+
+#[test]
+fn try_from_crossing_segments() {
+    let segments = vec![
+        Segment::new([0.0, 0.0], [2.0, 2.0]),
+        Segment::new([0.0, 2.0], [2.0, 0.0]),
+    ];
+    assert_eq!(
+        Dcel::<f64>::try_from_segments(&segments),
+        Err(GalmetryError::DegenerateInput(
+            "the segments cross or touch inside"
+        ))
+    );
+
+    let touching = vec![
+        Segment::new([0.0, 0.0], [2.0, 0.0]),
+        Segment::new([1.0, 0.0], [1.0, 2.0]),
+    ];
+    assert!(Dcel::<f64>::try_from_segments(&touching).is_err());
+    assert!(Dcel::<f64>::try_from_segments(&square(0.0, 1.0)).is_ok());
+}
@@ -0,0 +1,117 @@
+use super::{
+    point::Point,
+    scalar::{Field, Scalar},
+};
+
+/// The closed half-plane of the points (x, y) with a x + b y <= c. The normal
+/// (a, b) points outside
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HalfPlane<T = f64> {
+    pub a: T,
+    pub b: T,
+    pub c: T,
+}
+
+impl<T: Scalar> HalfPlane<T> {
+    pub fn new(a: T, b: T, c: T) -> Self {
+        Self { a, b, c }
+    }
+
+    /// the half-plane on the left of the line going from p to q, the line
+    /// included
+    pub fn left_of(p: &Point<T>, q: &Point<T>) -> Self {
+        let (a, b) = (q.y - p.y, p.x - q.x);
+        Self::new(a, b, a * p.x + b * p.y)
+    }
+
+    /// the outer normal (a, b)
+    pub fn normal(&self) -> Point<T> {
+        Point::from2d(self.a, self.b)
+    }
+
+    /// the direction of the boundary line keeping the half-plane on its left
+    pub fn direction(&self) -> Point<T> {
+        Point::from2d(-self.b, self.a)
+    }
+
+    /// a x + b y - c: negative inside, zero on the boundary, positive outside
+    pub fn eval(&self, p: &Point<T>) -> T {
+        self.a * p.x + self.b * p.y - self.c
+    }
+
+    pub fn contains(&self, p: &Point<T>) -> bool {
+        self.eval(p) <= T::zero()
+    }
+
+    /// true if the normal is null, so that the half-plane is either everything
+    /// or nothing
+    pub fn is_degenerate(&self) -> bool {
+        self.a == T::zero() && self.b == T::zero()
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.a.is_finite() && self.b.is_finite() && self.c.is_finite()
+    }
+}
+
+impl<T: Field> HalfPlane<T> {
+    /// the point of the boundary line closest to the origin, None if the
+    /// half-plane is degenerate
+    pub fn boundary_point(&self) -> Option<Point<T>> {
+        if self.is_degenerate() {
+            return None;
+        }
+
+        let scale = self.c / (self.a * self.a + self.b * self.b);
+        Some(Point::from2d(self.a * scale, self.b * scale))
+    }
+
+    /// the point where the boundary lines meet, None if they are parallel
+    pub fn intersection(&self, other: &HalfPlane<T>) -> Option<Point<T>> {
+        let det = self.a * other.b - other.a * self.b;
+        if det == T::zero() {
+            return None;
+        }
+
+        Some(Point::from2d(
+            (self.c * other.b - other.c * self.b) / det,
+            (self.a * other.c - other.a * self.c) / det,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HalfPlane;
+    use crate::geometry::{point::Point, rational::Rational};
+
+    #[test]
+    fn half_plane_left_of_a_line() {
+        let h = HalfPlane::left_of(&Point::from2d(0.0, 0.0), &Point::from2d(2.0, 0.0));
+        assert!(h.contains(&Point::from2d(1.0, 1.0)));
+        assert!(h.contains(&Point::from2d(5.0, 0.0)));
+        assert!(!h.contains(&Point::from2d(1.0, -1.0)));
+        assert_eq!(h.eval(&Point::from2d(1.0, -1.0)), 2.0);
+        assert_eq!(h.direction(), Point::from2d(2.0, 0.0));
+    }
+
+    #[test]
+    fn half_plane_boundary() {
+        let h = HalfPlane::new(Rational::from(1), Rational::from(1), Rational::from(2));
+        let p = h.boundary_point().unwrap();
+        assert_eq!(p, Point::from2d(Rational::from(1), Rational::from(1)));
+        assert_eq!(h.eval(&p), Rational::from(0));
+
+        let vertical = HalfPlane::new(Rational::from(1), Rational::from(0), Rational::from(3));
+        assert_eq!(
+            h.intersection(&vertical),
+            Some(Point::from2d(Rational::from(3), Rational::from(-1)))
+        );
+        let parallel = HalfPlane::new(Rational::from(-2), Rational::from(-2), Rational::from(1));
+        assert_eq!(h.intersection(&parallel), None);
+
+        let degenerate = HalfPlane::new(0.0, 0.0, 1.0);
+        assert!(degenerate.is_degenerate());
+        assert_eq!(degenerate.boundary_point(), None);
+    }
+}
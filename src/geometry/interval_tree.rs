@@ -0,0 +1,244 @@
+use std::cmp::Ordering;
+
+use super::{
+    point::Point, points::Points, priority_search_tree::PrioritySearchTree, scalar::Scalar,
+    segment::Segment,
+};
+use crate::error::{check_finite, GalmetryError, Result};
+
+/// An interval tree over horizontal segments (de Berg et al. chapter 10.1).
+///
+/// Each node splits the segments by the median x of their endpoints: the ones
+/// on its left and on its right go to the children, the ones across it stay.
+/// Those are kept in two priority search trees, one by the left ends and one
+/// by the right ends mirrored, so that the segments crossing a vertical query
+/// segment are found in O(log² n + k) time, where k is the number of
+/// segments reported. Building the tree takes O(n log n) time and O(n) memory.
+///
+/// Segments are referred by their index in the input
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntervalTree<T = f64> {
+    segments: Vec<Segment<T>>,
+
+    // the nodes, the root first
+    nodes: Vec<IntervalNode<T>>,
+
+    // the lowest and the highest segment, for the queries unbounded in y
+    min_y: T,
+    max_y: T,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct IntervalNode<T> {
+    center: T,
+
+    // the segments across the center, by their left end (x, y) and by their
+    // right end (-x, y), along with the index of each point
+    by_left: PrioritySearchTree<T>,
+    by_right: PrioritySearchTree<T>,
+    across: Vec<usize>,
+
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+impl<T: Scalar> IntervalTree<T> {
+    /// the tree of the horizontal segments. The y of a segment is the one of
+    /// its start
+    pub fn new(segments: Vec<Segment<T>>) -> Self {
+        let ys = segments.iter().map(|s| s.start.y);
+        let min_y = ys.clone().reduce(Scalar::min).unwrap_or(T::zero());
+        let max_y = ys.reduce(Scalar::max).unwrap_or(T::zero());
+
+        let mut tree = Self {
+            nodes: vec![],
+            min_y,
+            max_y,
+            segments,
+        };
+        tree.build((0..tree.segments.len()).collect());
+        tree
+    }
+
+    /// like `new`, but rejects NaN and infinite coordinates and segments that
+    /// are not horizontal
+    pub fn try_new(segments: Vec<Segment<T>>) -> Result<Self> {
+        check_finite(segments.iter().map(|s| s.is_finite()))?;
+        if segments.iter().any(|s| s.start.y != s.end.y) {
+            return Err(GalmetryError::DegenerateInput(
+                "the segments are not horizontal",
+            ));
+        }
+        Ok(Self::new(segments))
+    }
+
+    pub fn segments(&self) -> &[Segment<T>] {
+        &self.segments
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// the segments crossing the vertical line through x
+    pub fn stabbing(&self, x: T) -> Vec<usize> {
+        self.crossing(x, self.min_y, self.max_y)
+    }
+
+    /// the segments crossing the vertical segment from (x, min_y) to (x, max_y)
+    pub fn crossing(&self, x: T, min_y: T, max_y: T) -> Vec<usize> {
+        let mut found = vec![];
+        let mut node = (!self.nodes.is_empty()).then_some(0);
+        while let Some(n) = node.map(|n| &self.nodes[n]) {
+            let (tree, bound, next) = match x.partial_cmp(&n.center) {
+                Some(Ordering::Greater) => (&n.by_right, -x, n.right),
+                Some(Ordering::Less) => (&n.by_left, x, n.left),
+                _ => (&n.by_left, x, None),
+            };
+            found.extend(
+                tree.query(bound, min_y, max_y)
+                    .into_iter()
+                    .map(|i| n.across[i]),
+            );
+            node = next;
+        }
+        found
+    }
+
+    /// builds the subtree of the segments, returning its node
+    fn build(&mut self, segments: Vec<usize>) -> Option<usize> {
+        if segments.is_empty() {
+            return None;
+        }
+
+        let mut xs: Vec<T> = segments
+            .iter()
+            .flat_map(|&s| [self.segments[s].start.x, self.segments[s].end.x])
+            .collect();
+        let mid = xs.len() / 2;
+        let (_, &mut center, _) =
+            xs.select_nth_unstable_by(mid, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let (mut left, mut right, mut across) = (vec![], vec![], vec![]);
+        for s in segments {
+            let (a, b) = self.ends(s);
+            match (b < center, center < a) {
+                (true, _) => left.push(s),
+                (_, true) => right.push(s),
+                _ => across.push(s),
+            }
+        }
+
+        let by_left = Points::from(
+            across
+                .iter()
+                .map(|&s| Point::from2d(self.ends(s).0, self.segments[s].start.y))
+                .collect::<Vec<_>>(),
+        );
+        let by_right = Points::from(
+            across
+                .iter()
+                .map(|&s| Point::from2d(-self.ends(s).1, self.segments[s].start.y))
+                .collect::<Vec<_>>(),
+        );
+
+        let node = self.nodes.len();
+        self.nodes.push(IntervalNode {
+            center,
+            by_left: PrioritySearchTree::new(by_left),
+            by_right: PrioritySearchTree::new(by_right),
+            across,
+            left: None,
+            right: None,
+        });
+        self.nodes[node].left = self.build(left);
+        self.nodes[node].right = self.build(right);
+        Some(node)
+    }
+
+    /// the smallest and the largest x of the segment
+    fn ends(&self, s: usize) -> (T, T) {
+        let s = &self.segments[s];
+        (
+            Scalar::min(s.start.x, s.end.x),
+            Scalar::max(s.start.x, s.end.x),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntervalTree;
+    use crate::{
+        error::GalmetryError,
+        geometry::{segment::Segment, test_utils::sorted},
+    };
+    use rand::Rng;
+
+    #[test]
+    fn interval_tree_queries() {
+        let segments = vec![
+            Segment::new([0.0, 1.0], [4.0, 1.0]),
+            Segment::new([2.0, 2.0], [6.0, 2.0]),
+            Segment::new([5.0, 3.0], [9.0, 3.0]),
+            Segment::new([7.0, 0.0], [8.0, 0.0]),
+            Segment::new([3.0, 4.0], [3.0, 4.0]),
+        ];
+        let tree = IntervalTree::new(segments);
+
+        assert_eq!(sorted(tree.stabbing(3.0)), vec![0, 1, 4]);
+        assert_eq!(sorted(tree.stabbing(6.0)), vec![1, 2]);
+        assert_eq!(sorted(tree.stabbing(7.5)), vec![2, 3]);
+        assert_eq!(tree.stabbing(10.0), vec![]);
+        assert_eq!(sorted(tree.crossing(3.0, 1.5, 5.0)), vec![1, 4]);
+        assert_eq!(sorted(tree.crossing(4.0, 0.0, 1.0)), vec![0]);
+        assert_eq!(tree.crossing(4.0, 2.5, 3.5), vec![]);
+    }
+
+    #[test]
+    fn same_answers_as_brute_force() {
+        let mut rng = rand::thread_rng();
+        let segments: Vec<Segment<i64>> = (0..200)
+            .map(|_| {
+                let y = rng.gen_range(0..50);
+                Segment::new([rng.gen_range(0..100), y], [rng.gen_range(0..100), y])
+            })
+            .collect();
+        let tree = IntervalTree::new(segments.clone());
+
+        for _ in 0..100 {
+            let x = rng.gen_range(-10..110);
+            let (a, b) = (rng.gen_range(-10..60), rng.gen_range(-10..60));
+            let (min_y, max_y) = (a.min(b), a.max(b));
+            let expected: Vec<usize> = (0..segments.len())
+                .filter(|&i| {
+                    let s = &segments[i];
+                    s.start.x.min(s.end.x) <= x
+                        && x <= s.start.x.max(s.end.x)
+                        && min_y <= s.start.y
+                        && s.start.y <= max_y
+                })
+                .collect();
+            assert_eq!(sorted(tree.crossing(x, min_y, max_y)), expected);
+        }
+    }
+
+    #[test]
+    fn try_interval_tree() {
+        let slanted = vec![
+            Segment::new([0.0, 1.0], [4.0, 1.0]),
+            Segment::new([0.0, 0.0], [1.0, 1.0]),
+        ];
+        assert_eq!(
+            IntervalTree::try_new(slanted).err(),
+            Some(GalmetryError::DegenerateInput(
+                "the segments are not horizontal"
+            ))
+        );
+        assert!(IntervalTree::<f64>::new(vec![]).stabbing(0.0).is_empty());
+    }
+}
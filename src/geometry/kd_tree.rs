@@ -0,0 +1,432 @@
+use std::cmp::Ordering;
+
+use super::{point::Point, point_index::PointIndex, points::Points, scalar::Scalar};
+use crate::error::{check_finite, Result};
+
+/// A balanced kd-tree over points in 2 or 3 dimensions, the third one being z.
+///
+/// The tree is implicit: the points are reordered so that the node of a range
+/// of positions is its middle one, splitting the rest by the coordinate of its
+/// depth, smaller or equal ones first. Building it takes O(n log n), a range
+/// query O(n^(1-1/d) + k) where k is the number of points reported.
+///
+/// Points are referred by their index in the input
+#[derive(Clone, Debug, PartialEq)]
+pub struct KdTree<T = f64> {
+    points: Points<T>,
+
+    // 2 or 3
+    dimensions: usize,
+
+    // the indexes of the points in tree order
+    order: Vec<usize>,
+}
+
+impl<T: Scalar> KdTree<T> {
+    /// the tree of the points in the plane, ignoring z
+    pub fn new(points: Points<T>) -> Self {
+        Self::with_dimensions(points, 2)
+    }
+
+    /// the tree of the points in space
+    pub fn new3d(points: Points<T>) -> Self {
+        Self::with_dimensions(points, 3)
+    }
+
+    /// like `new`, but rejects NaN and infinite coordinates
+    pub fn try_new(points: Points<T>) -> Result<Self> {
+        check_finite(points.iter().map(|p| p.is_finite()))?;
+        Ok(Self::new(points))
+    }
+
+    /// like `new3d`, but rejects NaN and infinite coordinates
+    pub fn try_new3d(points: Points<T>) -> Result<Self> {
+        check_finite(points.iter().map(|p| p.is_finite()))?;
+        Ok(Self::new3d(points))
+    }
+
+    fn with_dimensions(points: Points<T>, dimensions: usize) -> Self {
+        let mut tree = Self {
+            order: (0..points.len()).collect(),
+            points,
+            dimensions,
+        };
+        let n = tree.order.len();
+        tree.split(0, n, 0);
+        tree
+    }
+
+    pub fn points(&self) -> &Points<T> {
+        &self.points
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// the k points closest to p, the closest first. Points as far as each
+    /// other are sorted by index
+    pub fn nearest(&self, p: &Point<T>, k: usize) -> Vec<usize> {
+        let mut best: Vec<(T, usize)> = vec![];
+        if k > 0 {
+            self.visit_nearest(0, self.order.len(), 0, p, k, &mut best);
+        }
+        best.into_iter().map(|(_, i)| i).collect()
+    }
+
+    /// the points at distance at most radius from p, closest first
+    pub fn within(&self, p: &Point<T>, radius: T) -> Vec<usize> {
+        let mut found: Vec<(T, usize)> = vec![];
+        let squared = radius * radius;
+        self.visit_within(0, self.order.len(), 0, p, squared, &mut found);
+        found.sort_by(|a, b| distance_cmp(a, b));
+        found.into_iter().map(|(_, i)| i).collect()
+    }
+
+    /// places the median of the range in its middle, by the axis of the depth
+    fn split(&mut self, start: usize, end: usize, depth: usize) {
+        if end - start <= 1 {
+            return;
+        }
+
+        let axis = depth % self.dimensions;
+        let mid = (start + end) / 2;
+        let points = &self.points;
+        self.order[start..end].select_nth_unstable_by(mid - start, |&a, &b| {
+            coordinate(&points[a], axis)
+                .partial_cmp(&coordinate(&points[b], axis))
+                .unwrap_or(Ordering::Equal)
+        });
+        self.split(start, mid, depth + 1);
+        self.split(mid + 1, end, depth + 1);
+    }
+
+    fn point(&self, position: usize) -> &Point<T> {
+        &self.points[self.order[position]]
+    }
+
+    fn inside(&self, p: &Point<T>, min: &Point<T>, max: &Point<T>) -> bool {
+        (0..self.dimensions).all(|axis| {
+            let v = coordinate(p, axis);
+            coordinate(min, axis) <= v && v <= coordinate(max, axis)
+        })
+    }
+
+    fn visit_range(
+        &self,
+        start: usize,
+        end: usize,
+        depth: usize,
+        min: &Point<T>,
+        max: &Point<T>,
+        report: &mut impl FnMut(usize),
+    ) {
+        if start >= end {
+            return;
+        }
+
+        let mid = (start + end) / 2;
+        let (axis, p) = (depth % self.dimensions, self.point(mid));
+        if self.inside(p, min, max) {
+            report(self.order[mid]);
+        }
+        let split = coordinate(p, axis);
+        if coordinate(min, axis) <= split {
+            self.visit_range(start, mid, depth + 1, min, max, report);
+        }
+        if split <= coordinate(max, axis) {
+            self.visit_range(mid + 1, end, depth + 1, min, max, report);
+        }
+    }
+
+    fn count_range(
+        &self,
+        start: usize,
+        end: usize,
+        depth: usize,
+        min: &Point<T>,
+        max: &Point<T>,
+        cell: &mut Cell<T>,
+    ) -> usize {
+        if start >= end {
+            return 0;
+        }
+        if cell.is_inside(min, max, self.dimensions) {
+            return end - start;
+        }
+
+        let mid = (start + end) / 2;
+        let (axis, p) = (depth % self.dimensions, self.point(mid));
+        let mut count = self.inside(p, min, max) as usize;
+        let split = coordinate(p, axis);
+
+        if coordinate(min, axis) <= split {
+            let high = cell.high[axis].replace(split);
+            count += self.count_range(start, mid, depth + 1, min, max, cell);
+            cell.high[axis] = high;
+        }
+        if split <= coordinate(max, axis) {
+            let low = cell.low[axis].replace(split);
+            count += self.count_range(mid + 1, end, depth + 1, min, max, cell);
+            cell.low[axis] = low;
+        }
+        count
+    }
+
+    fn visit_nearest(
+        &self,
+        start: usize,
+        end: usize,
+        depth: usize,
+        q: &Point<T>,
+        k: usize,
+        best: &mut Vec<(T, usize)>,
+    ) {
+        if start >= end {
+            return;
+        }
+
+        let mid = (start + end) / 2;
+        let (axis, p) = (depth % self.dimensions, self.point(mid));
+        let candidate = (self.squared_distance(p, q), self.order[mid]);
+        if best.len() < k || distance_cmp(&candidate, &best[best.len() - 1]) == Ordering::Less {
+            let position = best
+                .binary_search_by(|other| distance_cmp(other, &candidate))
+                .unwrap_or_else(|position| position);
+            best.insert(position, candidate);
+            best.truncate(k);
+        }
+
+        let diff = coordinate(q, axis) - coordinate(p, axis);
+        let (near, far) = match diff <= T::zero() {
+            true => ((start, mid), (mid + 1, end)),
+            false => ((mid + 1, end), (start, mid)),
+        };
+        self.visit_nearest(near.0, near.1, depth + 1, q, k, best);
+        if best.len() < k || diff * diff <= best[best.len() - 1].0 {
+            self.visit_nearest(far.0, far.1, depth + 1, q, k, best);
+        }
+    }
+
+    fn visit_within(
+        &self,
+        start: usize,
+        end: usize,
+        depth: usize,
+        q: &Point<T>,
+        squared: T,
+        found: &mut Vec<(T, usize)>,
+    ) {
+        if start >= end {
+            return;
+        }
+
+        let mid = (start + end) / 2;
+        let (axis, p) = (depth % self.dimensions, self.point(mid));
+        let distance = self.squared_distance(p, q);
+        if distance <= squared {
+            found.push((distance, self.order[mid]));
+        }
+
+        let diff = coordinate(q, axis) - coordinate(p, axis);
+        if diff <= T::zero() || diff * diff <= squared {
+            self.visit_within(start, mid, depth + 1, q, squared, found);
+        }
+        if diff >= T::zero() || diff * diff <= squared {
+            self.visit_within(mid + 1, end, depth + 1, q, squared, found);
+        }
+    }
+
+    fn squared_distance(&self, p: &Point<T>, q: &Point<T>) -> T {
+        (0..self.dimensions)
+            .map(|axis| coordinate(p, axis) - coordinate(q, axis))
+            .fold(T::zero(), |sum, d| sum + d * d)
+    }
+}
+
+impl<T: Scalar> PointIndex<T> for KdTree<T> {
+    fn range(&self, min: &Point<T>, max: &Point<T>) -> Vec<usize> {
+        let mut found = vec![];
+        self.visit_range(0, self.order.len(), 0, min, max, &mut |i| found.push(i));
+        found
+    }
+
+    /// subtrees inside the box are counted without visiting them
+    fn count(&self, min: &Point<T>, max: &Point<T>) -> usize {
+        let mut cell = Cell {
+            low: [None; 3],
+            high: [None; 3],
+        };
+        self.count_range(0, self.order.len(), 0, min, max, &mut cell)
+    }
+}
+
+// the region of a subtree, None where it is not bounded
+struct Cell<T> {
+    low: [Option<T>; 3],
+    high: [Option<T>; 3],
+}
+
+impl<T: Scalar> Cell<T> {
+    fn is_inside(&self, min: &Point<T>, max: &Point<T>, dimensions: usize) -> bool {
+        (0..dimensions).all(|axis| {
+            self.low[axis].is_some_and(|low| coordinate(min, axis) <= low)
+                && self.high[axis].is_some_and(|high| high <= coordinate(max, axis))
+        })
+    }
+}
+
+fn coordinate<T: Scalar>(p: &Point<T>, axis: usize) -> T {
+    match axis {
+        0 => p.x,
+        1 => p.y,
+        _ => p.z,
+    }
+}
+
+/// by distance, then by index
+fn distance_cmp<T: Scalar>(a: &(T, usize), b: &(T, usize)) -> Ordering {
+    a.0.partial_cmp(&b.0)
+        .unwrap_or(Ordering::Equal)
+        .then(a.1.cmp(&b.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KdTree;
+    use crate::geometry::{point::Point, point_index::PointIndex, points::Points};
+    use rand::Rng;
+
+    fn squared(p: &Point, q: &Point, dimensions: usize) -> f64 {
+        let d = [p.x - q.x, p.y - q.y, p.z - q.z];
+        d[..dimensions].iter().map(|d| d * d).sum()
+    }
+
+    /// the k closest points by a linear scan
+    fn brute_nearest(points: &Points, q: &Point, k: usize, dimensions: usize) -> Vec<usize> {
+        let mut all: Vec<usize> = (0..points.len()).collect();
+        all.sort_by(|&a, &b| {
+            let (da, db) = (
+                squared(&points[a], q, dimensions),
+                squared(&points[b], q, dimensions),
+            );
+            da.partial_cmp(&db).unwrap().then(a.cmp(&b))
+        });
+        all.truncate(k);
+        all
+    }
+
+    #[test]
+    fn range_in_the_plane() {
+        let points: Points = vec![
+            [1.0, 1.0],
+            [2.0, 5.0],
+            [4.0, 2.0],
+            [5.0, 5.0],
+            [3.0, 3.0],
+            [2.0, 2.0],
+        ]
+        .into();
+        let tree = KdTree::new(points);
+
+        let mut found = tree.range(&Point::from2d(2.0, 2.0), &Point::from2d(4.0, 5.0));
+        found.sort();
+        assert_eq!(found, vec![1, 2, 4, 5]);
+        assert_eq!(
+            tree.count(&Point::from2d(2.0, 2.0), &Point::from2d(4.0, 5.0)),
+            4
+        );
+        assert_eq!(
+            tree.count(&Point::from2d(0.0, 0.0), &Point::from2d(9.0, 9.0)),
+            6
+        );
+        assert!(tree
+            .range(&Point::from2d(6.0, 0.0), &Point::from2d(9.0, 9.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn random_ranges() {
+        let mut rng = rand::thread_rng();
+        for dimensions in [2, 3] {
+            let points = Points::random(500, 0.0..100.0);
+            let tree = match dimensions {
+                2 => KdTree::new(points.clone()),
+                _ => KdTree::new3d(points.clone()),
+            };
+
+            for _ in 0..50 {
+                let (a, b) = (Point::random(0.0..100.0), Point::random(0.0..100.0));
+                let min = Point::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z));
+                let max = Point::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z));
+                let inside = |p: &Point| {
+                    let (p, min, max) = (
+                        [p.x, p.y, p.z],
+                        [min.x, min.y, min.z],
+                        [max.x, max.y, max.z],
+                    );
+                    (0..dimensions).all(|d| min[d] <= p[d] && p[d] <= max[d])
+                };
+                let expected: Vec<usize> =
+                    (0..points.len()).filter(|&i| inside(&points[i])).collect();
+
+                let mut found = tree.range(&min, &max);
+                found.sort();
+                assert_eq!(found, expected);
+                assert_eq!(tree.count(&min, &max), expected.len());
+            }
+
+            let q = Point::new(rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), 50.0);
+            for k in [0, 1, 7, 600] {
+                assert_eq!(
+                    tree.nearest(&q, k),
+                    brute_nearest(&points, &q, k, dimensions)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_radius() {
+        let points = Points::random(300, 0.0..10.0);
+        let tree = KdTree::new3d(points.clone());
+        let q = Point::new(5.0, 5.0, 5.0);
+
+        let found = tree.within(&q, 2.5);
+        let expected: Vec<usize> = brute_nearest(&points, &q, points.len(), 3)
+            .into_iter()
+            .filter(|&i| squared(&points[i], &q, 3) <= 6.25)
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn integer_points_with_duplicates() {
+        let points: Points<i32> = vec![[0, 0], [1, 1], [1, 1], [1, 1], [2, 2], [3, 0]].into();
+        let tree = KdTree::new(points);
+
+        assert_eq!(tree.nearest(&Point::from2d(1, 1), 4), vec![1, 2, 3, 0]);
+        assert_eq!(tree.within(&Point::from2d(1, 1), 0), vec![1, 2, 3]);
+        assert_eq!(tree.count(&Point::from2d(1, 1), &Point::from2d(1, 1)), 3);
+        assert_eq!(tree.count(&Point::from2d(0, 0), &Point::from2d(3, 0)), 2);
+    }
+
+    #[test]
+    fn empty_tree() {
+        let tree: KdTree = KdTree::new(Points::new());
+        assert!(tree.is_empty());
+        assert!(tree.nearest(&Point::from2d(0.0, 0.0), 3).is_empty());
+        assert_eq!(
+            tree.count(&Point::from2d(0.0, 0.0), &Point::from2d(1.0, 1.0)),
+            0
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+/// Triangles referring to their vertexes by index, each one in counterclockwise
+/// order, together with the adjacency between them
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TriangleMesh {
+    triangles: Vec<[usize; 3]>,
+
+    // the i-th neighbour of a triangle is the one across the edge opposite to
+    // its i-th vertex, None on the boundary
+    neighbors: Vec<[Option<usize>; 3]>,
+}
+
+impl TriangleMesh {
+    /// computes the adjacency of the triangles, two of them are neighbours when
+    /// they share an edge traversed in opposite directions
+    pub fn new(triangles: Vec<[usize; 3]>) -> Self {
+        let mut edges: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        for (t, triangle) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                edges.insert((triangle[(i + 1) % 3], triangle[(i + 2) % 3]), (t, i));
+            }
+        }
+
+        let neighbors = triangles
+            .iter()
+            .map(|triangle| {
+                let mut neighbors = [None; 3];
+                for (i, neighbor) in neighbors.iter_mut().enumerate() {
+                    let (a, b) = (triangle[(i + 1) % 3], triangle[(i + 2) % 3]);
+                    *neighbor = edges.get(&(b, a)).map(|&(t, _)| t);
+                }
+                neighbors
+            })
+            .collect();
+
+        Self {
+            triangles,
+            neighbors,
+        }
+    }
+
+    pub fn triangles(&self) -> &Vec<[usize; 3]> {
+        &self.triangles
+    }
+
+    pub fn neighbors(&self) -> &Vec<[Option<usize>; 3]> {
+        &self.neighbors
+    }
+
+    /// the triangle across the edge opposite to the i-th vertex of triangle t
+    pub fn neighbor(&self, t: usize, i: usize) -> Option<usize> {
+        self.neighbors[t][i]
+    }
+
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// every edge once, with the smaller index first
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = self
+            .triangles
+            .iter()
+            .flat_map(|t| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])])
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect();
+        edges.sort();
+        edges.dedup();
+        edges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TriangleMesh;
+
+    #[test]
+    fn mesh_adjacency() {
+        // a square split along the diagonal 0-2, and a triangle on its right
+        let mesh = TriangleMesh::new(vec![[0, 1, 2], [0, 2, 3], [1, 4, 2]]);
+
+        assert_eq!(mesh.len(), 3);
+        assert_eq!(mesh.neighbors()[0], [Some(2), Some(1), None]);
+        assert_eq!(mesh.neighbors()[1], [None, None, Some(0)]);
+        assert_eq!(mesh.neighbor(2, 1), Some(0));
+        assert_eq!(
+            mesh.edges(),
+            vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 4), (2, 3), (2, 4)]
+        );
+    }
+
+    #[test]
+    fn empty_mesh() {
+        let mesh = TriangleMesh::new(vec![]);
+
+        assert!(mesh.is_empty());
+        assert!(mesh.edges().is_empty());
+    }
+}
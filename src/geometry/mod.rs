@@ -1,3 +1,20 @@
+pub mod dcel;
+pub mod half_plane;
+pub mod interval_tree;
+pub mod kd_tree;
+pub mod mesh;
 pub mod point;
+pub mod point_index;
 pub mod points;
+pub mod polygon;
+pub mod predicates;
+pub mod priority_search_tree;
+pub mod quadtree;
+pub mod range_tree;
+pub mod rational;
+pub mod scalar;
 pub mod segment;
+pub mod segment_tree;
+#[cfg(test)]
+pub(crate) mod test_utils;
+pub mod window_index;
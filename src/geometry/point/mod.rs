@@ -1,9 +1,12 @@
-/// A rappresentation of a point in a three-dimensional space
+/// A rappresentation of a point in a three-dimensional space.
+/// The coordinates can be any `Scalar`, f64 by default.
+/// `PartialOrd` is the lexicographic order, while `Ord` is the sweep plane order
+#[allow(clippy::derive_ord_xor_partial_ord)]
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
-pub struct Point {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
+pub struct Point<T = f64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
 }
 
 pub mod point_impl;
@@ -7,34 +7,41 @@ use std::{
 use rand::Rng;
 
 use super::Point;
+use crate::geometry::{predicates::Orientation, scalar::Scalar};
 
-impl Eq for Point {}
+impl<T: Scalar> Eq for Point<T> {}
 
-impl Ord for Point {
-    fn cmp(&self, other: &Point) -> Ordering {
+impl<T: Scalar> Ord for Point<T> {
+    fn cmp(&self, other: &Point<T>) -> Ordering {
         self.sweep_plane_cmp(other)
     }
 }
 
-impl From<[f64; 2]> for Point {
-    fn from(p: [f64; 2]) -> Self {
+impl<T: Scalar> From<[T; 2]> for Point<T> {
+    fn from(p: [T; 2]) -> Self {
         Self::from2d(p[0], p[1])
     }
 }
 
-impl From<Point> for [f64; 2] {
-    fn from(p: Point) -> Self {
+impl<T: Scalar> From<[T; 3]> for Point<T> {
+    fn from(p: [T; 3]) -> Self {
+        Self::new(p[0], p[1], p[2])
+    }
+}
+
+impl<T: Scalar> From<Point<T>> for [T; 2] {
+    fn from(p: Point<T>) -> Self {
         [p.x, p.y]
     }
 }
 
-impl From<Point> for [f64; 3] {
-    fn from(p: Point) -> Self {
+impl<T: Scalar> From<Point<T>> for [T; 3] {
+    fn from(p: Point<T>) -> Self {
         [p.x, p.y, p.z]
     }
 }
 
-impl Sub for Point {
+impl<T: Scalar> Sub for Point<T> {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
@@ -46,18 +53,13 @@ impl Sub for Point {
     }
 }
 
-impl Display for Point {
+impl<T: Scalar> Display for Point<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({:.1}, {:.1})", self.x, self.y)
     }
 }
 
-impl Point {
-    /// create new Point starting from (x, y, z) coordinates
-    pub fn new(x: f64, y: f64, z: f64) -> Self {
-        Point { x, y, z }
-    }
-
+impl Point<f64> {
     pub fn random(r: Range<f64>) -> Self {
         let mut rng = rand::thread_rng();
         Self {
@@ -66,49 +68,81 @@ impl Point {
             z: rng.gen_range(r.clone()),
         }
     }
+}
+
+impl<T: Scalar> Point<T> {
+    /// create new Point starting from (x, y, z) coordinates
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Point { x, y, z }
+    }
+
+    /// create new Point starting from (x, y) coordinates
+    pub fn from2d(x: T, y: T) -> Self {
+        Point { x, y, z: T::zero() }
+    }
+
+    /// the same point with coordinates converted to another scalar
+    pub fn cast<U: Scalar>(&self) -> Point<U> {
+        Point::new(
+            U::from_f64(self.x.to_f64()),
+            U::from_f64(self.y.to_f64()),
+            U::from_f64(self.z.to_f64()),
+        )
+    }
 
-    pub fn lexicograph_cmp(&self, p: &Point) -> Ordering {
-        self.partial_cmp(&p).unwrap()
+    /// true if no coordinate is NaN or infinite
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
     }
 
-    pub fn sweep_plane_cmp(&self, p: &Point) -> Ordering {
+    /// # Panics
+    /// if a coordinate is NaN, see `Point::is_finite`
+    pub fn lexicograph_cmp(&self, p: &Point<T>) -> Ordering {
+        self.partial_cmp(p)
+            .expect("Comparision is impossible with NaN coordinates!")
+    }
+
+    /// # Panics
+    /// if a coordinate is NaN, see `Point::is_finite`
+    pub fn sweep_plane_cmp(&self, p: &Point<T>) -> Ordering {
         match self.y.partial_cmp(&p.y) {
             // self.y < p.y
             Some(Ordering::Less) => Ordering::Greater,
             // self.y > p.y
             Some(Ordering::Greater) => Ordering::Less,
             // self.y  == p.y && self.x < p.x
-            Some(Ordering::Equal) => self.x.partial_cmp(&p.x).unwrap(),
+            Some(Ordering::Equal) => self
+                .x
+                .partial_cmp(&p.x)
+                .expect("Comparision is impossible with NaN coordinates!"),
             // NaN value
-            None => panic!("Comparision is impossible!"),
+            None => panic!("Comparision is impossible with NaN coordinates!"),
         }
     }
 
-    /// create new Point starting from (x, y) coordinates
-    pub fn from2d(x: f64, y: f64) -> Self {
-        Point { x, y, z: 0.0 }
-    }
-
     /// distance from p2
-    pub fn distance_from(&self, p2: &Point) -> f64 {
-        ((p2.x - self.x).powi(2) + (p2.y - self.y).powi(2) + (p2.z - self.z).powi(2)).sqrt()
+    pub fn distance_from(&self, p2: &Point<T>) -> f64 {
+        let d = *p2 - *self;
+        let (x, y, z) = (d.x.to_f64(), d.y.to_f64(), d.z.to_f64());
+        (x.powi(2) + y.powi(2) + z.powi(2)).sqrt()
     }
 
-    pub fn distance(p1: &Point, p2: &Point) -> f64 {
+    pub fn distance(p1: &Point<T>, p2: &Point<T>) -> f64 {
         p1.distance_from(p2)
     }
 
     /// Given 3 Points it returns true if they make a right turn, false otherwise
-    pub fn make_right_turn(p1: &Point, p2: &Point, p3: &Point) -> bool {
-        Point::clockwise(&(*p3 - *p1), &(*p2 - *p1))
+    pub fn make_right_turn(p1: &Point<T>, p2: &Point<T>, p3: &Point<T>) -> bool {
+        T::orientation(p1, p2, p3) == Orientation::Right
     }
 
     /// cross product p1 x p2 > 0
-    pub fn clockwise(p1: &Point, p2: &Point) -> bool {
-        (p1.x * p2.y - p1.y * p2.x) > 0.0
+    pub fn clockwise(p1: &Point<T>, p2: &Point<T>) -> bool {
+        let origin = Point::new(T::zero(), T::zero(), T::zero());
+        T::orientation(&origin, p1, p2) == Orientation::Left
     }
 
-    pub fn anti_clockwise(p1: &Point, p2: &Point) -> bool {
+    pub fn anti_clockwise(p1: &Point<T>, p2: &Point<T>) -> bool {
         !Point::clockwise(p1, p2)
     }
 }
@@ -88,7 +88,7 @@ fn make_right_turn_false() {
     let p2 = Point::from2d(0.5, 0.5);
     let p3 = Point::from2d(0.0, 1.0);
 
-    assert_eq!(Point::make_right_turn(&p1, &p2, &p3), false);
+    assert!(!Point::make_right_turn(&p1, &p2, &p3));
 }
 
 #[test]
@@ -97,7 +97,17 @@ fn make_right_turn_true() {
     let p2 = Point::from2d(1.0, 1.0);
     let p3 = Point::from2d(2.0, 0.0);
 
-    assert_eq!(Point::make_right_turn(&p1, &p2, &p3), true);
+    assert!(Point::make_right_turn(&p1, &p2, &p3));
+}
+
+#[test]
+fn make_right_turn_collinear() {
+    let p1 = Point::from2d(0.1, 0.1);
+    let p2 = Point::from2d(0.2, 0.2);
+    let p3 = Point::from2d(0.3, 0.3);
+
+    assert!(!Point::make_right_turn(&p1, &p2, &p3));
+    assert!(!Point::make_right_turn(&p3, &p2, &p1));
 }
 
 #[test]
@@ -0,0 +1,13 @@
+use super::point::Point;
+
+/// A spatial index answering orthogonal range queries over points, which are
+/// referred by their index in the input. The implementations trade memory and
+/// building time for query time
+pub trait PointIndex<T> {
+    /// the points in the box going from min to max, borders included
+    fn range(&self, min: &Point<T>, max: &Point<T>) -> Vec<usize>;
+
+    /// the number of points in the box going from min to max, borders
+    /// included, without reporting them
+    fn count(&self, min: &Point<T>, max: &Point<T>) -> usize;
+}
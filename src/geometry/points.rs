@@ -1,21 +1,15 @@
 use std::{
     ops::{Index, IndexMut, Range},
+    slice::Iter,
     vec::IntoIter,
 };
 
-use super::point::Point;
+use super::{point::Point, scalar::Scalar};
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Points(Vec<Point>);
-impl Points {
-    pub fn new() -> Self {
-        Self { 0: vec![] }
-    }
-
-    pub fn from(data: impl Into<Vec<Point>>) -> Self {
-        Self { 0: data.into() }
-    }
+pub struct Points<T = f64>(Vec<Point<T>>);
 
+impl Points<f64> {
     pub fn random(capacity: usize, r: Range<f64>) -> Self {
         let mut random_points = Self::new();
         for _i in 0..capacity {
@@ -23,6 +17,16 @@ impl Points {
         }
         random_points
     }
+}
+
+impl<T: Scalar> Points<T> {
+    pub fn new() -> Self {
+        Self(vec![])
+    }
+
+    pub fn from(data: impl Into<Vec<Point<T>>>) -> Self {
+        Self(data.into())
+    }
 
     pub fn lexicograph_sort(&mut self) {
         self.0.sort_by(|p1, p2| p1.lexicograph_cmp(p2));
@@ -36,7 +40,15 @@ impl Points {
         self.0.len()
     }
 
-    pub fn push(&mut self, p: impl Into<Point>) {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, Point<T>> {
+        self.0.iter()
+    }
+
+    pub fn push(&mut self, p: impl Into<Point<T>>) {
         self.0.push(p.into());
     }
 
@@ -44,13 +56,19 @@ impl Points {
         self.0.pop();
     }
 
-    pub fn append(&mut self, points: &mut Points) {
+    pub fn append(&mut self, points: &mut Points<T>) {
         self.0.append(&mut points.0);
     }
 }
 
-impl IntoIterator for Points {
-    type Item = Point;
+impl<T: Scalar> Default for Points<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> IntoIterator for Points<T> {
+    type Item = Point<T>;
 
     type IntoIter = IntoIter<Self::Item>;
 
@@ -59,36 +77,36 @@ impl IntoIterator for Points {
     }
 }
 
-impl Index<usize> for Points {
-    type Output = Point;
+impl<T> Index<usize> for Points<T> {
+    type Output = Point<T>;
 
     fn index(&self, index: usize) -> &Self::Output {
         &self.0[index]
     }
 }
 
-impl IndexMut<usize> for Points {
+impl<T> IndexMut<usize> for Points<T> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.0[index]
     }
 }
 
-impl From<Points> for Vec<[f64; 2]> {
-    fn from(points: Points) -> Self {
+impl<T: Scalar> From<Points<T>> for Vec<[T; 2]> {
+    fn from(points: Points<T>) -> Self {
         points.0.iter().map(|p| (*p).into()).collect()
     }
 }
 
-impl From<Vec<[f64; 2]>> for Points {
-    fn from(points: Vec<[f64; 2]>) -> Self {
+impl<T: Scalar> From<Vec<[T; 2]>> for Points<T> {
+    fn from(points: Vec<[T; 2]>) -> Self {
         Self::from(
             points
                 .iter()
                 .map(|array_p| {
-                    let p: Point = (*array_p).into();
+                    let p: Point<T> = (*array_p).into();
                     p
                 })
-                .collect::<Vec<Point>>(),
+                .collect::<Vec<Point<T>>>(),
         )
     }
 }
@@ -0,0 +1,12 @@
+use super::points::Points;
+
+/// A closed ring of vertices: the last vertex is joined to the first one.
+/// The coordinates can be any `Scalar`, f64 by default
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polygon<T = f64> {
+    vertices: Points<T>,
+}
+
+pub mod polygon_impl;
+
+mod tests;
@@ -0,0 +1,188 @@
+use super::Polygon;
+use crate::{
+    algorithms::{algorithm::Algorithm, sweep_plane::SweepPlane},
+    error::{check_finite, GalmetryError, Result},
+    geometry::{
+        point::Point,
+        points::Points,
+        predicates::Orientation,
+        scalar::{Field, Scalar},
+        segment::Segment,
+    },
+};
+
+impl<T: Scalar> Polygon<T> {
+    /// The vertices are taken in the given order, without repeating the first one
+    pub fn new(vertices: Points<T>) -> Self {
+        Self { vertices }
+    }
+
+    /// like `new`, but rejects NaN and infinite coordinates and fewer than 3 vertices
+    pub fn try_new(vertices: Points<T>) -> Result<Self> {
+        check_finite(vertices.iter().map(|p| p.is_finite()))?;
+        if vertices.len() < 3 {
+            return Err(GalmetryError::InsufficientInput {
+                required: 3,
+                found: vertices.len(),
+            });
+        }
+        Ok(Self::new(vertices))
+    }
+
+    pub fn vertices(&self) -> &Points<T> {
+        &self.vertices
+    }
+
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// the i-th edge goes from the i-th vertex to the next one
+    pub fn edges(&self) -> Vec<Segment<T>> {
+        let n = self.len();
+        (0..n)
+            .map(|i| Segment::new(self.vertices[i], self.vertices[(i + 1) % n]))
+            .collect()
+    }
+
+    /// twice the signed area, positive if the vertices are in counterclockwise
+    /// order. It is exact for integer and rational coordinates
+    pub fn doubled_signed_area(&self) -> T {
+        let n = self.len();
+        (0..n).fold(T::zero(), |area, i| {
+            let (p, q) = (self.vertices[i], self.vertices[(i + 1) % n]);
+            area + p.x * q.y - q.x * p.y
+        })
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        self.edges()
+            .iter()
+            .map(|edge| edge.start.distance_from(&edge.end))
+            .sum()
+    }
+
+    /// Left if the vertices are in counterclockwise order, Right if clockwise and
+    /// Collinear if the polygon has no area. It looks at the turn in the
+    /// lowest-leftmost vertex, which is convex in a simple polygon, so the result
+    /// is exact even where the area rounds to zero
+    pub fn orientation(&self) -> Orientation {
+        let n = self.len();
+        if n < 3 {
+            return Orientation::Collinear;
+        }
+
+        let lowest = (0..n)
+            .min_by(|&i, &j| {
+                let (p, q) = (self.vertices[i], self.vertices[j]);
+                (p.y, p.x)
+                    .partial_cmp(&(q.y, q.x))
+                    .expect("Comparision is impossible with NaN coordinates!")
+            })
+            .unwrap_or(0);
+
+        let prev = self.vertices[(lowest + n - 1) % n];
+        let next = self.vertices[(lowest + 1) % n];
+        T::orientation(&prev, &self.vertices[lowest], &next)
+    }
+
+    pub fn is_ccw(&self) -> bool {
+        self.orientation() == Orientation::Left
+    }
+
+    pub fn is_cw(&self) -> bool {
+        self.orientation() == Orientation::Right
+    }
+
+    /// reverse the vertices if they are in clockwise order
+    pub fn make_ccw(&mut self) {
+        if self.is_cw() {
+            self.reverse();
+        }
+    }
+
+    /// reverse the vertices if they are in counterclockwise order
+    pub fn make_cw(&mut self) {
+        if self.is_ccw() {
+            self.reverse();
+        }
+    }
+
+    fn reverse(&mut self) {
+        let mut vertices: Vec<Point<T>> = self.vertices.clone().into_iter().collect();
+        vertices.reverse();
+        self.vertices = Points::from(vertices);
+    }
+}
+
+impl<T: Field> Polygon<T> {
+    /// positive if the vertices are in counterclockwise order
+    pub fn signed_area(&self) -> T {
+        self.doubled_signed_area() / (T::one() + T::one())
+    }
+
+    pub fn area(&self) -> T {
+        self.signed_area().abs()
+    }
+
+    /// the center of mass of the polygon area, `None` if the area is zero
+    pub fn centroid(&self) -> Option<Point<T>> {
+        let doubled_area = self.doubled_signed_area();
+        if doubled_area == T::zero() {
+            return None;
+        }
+
+        let n = self.len();
+        let (mut x, mut y) = (T::zero(), T::zero());
+        for i in 0..n {
+            let (p, q) = (self.vertices[i], self.vertices[(i + 1) % n]);
+            let cross = p.x * q.y - q.x * p.y;
+            x = x + (p.x + q.x) * cross;
+            y = y + (p.y + q.y) * cross;
+        }
+
+        let six_area = doubled_area * T::from_f64(3.0);
+        Some(Point::from2d(x / six_area, y / six_area))
+    }
+
+    /// true if the edges meet only in the vertices they share with the
+    /// previous and the next edge. The crossings are found with `SweepPlane`
+    pub fn is_simple(&self) -> bool {
+        let n = self.len();
+        if n < 3 {
+            return false;
+        }
+
+        let intersections = SweepPlane::build(self.edges()).calculate();
+        intersections.iter().all(|(point, intersection)| {
+            let edges: Vec<usize> = intersection.segments().into_iter().collect();
+            if edges.len() != 2 || !intersection.interior.is_empty() {
+                return false;
+            }
+
+            // the vertex shared by two consecutive edges
+            let shared = match (edges[0], edges[1]) {
+                (i, j) if j == i + 1 => j,
+                (0, j) if j == n - 1 => 0,
+                _ => return false,
+            };
+            *point == self.vertices[shared]
+        })
+    }
+}
+
+impl<T: Scalar> From<Points<T>> for Polygon<T> {
+    fn from(vertices: Points<T>) -> Self {
+        Self::new(vertices)
+    }
+}
+
+impl<T: Scalar> From<Vec<[T; 2]>> for Polygon<T> {
+    fn from(vertices: Vec<[T; 2]>) -> Self {
+        Self::new(vertices.into())
+    }
+}
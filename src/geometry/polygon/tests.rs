@@ -0,0 +1,116 @@
+#[cfg(test)]
+use crate::error::GalmetryError;
+
+#[cfg(test)]
+use crate::geometry::{
+    point::Point, points::Points, polygon::Polygon, predicates::Orientation, rational::Rational,
+};
+
+#[cfg(test)]
+fn square() -> Polygon {
+    vec![[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0]].into()
+}
+
+#[test]
+fn polygon_area_and_perimeter() {
+    let polygon = square();
+
+    assert_eq!(polygon.signed_area(), 16.0);
+    assert_eq!(polygon.area(), 16.0);
+    assert_eq!(polygon.perimeter(), 16.0);
+}
+
+#[test]
+fn polygon_orientation() {
+    let mut polygon = square();
+    assert_eq!(polygon.orientation(), Orientation::Left);
+    assert!(polygon.is_ccw());
+
+    polygon.make_cw();
+    assert!(polygon.is_cw());
+    assert_eq!(polygon.signed_area(), -16.0);
+    assert_eq!(polygon.vertices()[0], Point::from2d(0.0, 4.0));
+
+    polygon.make_ccw();
+    assert_eq!(polygon, square());
+}
+
+#[test]
+fn polygon_orientation_tiny_area() {
+    // the third vertex is one ulp above the line of the other two
+    let ulp = 2.0f64.powi(-19);
+    let polygon: Polygon = vec![[1e10, 1e10], [1e10 + 1.0, 1e10], [1e10 + 2.0, 1e10 + ulp]].into();
+
+    assert!(polygon.is_ccw());
+}
+
+#[test]
+fn polygon_centroid() {
+    let polygon: Polygon = vec![[0.0, 0.0], [6.0, 0.0], [0.0, 6.0]].into();
+
+    assert_eq!(polygon.centroid(), Some(Point::from2d(2.0, 2.0)));
+    assert_eq!(square().centroid(), Some(Point::from2d(2.0, 2.0)));
+
+    let flat: Polygon = vec![[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]].into();
+    assert_eq!(flat.centroid(), None);
+}
+
+#[test]
+fn polygon_centroid_rational() {
+    let r = |x: i64, y: i64| Point::from2d(Rational::from(x), Rational::from(y));
+    let polygon = Polygon::new(Points::from(vec![r(0, 0), r(1, 0), r(0, 1)]));
+
+    let third = Rational::new(1, 3);
+    assert_eq!(polygon.area(), Rational::new(1, 2));
+    assert_eq!(polygon.centroid(), Some(Point::from2d(third, third)));
+}
+
+#[test]
+fn polygon_integer_coordinates() {
+    let polygon: Polygon<i32> = vec![[0, 0], [3, 0], [0, 3]].into();
+
+    assert_eq!(polygon.doubled_signed_area(), 9);
+    assert!(polygon.is_ccw());
+}
+
+#[test]
+fn polygon_is_simple() {
+    assert!(square().is_simple());
+
+    let concave: Polygon = vec![[0.0, 0.0], [4.0, 0.0], [2.0, 1.0], [4.0, 4.0], [0.0, 4.0]].into();
+    assert!(concave.is_simple());
+}
+
+#[test]
+fn polygon_bowtie_is_not_simple() {
+    let bowtie: Polygon = vec![[0.0, 0.0], [4.0, 4.0], [4.0, 0.0], [0.0, 4.0]].into();
+
+    assert!(!bowtie.is_simple());
+}
+
+#[test]
+fn polygon_touching_vertex_is_not_simple() {
+    // the vertex (2, 0) touches the opposite edge
+    let polygon: Polygon = vec![[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [2.0, 0.0], [0.0, 4.0]].into();
+
+    assert!(!polygon.is_simple());
+}
+
+#[test]
+fn polygon_spike_is_not_simple() {
+    let polygon: Polygon = vec![[0.0, 0.0], [4.0, 0.0], [2.0, 0.0], [2.0, 4.0]].into();
+
+    assert!(!polygon.is_simple());
+}
+
+#[test]
+fn try_new_polygon() {
+    assert_eq!(
+        Polygon::try_new(vec![[0.0, 0.0], [1.0, 0.0]].into()),
+        Err(GalmetryError::InsufficientInput {
+            required: 3,
+            found: 2
+        })
+    );
+    assert!(Polygon::try_new(square().vertices().clone()).is_ok());
+}
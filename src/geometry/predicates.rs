@@ -0,0 +1,382 @@
+//! Robust geometric predicates.
+//!
+//! Every predicate first evaluates its determinant with plain `f64` arithmetic
+//! and checks the result against a forward error bound (Shewchuk, "Adaptive
+//! Precision Floating-Point Arithmetic and Fast Robust Geometric Predicates").
+//! When the bound cannot certify the sign, the determinant is evaluated again
+//! exactly using floating-point expansions, so the sign of the returned value
+//! is always correct.
+
+use std::cmp::Ordering;
+
+use super::{point::Point, scalar::Scalar};
+
+const EPSILON: f64 = f64::EPSILON / 2.0;
+const CCW_ERRBOUND_A: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const O3D_ERRBOUND_A: f64 = (7.0 + 56.0 * EPSILON) * EPSILON;
+const ICC_ERRBOUND_A: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+
+/// Position of a point with respect to a directed line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Left,
+    Right,
+    Collinear,
+}
+
+impl Orientation {
+    pub fn from_ordering(sign: Ordering) -> Self {
+        match sign {
+            Ordering::Greater => Orientation::Left,
+            Ordering::Less => Orientation::Right,
+            Ordering::Equal => Orientation::Collinear,
+        }
+    }
+
+    pub fn from_det(det: f64) -> Self {
+        if det > 0.0 {
+            Orientation::Left
+        } else if det < 0.0 {
+            Orientation::Right
+        } else {
+            Orientation::Collinear
+        }
+    }
+}
+
+/// Orientation of c with respect to the line going from a to b
+pub fn orientation<T: Scalar>(a: &Point<T>, b: &Point<T>, c: &Point<T>) -> Orientation {
+    T::orientation(a, b, c)
+}
+
+/// Returns a positive value if a, b and c are in counterclockwise order,
+/// a negative value if they are in clockwise order and zero if they are collinear.
+/// The sign of the result is exact.
+pub fn orient2d(a: &Point, b: &Point, c: &Point) -> f64 {
+    let detleft = (a.x - c.x) * (b.y - c.y);
+    let detright = (a.y - c.y) * (b.x - c.x);
+    let det = detleft - detright;
+
+    let errbound = CCW_ERRBOUND_A * (detleft.abs() + detright.abs());
+    if det.abs() > errbound {
+        return det;
+    }
+
+    orient2d_exact(a, b, c)
+}
+
+/// Returns a positive value if d lies inside the circle passing through a, b and c,
+/// a negative value if it lies outside and zero if the four points are cocircular.
+/// a, b and c must be in counterclockwise order, otherwise the sign is reversed.
+pub fn incircle(a: &Point, b: &Point, c: &Point, d: &Point) -> f64 {
+    let adx = a.x - d.x;
+    let bdx = b.x - d.x;
+    let cdx = c.x - d.x;
+    let ady = a.y - d.y;
+    let bdy = b.y - d.y;
+    let cdy = c.y - d.y;
+
+    let bdxcdy = bdx * cdy;
+    let cdxbdy = cdx * bdy;
+    let alift = adx * adx + ady * ady;
+
+    let cdxady = cdx * ady;
+    let adxcdy = adx * cdy;
+    let blift = bdx * bdx + bdy * bdy;
+
+    let adxbdy = adx * bdy;
+    let bdxady = bdx * ady;
+    let clift = cdx * cdx + cdy * cdy;
+
+    let det = alift * (bdxcdy - cdxbdy) + blift * (cdxady - adxcdy) + clift * (adxbdy - bdxady);
+
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift
+        + (cdxady.abs() + adxcdy.abs()) * blift
+        + (adxbdy.abs() + bdxady.abs()) * clift;
+    let errbound = ICC_ERRBOUND_A * permanent;
+    if det.abs() > errbound {
+        return det;
+    }
+
+    incircle_exact(a, b, c, d)
+}
+
+/// Returns a positive value if d lies below the plane passing through a, b and c,
+/// where "below" means that a, b and c appear in counterclockwise order when seen
+/// from above the plane. Returns zero if the four points are coplanar.
+pub fn orient3d(a: &Point, b: &Point, c: &Point, d: &Point) -> f64 {
+    let adx = a.x - d.x;
+    let bdx = b.x - d.x;
+    let cdx = c.x - d.x;
+    let ady = a.y - d.y;
+    let bdy = b.y - d.y;
+    let cdy = c.y - d.y;
+    let adz = a.z - d.z;
+    let bdz = b.z - d.z;
+    let cdz = c.z - d.z;
+
+    let bdxcdy = bdx * cdy;
+    let cdxbdy = cdx * bdy;
+
+    let cdxady = cdx * ady;
+    let adxcdy = adx * cdy;
+
+    let adxbdy = adx * bdy;
+    let bdxady = bdx * ady;
+
+    let det = adz * (bdxcdy - cdxbdy) + bdz * (cdxady - adxcdy) + cdz * (adxbdy - bdxady);
+
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * adz.abs()
+        + (cdxady.abs() + adxcdy.abs()) * bdz.abs()
+        + (adxbdy.abs() + bdxady.abs()) * cdz.abs();
+    let errbound = O3D_ERRBOUND_A * permanent;
+    if det.abs() > errbound {
+        return det;
+    }
+
+    orient3d_exact(a, b, c, d)
+}
+
+fn orient2d_exact(a: &Point, b: &Point, c: &Point) -> f64 {
+    let acx = Expansion::diff(a.x, c.x);
+    let acy = Expansion::diff(a.y, c.y);
+    let bcx = Expansion::diff(b.x, c.x);
+    let bcy = Expansion::diff(b.y, c.y);
+
+    acx.mul(&bcy).sub(&acy.mul(&bcx)).estimate()
+}
+
+fn incircle_exact(a: &Point, b: &Point, c: &Point, d: &Point) -> f64 {
+    let adx = Expansion::diff(a.x, d.x);
+    let bdx = Expansion::diff(b.x, d.x);
+    let cdx = Expansion::diff(c.x, d.x);
+    let ady = Expansion::diff(a.y, d.y);
+    let bdy = Expansion::diff(b.y, d.y);
+    let cdy = Expansion::diff(c.y, d.y);
+
+    let alift = adx.mul(&adx).add(&ady.mul(&ady));
+    let blift = bdx.mul(&bdx).add(&bdy.mul(&bdy));
+    let clift = cdx.mul(&cdx).add(&cdy.mul(&cdy));
+
+    let bc = bdx.mul(&cdy).sub(&cdx.mul(&bdy));
+    let ca = cdx.mul(&ady).sub(&adx.mul(&cdy));
+    let ab = adx.mul(&bdy).sub(&bdx.mul(&ady));
+
+    alift
+        .mul(&bc)
+        .add(&blift.mul(&ca))
+        .add(&clift.mul(&ab))
+        .estimate()
+}
+
+fn orient3d_exact(a: &Point, b: &Point, c: &Point, d: &Point) -> f64 {
+    let adx = Expansion::diff(a.x, d.x);
+    let bdx = Expansion::diff(b.x, d.x);
+    let cdx = Expansion::diff(c.x, d.x);
+    let ady = Expansion::diff(a.y, d.y);
+    let bdy = Expansion::diff(b.y, d.y);
+    let cdy = Expansion::diff(c.y, d.y);
+    let adz = Expansion::diff(a.z, d.z);
+    let bdz = Expansion::diff(b.z, d.z);
+    let cdz = Expansion::diff(c.z, d.z);
+
+    let bc = bdx.mul(&cdy).sub(&cdx.mul(&bdy));
+    let ca = cdx.mul(&ady).sub(&adx.mul(&cdy));
+    let ab = adx.mul(&bdy).sub(&bdx.mul(&ady));
+
+    adz.mul(&bc)
+        .add(&bdz.mul(&ca))
+        .add(&cdz.mul(&ab))
+        .estimate()
+}
+
+/// A number represented exactly as the sum of non-overlapping f64 components,
+/// sorted by increasing magnitude
+#[derive(Clone, Debug)]
+struct Expansion(Vec<f64>);
+
+impl Expansion {
+    fn diff(a: f64, b: f64) -> Self {
+        let (hi, lo) = two_diff(a, b);
+        Self::from_components(&[lo, hi])
+    }
+
+    fn from_components(components: &[f64]) -> Self {
+        Self(components.iter().copied().filter(|c| *c != 0.0).collect())
+    }
+
+    /// adds a single component keeping the expansion non-overlapping (Grow-Expansion)
+    fn grow(&self, b: f64) -> Self {
+        let mut h = Vec::with_capacity(self.0.len() + 1);
+        let mut q = b;
+        for e in self.0.iter() {
+            let (sum, err) = two_sum(q, *e);
+            if err != 0.0 {
+                h.push(err);
+            }
+            q = sum;
+        }
+        if q != 0.0 {
+            h.push(q);
+        }
+        Self(h)
+    }
+
+    fn add(&self, other: &Expansion) -> Self {
+        other.0.iter().fold(self.clone(), |acc, c| acc.grow(*c))
+    }
+
+    fn neg(&self) -> Self {
+        Self(self.0.iter().map(|c| -c).collect())
+    }
+
+    fn sub(&self, other: &Expansion) -> Self {
+        self.add(&other.neg())
+    }
+
+    /// multiplies the expansion by a single component (Scale-Expansion)
+    fn scale(&self, b: f64) -> Self {
+        let mut h = Vec::with_capacity(2 * self.0.len());
+        let mut iter = self.0.iter();
+        let mut q = match iter.next() {
+            Some(e) => {
+                let (hi, lo) = two_product(*e, b);
+                if lo != 0.0 {
+                    h.push(lo);
+                }
+                hi
+            }
+            None => return Self(h),
+        };
+        for e in iter {
+            let (p_hi, p_lo) = two_product(*e, b);
+            let (sum, err) = two_sum(q, p_lo);
+            if err != 0.0 {
+                h.push(err);
+            }
+            let (new_q, err) = fast_two_sum(p_hi, sum);
+            if err != 0.0 {
+                h.push(err);
+            }
+            q = new_q;
+        }
+        if q != 0.0 {
+            h.push(q);
+        }
+        Self(h)
+    }
+
+    fn mul(&self, other: &Expansion) -> Self {
+        other
+            .0
+            .iter()
+            .fold(Self(vec![]), |acc, c| acc.add(&self.scale(*c)))
+    }
+
+    /// the largest component has the same sign of the exact value
+    fn estimate(&self) -> f64 {
+        self.0.iter().sum()
+    }
+}
+
+fn fast_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    (x, b - b_virtual)
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    let b_roundoff = b - b_virtual;
+    let a_roundoff = a - a_virtual;
+    (x, a_roundoff + b_roundoff)
+}
+
+fn two_diff(a: f64, b: f64) -> (f64, f64) {
+    two_sum(a, -b)
+}
+
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    (x, a.mul_add(b, -x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{incircle, orient2d, orient3d, orientation, Orientation};
+    use crate::geometry::point::Point;
+
+    #[test]
+    fn orient2d_sign() {
+        let a = Point::from2d(0.0, 0.0);
+        let b = Point::from2d(1.0, 0.0);
+
+        assert!(orient2d(&a, &b, &Point::from2d(0.0, 1.0)) > 0.0);
+        assert!(orient2d(&a, &b, &Point::from2d(0.0, -1.0)) < 0.0);
+        assert_eq!(orient2d(&a, &b, &Point::from2d(3.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn orientation_nearly_collinear() {
+        let a = Point::from2d(0.5, 0.5);
+        let b = Point::from2d(12.0, 12.0);
+        let c = Point::from2d(24.0, 24.0);
+
+        assert_eq!(orientation(&a, &b, &c), Orientation::Collinear);
+
+        // the naive cross product rounds this one to zero
+        let d = Point::from2d(0.5 + f64::EPSILON, 0.5);
+        assert_eq!(orientation(&d, &b, &c), Orientation::Right);
+    }
+
+    #[test]
+    fn orientation_is_consistent() {
+        // moving the query point along a tiny grid near a line, the answer must
+        // never contradict the permutations of the same triple
+        let a = Point::from2d(12.0, 12.0);
+        let b = Point::from2d(24.0, 24.0);
+        for i in 0..64 {
+            for j in 0..64 {
+                let c = Point::from2d(0.5 + i as f64 * f64::EPSILON, 0.5 + j as f64 * f64::EPSILON);
+                let o = orientation(&a, &b, &c);
+                assert_eq!(orientation(&b, &c, &a), o);
+                assert_eq!(orientation(&c, &a, &b), o);
+                assert_eq!(Orientation::from_det(-orient2d(&b, &a, &c)), o);
+            }
+        }
+    }
+
+    #[test]
+    fn incircle_sign() {
+        let a = Point::from2d(0.0, 0.0);
+        let b = Point::from2d(1.0, 0.0);
+        let c = Point::from2d(0.0, 1.0);
+
+        assert!(incircle(&a, &b, &c, &Point::from2d(0.5, 0.5)) > 0.0);
+        assert!(incircle(&a, &b, &c, &Point::from2d(2.0, 2.0)) < 0.0);
+        assert_eq!(incircle(&a, &b, &c, &Point::from2d(1.0, 1.0)), 0.0);
+    }
+
+    #[test]
+    fn incircle_nearly_cocircular() {
+        let a = Point::from2d(1e-20, 0.0);
+        let b = Point::from2d(1.0, 1e-20);
+        let c = Point::from2d(0.0, 1.0);
+        let d = Point::from2d(1.0, 1.0);
+
+        assert!(incircle(&a, &b, &c, &d) < 0.0);
+    }
+
+    #[test]
+    fn orient3d_sign() {
+        let a = Point::new(0.0, 0.0, 0.0);
+        let b = Point::new(1.0, 0.0, 0.0);
+        let c = Point::new(0.0, 1.0, 0.0);
+
+        assert!(orient3d(&a, &b, &c, &Point::new(0.0, 0.0, -1.0)) > 0.0);
+        assert!(orient3d(&a, &b, &c, &Point::new(0.0, 0.0, 1.0)) < 0.0);
+        assert_eq!(orient3d(&a, &b, &c, &Point::new(5.0, 7.0, 0.0)), 0.0);
+    }
+}
@@ -0,0 +1,190 @@
+use std::cmp::Ordering;
+
+use super::{points::Points, scalar::Scalar};
+use crate::error::{check_finite, Result};
+
+/// A priority search tree over points in the plane, ignoring z (de Berg et
+/// al. chapter 10.2).
+///
+/// Each node holds the point with the smallest x among the points below it,
+/// which makes a heap on x, and splits the others by y in two halves, which
+/// makes a balanced search tree on y. Building it takes O(n log n) time and
+/// O(n) memory, and it answers the queries unbounded on the left,
+/// (-inf, max_x] x [min_y, max_y], in O(log n + k) time where k is the number
+/// of points reported.
+///
+/// Points are referred by their index in the input
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrioritySearchTree<T = f64> {
+    points: Points<T>,
+
+    // the nodes, the root first
+    nodes: Vec<PstNode<T>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct PstNode<T> {
+    // the point with the smallest x below the node
+    point: usize,
+
+    // the largest y of the left subtree, which is also the smallest of the
+    // right one
+    y_mid: T,
+
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+impl<T: Scalar> PrioritySearchTree<T> {
+    pub fn new(points: Points<T>) -> Self {
+        let mut by_y: Vec<usize> = (0..points.len()).collect();
+        by_y.sort_by(|&a, &b| {
+            points[a]
+                .y
+                .partial_cmp(&points[b].y)
+                .unwrap_or(Ordering::Equal)
+                .then(a.cmp(&b))
+        });
+
+        let mut tree = Self {
+            points,
+            nodes: vec![],
+        };
+        tree.build(by_y);
+        tree
+    }
+
+    /// like `new`, but rejects NaN and infinite coordinates
+    pub fn try_new(points: Points<T>) -> Result<Self> {
+        check_finite(points.iter().map(|p| p.is_finite()))?;
+        Ok(Self::new(points))
+    }
+
+    pub fn points(&self) -> &Points<T> {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// the points with x <= max_x and min_y <= y <= max_y
+    pub fn query(&self, max_x: T, min_y: T, max_y: T) -> Vec<usize> {
+        let mut found = vec![];
+        if !self.nodes.is_empty() {
+            self.report(0, max_x, min_y, max_y, &mut found);
+        }
+        found
+    }
+
+    /// builds the subtree of the points sorted by y, returning its node
+    fn build(&mut self, mut by_y: Vec<usize>) -> Option<usize> {
+        let first = (0..by_y.len()).min_by(|&a, &b| {
+            let (a, b) = (by_y[a], by_y[b]);
+            self.points[a]
+                .x
+                .partial_cmp(&self.points[b].x)
+                .unwrap_or(Ordering::Equal)
+                .then(a.cmp(&b))
+        })?;
+        let point = by_y.remove(first);
+
+        let node = self.nodes.len();
+        self.nodes.push(PstNode {
+            point,
+            y_mid: self.points[point].y,
+            left: None,
+            right: None,
+        });
+        if by_y.is_empty() {
+            return Some(node);
+        }
+
+        let right = by_y.split_off(by_y.len().div_ceil(2));
+        self.nodes[node].y_mid = self.points[by_y[by_y.len() - 1]].y;
+        self.nodes[node].left = self.build(by_y);
+        self.nodes[node].right = self.build(right);
+        Some(node)
+    }
+
+    fn report(&self, node: usize, max_x: T, min_y: T, max_y: T, found: &mut Vec<usize>) {
+        let n = &self.nodes[node];
+        let p = &self.points[n.point];
+        // the points below are not on the left of this one
+        if p.x > max_x {
+            return;
+        }
+        if min_y <= p.y && p.y <= max_y {
+            found.push(n.point);
+        }
+
+        if let (Some(left), true) = (n.left, min_y <= n.y_mid) {
+            self.report(left, max_x, min_y, max_y, found);
+        }
+        if let (Some(right), true) = (n.right, n.y_mid <= max_y) {
+            self.report(right, max_x, min_y, max_y, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrioritySearchTree;
+    use crate::geometry::{point::Point, points::Points, test_utils::sorted};
+
+    #[test]
+    fn priority_search_tree_queries() {
+        let points: Points = vec![
+            [1.0, 1.0],
+            [2.0, 5.0],
+            [4.0, 2.0],
+            [5.0, 5.0],
+            [3.0, 3.0],
+            [2.0, 2.0],
+        ]
+        .into();
+        let tree = PrioritySearchTree::new(points);
+
+        assert_eq!(sorted(tree.query(3.0, 2.0, 5.0)), vec![1, 4, 5]);
+        assert_eq!(sorted(tree.query(9.0, 2.0, 2.0)), vec![2, 5]);
+        assert_eq!(tree.query(0.5, 0.0, 9.0), vec![]);
+        assert_eq!(tree.query(9.0, 6.0, 9.0), vec![]);
+    }
+
+    #[test]
+    fn same_answers_as_brute_force() {
+        let points = Points::random(300, 0.0..100.0);
+        let tree = PrioritySearchTree::new(points.clone());
+
+        for _ in 0..100 {
+            let (a, b) = (Point::random(0.0..100.0), Point::random(0.0..100.0));
+            let (min_y, max_y) = (a.y.min(b.y), a.y.max(b.y));
+            let expected: Vec<usize> = (0..points.len())
+                .filter(|&i| {
+                    let p = points[i];
+                    p.x <= a.x && min_y <= p.y && p.y <= max_y
+                })
+                .collect();
+            assert_eq!(sorted(tree.query(a.x, min_y, max_y)), expected);
+        }
+    }
+
+    #[test]
+    fn integer_points_with_duplicates() {
+        let points: Points<i64> = vec![[1, 1], [1, 1], [0, 1], [1, 0], [1, 1], [2, 1]].into();
+        let tree = PrioritySearchTree::new(points);
+
+        assert_eq!(sorted(tree.query(1, 1, 1)), vec![0, 1, 2, 4]);
+        assert_eq!(sorted(tree.query(0, 0, 2)), vec![2]);
+        assert_eq!(tree.query(5, 0, 5).len(), 6);
+    }
+
+    #[test]
+    fn empty_priority_search_tree() {
+        assert!(PrioritySearchTree::<f64>::new(Points::new()).is_empty());
+    }
+}
@@ -0,0 +1,485 @@
+use super::{
+    point::Point,
+    points::Points,
+    scalar::{Field, Scalar},
+};
+use crate::error::{check_finite, Result};
+
+/// A side of a square
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    /// the two quadrants on this side, in the order of the children
+    fn quadrants(&self) -> [usize; 2] {
+        match self {
+            Direction::North => [NE, NW],
+            Direction::East => [NE, SE],
+            Direction::South => [SW, SE],
+            Direction::West => [NW, SW],
+        }
+    }
+
+    /// the quadrant across the side of this direction
+    fn mirror(&self, quadrant: usize) -> usize {
+        match self {
+            Direction::North | Direction::South => 3 - quadrant,
+            Direction::East | Direction::West => quadrant ^ 1,
+        }
+    }
+}
+
+// the quadrants, in the order of the children
+const NE: usize = 0;
+const NW: usize = 1;
+const SW: usize = 2;
+const SE: usize = 3;
+
+/// A square of a quadtree
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuadNode<T = f64> {
+    /// the south-west corner
+    pub min: Point<T>,
+
+    pub size: T,
+
+    /// 0 for the root
+    pub depth: usize,
+
+    pub parent: Option<usize>,
+
+    /// the north-east, north-west, south-west and south-east quarters
+    pub children: Option<[usize; 4]>,
+
+    // the points inside, for a leaf
+    points: Vec<usize>,
+}
+
+impl<T: Scalar> QuadNode<T> {
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_none()
+    }
+
+    /// the points inside a leaf, by index. Those on a side shared with
+    /// another square belong to the north or east one
+    pub fn points(&self) -> &[usize] {
+        &self.points
+    }
+
+    /// the corners, counterclockwise from the south-west one
+    pub fn corners(&self) -> [Point<T>; 4] {
+        let (min, size) = (self.min, self.size);
+        [
+            min,
+            Point::from2d(min.x + size, min.y),
+            Point::from2d(min.x + size, min.y + size),
+            Point::from2d(min.x, min.y + size),
+        ]
+    }
+
+    /// true if the point is in the closed square
+    pub fn contains(&self, p: &Point<T>) -> bool {
+        self.min.x <= p.x
+            && p.x <= self.min.x + self.size
+            && self.min.y <= p.y
+            && p.y <= self.min.y + self.size
+    }
+}
+
+/// A region quadtree over points in the plane, ignoring z (de Berg et al.
+/// chapter 14).
+///
+/// A square is split in four equal quarters as long as it holds more than one
+/// distinct point, so the depth depends on how close the points are rather
+/// than on how many they are. Points too close to be told apart by halving
+/// the square in the precision of the coordinates stay in the same leaf. Neighbors are found walking up and down the
+/// tree, in O(depth) time, and balancing makes sure that leaves sharing a
+/// side differ in size by a factor 2 at most, adding O(n) nodes.
+///
+/// Points are referred by their index in the input, nodes by their index in
+/// `nodes`, the root first
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quadtree<T = f64> {
+    points: Points<T>,
+    nodes: Vec<QuadNode<T>>,
+}
+
+impl<T: Field> Quadtree<T> {
+    /// the quadtree of the points in their smallest bounding square, or in the
+    /// unit square at the origin if there are none
+    pub fn new(points: Points<T>) -> Self {
+        let (mut min, mut size) = (Point::from2d(T::zero(), T::zero()), T::one());
+        if let Some(first) = points.iter().next() {
+            let (mut lo, mut hi) = (
+                Point::from2d(first.x, first.y),
+                Point::from2d(first.x, first.y),
+            );
+            for p in points.iter() {
+                lo = Point::from2d(Scalar::min(lo.x, p.x), Scalar::min(lo.y, p.y));
+                hi = Point::from2d(Scalar::max(hi.x, p.x), Scalar::max(hi.y, p.y));
+            }
+            min = lo;
+            size = Scalar::max(hi.x - lo.x, hi.y - lo.y);
+            if size == T::zero() {
+                size = T::one();
+            }
+        }
+
+        let mut tree = Self::from_square(min, size);
+        tree.nodes[0].points = (0..points.len()).collect();
+        tree.points = points;
+        let mut pending = vec![0];
+        while let Some(node) = pending.pop() {
+            let points = &tree.nodes[node].points;
+            let first = points.first().map(|&p| tree.points[p]);
+            let distinct = points.iter().any(|&p| {
+                first
+                    .is_some_and(|first| (first.x, first.y) != (tree.points[p].x, tree.points[p].y))
+            });
+            if distinct && tree.is_divisible(node) {
+                pending.extend(tree.split(node));
+            }
+        }
+        tree
+    }
+
+    /// true if halving the square gives sides apart from its own ones, which
+    /// rounding prevents once it is a few ulps wide
+    fn is_divisible(&self, node: usize) -> bool {
+        let n = &self.nodes[node];
+        let half = n.size / (T::one() + T::one());
+        [n.min.x, n.min.y]
+            .iter()
+            .all(|&lo| lo < lo + half && lo + half < lo + n.size)
+    }
+
+    /// like `new`, but rejects NaN and infinite coordinates
+    pub fn try_new(points: Points<T>) -> Result<Self> {
+        check_finite(points.iter().map(|p| p.is_finite()))?;
+        Ok(Self::new(points))
+    }
+
+    /// a quadtree without points made of a single square, to be split at will
+    pub fn from_square(min: Point<T>, size: T) -> Self {
+        Self {
+            points: Points::new(),
+            nodes: vec![QuadNode {
+                min: Point::from2d(min.x, min.y),
+                size,
+                depth: 0,
+                parent: None,
+                children: None,
+                points: vec![],
+            }],
+        }
+    }
+
+    pub fn points(&self) -> &Points<T> {
+        &self.points
+    }
+
+    pub fn nodes(&self) -> &[QuadNode<T>] {
+        &self.nodes
+    }
+
+    pub fn node(&self, node: usize) -> &QuadNode<T> {
+        &self.nodes[node]
+    }
+
+    /// the nodes without children
+    pub fn leaves(&self) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|&n| self.nodes[n].is_leaf())
+            .collect()
+    }
+
+    /// the leaf containing the point, None if it is outside the root square.
+    /// A point on a side shared by two squares is in the north or east one
+    pub fn locate(&self, p: &Point<T>) -> Option<usize> {
+        if !self.nodes[0].contains(p) {
+            return None;
+        }
+
+        let mut node = 0;
+        while let Some(children) = self.nodes[node].children {
+            node = children[self.quadrant(node, p)];
+        }
+        Some(node)
+    }
+
+    /// Splits a leaf in four quarters, handing its points down, and returns
+    /// them. A node already split is left as it is
+    pub fn split(&mut self, node: usize) -> [usize; 4] {
+        if let Some(children) = self.nodes[node].children {
+            return children;
+        }
+
+        let n = &self.nodes[node];
+        let half = n.size / (T::one() + T::one());
+        let (x, y) = (n.min.x, n.min.y);
+        let mins = [
+            Point::from2d(x + half, y + half),
+            Point::from2d(x, y + half),
+            Point::from2d(x, y),
+            Point::from2d(x + half, y),
+        ];
+        let first = self.nodes.len();
+        let children = [first, first + 1, first + 2, first + 3];
+        let points = std::mem::take(&mut self.nodes[node].points);
+        let depth = self.nodes[node].depth + 1;
+        for min in mins {
+            self.nodes.push(QuadNode {
+                min,
+                size: half,
+                depth,
+                parent: Some(node),
+                children: None,
+                points: vec![],
+            });
+        }
+        for p in points {
+            let quadrant = self.quadrant(node, &self.points[p]);
+            self.nodes[children[quadrant]].points.push(p);
+        }
+        self.nodes[node].children = Some(children);
+        children
+    }
+
+    /// The neighbor across a side: the node of the same depth there, or the
+    /// leaf covering it if that one is larger. None on the boundary of the
+    /// root square
+    pub fn neighbor(&self, node: usize, direction: Direction) -> Option<usize> {
+        let parent = self.nodes[node].parent?;
+        let siblings = self.nodes[parent].children?;
+        let quadrant = siblings.iter().position(|&c| c == node)?;
+        if !direction.quadrants().contains(&quadrant) {
+            return Some(siblings[direction.mirror(quadrant)]);
+        }
+
+        let across = self.neighbor(parent, direction)?;
+        match self.nodes[across].children {
+            Some(children) => Some(children[direction.mirror(quadrant)]),
+            None => Some(across),
+        }
+    }
+
+    /// the leaves sharing a part of a side of the node
+    pub fn leaf_neighbors(&self, node: usize, direction: Direction) -> Vec<usize> {
+        let mut leaves = vec![];
+        let mut pending: Vec<usize> = self.neighbor(node, direction).into_iter().collect();
+        while let Some(n) = pending.pop() {
+            match self.nodes[n].children {
+                Some(children) => {
+                    pending.extend(direction.opposite().quadrants().map(|q| children[q]))
+                }
+                None => leaves.push(n),
+            }
+        }
+        leaves
+    }
+
+    /// true if the node is a leaf with a neighbor across a side holding
+    /// leaves of less than half its size
+    pub fn is_unbalanced(&self, node: usize) -> bool {
+        self.nodes[node].is_leaf()
+            && Direction::ALL.iter().any(|&direction| {
+                let across = self.neighbor(node, direction);
+                across
+                    .and_then(|n| self.nodes[n].children)
+                    .is_some_and(|children| {
+                        direction
+                            .opposite()
+                            .quadrants()
+                            .iter()
+                            .any(|&q| !self.nodes[children[q]].is_leaf())
+                    })
+            })
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        (0..self.nodes.len()).all(|n| !self.is_unbalanced(n))
+    }
+
+    /// Splits the leaves until the ones sharing a side differ in size by a
+    /// factor 2 at most
+    pub fn balance(&mut self) {
+        let mut pending = self.leaves();
+        while let Some(node) = pending.pop() {
+            pending.extend(self.balance_leaf(node));
+        }
+    }
+
+    /// Splits the leaf if it is unbalanced, returning the nodes to check
+    /// again: its children and the larger leaves next to it
+    pub fn balance_leaf(&mut self, node: usize) -> Vec<usize> {
+        if !self.is_unbalanced(node) {
+            return vec![];
+        }
+
+        let mut pending = self.split(node).to_vec();
+        for direction in Direction::ALL {
+            pending.extend(
+                self.neighbor(node, direction)
+                    .filter(|&n| self.nodes[n].is_leaf()),
+            );
+        }
+        pending
+    }
+
+    /// the quadrant of the node where the point falls
+    fn quadrant(&self, node: usize, p: &Point<T>) -> usize {
+        let n = &self.nodes[node];
+        let half = n.size / (T::one() + T::one());
+        match (p.y >= n.min.y + half, p.x >= n.min.x + half) {
+            (true, true) => NE,
+            (true, false) => NW,
+            (false, false) => SW,
+            (false, true) => SE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Quadtree};
+    use crate::geometry::{point::Point, points::Points};
+
+    /// true if the leaves sharing a part of a side differ in size by a factor
+    /// 2 at most, by brute force
+    fn balanced(tree: &Quadtree) -> bool {
+        let leaves = tree.leaves();
+        leaves.iter().all(|&a| {
+            leaves.iter().all(|&b| {
+                let (a, b) = (tree.node(a), tree.node(b));
+                let overlap = |a0: f64, a1: f64, b0: f64, b1: f64| a0.max(b0) < a1.min(b1);
+                let (ax, ay, bx, by) = (a.min.x, a.min.y, b.min.x, b.min.y);
+                let vertical = (ax + a.size == bx || bx + b.size == ax)
+                    && overlap(ay, ay + a.size, by, by + b.size);
+                let horizontal = (ay + a.size == by || by + b.size == ay)
+                    && overlap(ax, ax + a.size, bx, bx + b.size);
+                !(vertical || horizontal) || a.size <= 2.0 * b.size
+            })
+        })
+    }
+
+    #[test]
+    fn quadtree_of_points() {
+        let points: Points =
+            vec![[0.0, 0.0], [4.0, 4.0], [1.0, 3.0], [3.5, 3.5], [1.0, 3.0]].into();
+        let tree = Quadtree::new(points.clone());
+
+        assert_eq!(tree.node(0).size, 4.0);
+        for leaf in tree.leaves() {
+            let held = tree.node(leaf).points();
+            assert!(held.iter().all(|&p| points[p] == points[held[0]]));
+        }
+        for (i, p) in points.iter().enumerate() {
+            let leaf = tree.locate(p).unwrap();
+            assert!(tree.node(leaf).points().contains(&i));
+        }
+        // the repeated point stays in one leaf
+        assert_eq!(
+            tree.node(tree.locate(&points[2]).unwrap()).points(),
+            &[2, 4]
+        );
+        assert_eq!(tree.locate(&Point::from2d(5.0, 0.0)), None);
+    }
+
+    #[test]
+    fn points_one_ulp_apart() {
+        let next = f64::from_bits(0.7f64.to_bits() + 1);
+        let points: Points = vec![[0.1, 0.3], [0.7, 0.7], [next, 0.7]].into();
+        let tree = Quadtree::new(points.clone());
+
+        // the last two can not be separated, they share a leaf
+        let leaf = tree.locate(&points[1]).unwrap();
+        assert_eq!(tree.node(leaf).points(), &[1, 2]);
+        assert_ne!(tree.locate(&points[0]), Some(leaf));
+    }
+
+    #[test]
+    fn quadtree_neighbors() {
+        let mut tree = Quadtree::from_square(Point::from2d(0.0, 0.0), 4.0);
+        let [ne, nw, sw, se] = tree.split(0);
+        let [_, ne_nw, ne_sw, _] = tree.split(ne);
+
+        assert_eq!(tree.neighbor(nw, Direction::East), Some(ne));
+        assert_eq!(tree.neighbor(nw, Direction::South), Some(sw));
+        assert_eq!(tree.neighbor(nw, Direction::North), None);
+        assert_eq!(tree.neighbor(sw, Direction::West), None);
+        assert_eq!(tree.neighbor(se, Direction::North), Some(ne));
+        // a larger leaf across the side
+        assert_eq!(tree.neighbor(ne_sw, Direction::West), Some(nw));
+        assert_eq!(tree.neighbor(ne_sw, Direction::South), Some(se));
+        assert_eq!(tree.neighbor(ne_nw, Direction::South), Some(ne_sw));
+
+        let mut east = tree.leaf_neighbors(nw, Direction::East);
+        east.sort();
+        assert_eq!(east, vec![ne_nw, ne_sw]);
+        assert_eq!(tree.leaf_neighbors(ne_sw, Direction::West), vec![nw]);
+        assert!(tree.leaf_neighbors(se, Direction::East).is_empty());
+        assert_eq!(tree.node(ne_sw).depth, 2);
+    }
+
+    #[test]
+    fn quadtree_balancing() {
+        let mut tree = Quadtree::from_square(Point::from2d(0.0, 0.0), 16.0);
+        // unit squares at the center, next to the south-east quarter of size 8
+        let [_, _, sw, se] = tree.split(0);
+        let [sw_ne, ..] = tree.split(sw);
+        let [sw_ne_ne, ..] = tree.split(sw_ne);
+        tree.split(sw_ne_ne);
+        assert!(tree.is_unbalanced(se));
+        assert!(!tree.is_balanced());
+        assert!(!balanced(&tree));
+
+        tree.balance();
+        assert!(tree.is_balanced());
+        assert!(balanced(&tree));
+    }
+
+    #[test]
+    fn random_points_balanced() {
+        // the corners make the root the unit square, so the squares are exact
+        let mut points = Points::random(100, 0.0..1.0);
+        points.push([0.0, 0.0]);
+        points.push([1.0, 1.0]);
+        let mut tree = Quadtree::new(points.clone());
+        let leaves = tree.leaves().len();
+
+        tree.balance();
+        assert!(balanced(&tree));
+        assert!(tree.leaves().len() >= leaves);
+        for (i, p) in points.iter().enumerate() {
+            let leaf = tree.locate(p).unwrap();
+            assert!(tree.node(leaf).points().contains(&i));
+        }
+    }
+
+    #[test]
+    fn empty_quadtree() {
+        let empty: Quadtree = Quadtree::new(Points::new());
+        assert_eq!(empty.leaves(), vec![0]);
+    }
+}
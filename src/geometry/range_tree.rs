@@ -0,0 +1,281 @@
+use std::cmp::Ordering;
+
+use super::{point::Point, point_index::PointIndex, points::Points, scalar::Scalar};
+use crate::error::{check_finite, Result};
+
+/// A layered range tree over points in the plane, ignoring z (de Berg et al.
+/// chapter 5.6).
+///
+/// A balanced tree splits the points by x, and each node keeps its points
+/// sorted by y. With fractional cascading each entry of a node also knows
+/// where the entries not below it start in the lists of the children, so a
+/// query searches y only once, at the root. Building it takes O(n log n) time
+/// and memory, a query O(log n + k) where k is the number of points reported,
+/// and counting O(log n).
+///
+/// Points are referred by their index in the input
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeTree<T = f64> {
+    points: Points<T>,
+
+    // the nodes, the root first
+    nodes: Vec<RangeNode<T>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct RangeNode<T> {
+    // the smallest and the largest x of the points below
+    min_x: T,
+    max_x: T,
+
+    // the points below sorted by y, then by index
+    by_y: Vec<usize>,
+
+    // the left and the right children, None for a leaf
+    children: Option<(usize, usize)>,
+
+    // for each position in by_y and the end of it, the position of the first
+    // entry not before it in the list of each child
+    left: Vec<usize>,
+    right: Vec<usize>,
+}
+
+impl<T: Scalar> RangeTree<T> {
+    pub fn new(points: Points<T>) -> Self {
+        let mut by_x: Vec<usize> = (0..points.len()).collect();
+        by_x.sort_by(|&a, &b| {
+            points[a]
+                .x
+                .partial_cmp(&points[b].x)
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let mut tree = Self {
+            points,
+            nodes: vec![],
+        };
+        if !by_x.is_empty() {
+            tree.build(&by_x);
+        }
+        tree
+    }
+
+    /// like `new`, but rejects NaN and infinite coordinates
+    pub fn try_new(points: Points<T>) -> Result<Self> {
+        check_finite(points.iter().map(|p| p.is_finite()))?;
+        Ok(Self::new(points))
+    }
+
+    pub fn points(&self) -> &Points<T> {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// builds the subtree of the points sorted by x, returning its node
+    fn build(&mut self, by_x: &[usize]) -> usize {
+        let node = self.nodes.len();
+        self.nodes.push(RangeNode {
+            min_x: self.points[by_x[0]].x,
+            max_x: self.points[by_x[by_x.len() - 1]].x,
+            by_y: vec![],
+            children: None,
+            left: vec![],
+            right: vec![],
+        });
+
+        if by_x.len() == 1 {
+            self.nodes[node].by_y = by_x.to_vec();
+            self.nodes[node].left = vec![0, 0];
+            self.nodes[node].right = vec![0, 0];
+            return node;
+        }
+
+        let mid = by_x.len() / 2;
+        let left = self.build(&by_x[..mid]);
+        let right = self.build(&by_x[mid..]);
+
+        // merges the children lists, noting how far each one got
+        let (a, b) = (&self.nodes[left].by_y, &self.nodes[right].by_y);
+        let mut merged = RangeNode {
+            by_y: Vec::with_capacity(a.len() + b.len()),
+            left: Vec::with_capacity(a.len() + b.len() + 1),
+            right: Vec::with_capacity(a.len() + b.len() + 1),
+            ..self.nodes[node].clone()
+        };
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            merged.left.push(i);
+            merged.right.push(j);
+            let take_left =
+                j == b.len() || (i < a.len() && self.y_cmp(a[i], b[j]) == Ordering::Less);
+            match take_left {
+                true => {
+                    merged.by_y.push(a[i]);
+                    i += 1;
+                }
+                false => {
+                    merged.by_y.push(b[j]);
+                    j += 1;
+                }
+            }
+        }
+        merged.left.push(i);
+        merged.right.push(j);
+        merged.children = Some((left, right));
+
+        self.nodes[node] = merged;
+        node
+    }
+
+    fn y_cmp(&self, a: usize, b: usize) -> Ordering {
+        self.points[a]
+            .y
+            .partial_cmp(&self.points[b].y)
+            .unwrap_or(Ordering::Equal)
+            .then(a.cmp(&b))
+    }
+
+    /// Calls report with the node and the positions in its list of the points
+    /// with y in the query, for the nodes of the points with x in it. The
+    /// positions are carried to the children by the cascading pointers
+    fn query(
+        &self,
+        node: usize,
+        (start, end): (usize, usize),
+        min: &Point<T>,
+        max: &Point<T>,
+        report: &mut impl FnMut(&RangeNode<T>, usize, usize),
+    ) {
+        let n = &self.nodes[node];
+        if start >= end || n.max_x < min.x || max.x < n.min_x {
+            return;
+        }
+        if min.x <= n.min_x && n.max_x <= max.x {
+            report(n, start, end);
+            return;
+        }
+
+        if let Some((left, right)) = n.children {
+            self.query(left, (n.left[start], n.left[end]), min, max, report);
+            self.query(right, (n.right[start], n.right[end]), min, max, report);
+        }
+    }
+
+    /// the positions in the root list of the points with y in the query
+    fn root_span(&self, min: &Point<T>, max: &Point<T>) -> (usize, usize) {
+        let by_y = &self.nodes[0].by_y;
+        let start = by_y.partition_point(|&i| self.points[i].y < min.y);
+        let end = by_y.partition_point(|&i| self.points[i].y <= max.y);
+        (start, end.max(start))
+    }
+}
+
+impl<T: Scalar> PointIndex<T> for RangeTree<T> {
+    fn range(&self, min: &Point<T>, max: &Point<T>) -> Vec<usize> {
+        let mut found = vec![];
+        if !self.nodes.is_empty() {
+            let span = self.root_span(min, max);
+            self.query(0, span, min, max, &mut |node, start, end| {
+                found.extend_from_slice(&node.by_y[start..end])
+            });
+        }
+        found
+    }
+
+    fn count(&self, min: &Point<T>, max: &Point<T>) -> usize {
+        let mut count = 0;
+        if !self.nodes.is_empty() {
+            let span = self.root_span(min, max);
+            self.query(0, span, min, max, &mut |_, start, end| count += end - start);
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeTree;
+    use crate::geometry::{
+        kd_tree::KdTree, point::Point, point_index::PointIndex, points::Points, test_utils::sorted,
+    };
+
+    #[test]
+    fn range_tree_queries() {
+        let points: Points = vec![
+            [1.0, 1.0],
+            [2.0, 5.0],
+            [4.0, 2.0],
+            [5.0, 5.0],
+            [3.0, 3.0],
+            [2.0, 2.0],
+        ]
+        .into();
+        let tree = RangeTree::new(points);
+
+        let (min, max) = (Point::from2d(2.0, 2.0), Point::from2d(4.0, 5.0));
+        assert_eq!(sorted(tree.range(&min, &max)), vec![1, 2, 4, 5]);
+        assert_eq!(tree.count(&min, &max), 4);
+        assert_eq!(
+            tree.count(&Point::from2d(0.0, 0.0), &Point::from2d(9.0, 9.0)),
+            6
+        );
+        assert!(tree
+            .range(&Point::from2d(0.0, 6.0), &Point::from2d(9.0, 9.0))
+            .is_empty());
+        // an empty box
+        assert_eq!(tree.count(&max, &min), 0);
+    }
+
+    #[test]
+    fn same_answers_as_a_kd_tree() {
+        let points = Points::random(400, 0.0..100.0);
+        let indexes: [Box<dyn PointIndex<f64>>; 2] = [
+            Box::new(KdTree::new(points.clone())),
+            Box::new(RangeTree::new(points.clone())),
+        ];
+
+        for _ in 0..100 {
+            let (a, b) = (Point::random(0.0..100.0), Point::random(0.0..100.0));
+            let min = Point::from2d(a.x.min(b.x), a.y.min(b.y));
+            let max = Point::from2d(a.x.max(b.x), a.y.max(b.y));
+            let expected: Vec<usize> = (0..points.len())
+                .filter(|&i| {
+                    let p = points[i];
+                    min.x <= p.x && p.x <= max.x && min.y <= p.y && p.y <= max.y
+                })
+                .collect();
+
+            for index in indexes.iter() {
+                assert_eq!(sorted(index.range(&min, &max)), expected);
+                assert_eq!(index.count(&min, &max), expected.len());
+            }
+        }
+    }
+
+    #[test]
+    fn integer_points_with_duplicates() {
+        let points: Points<i64> = vec![[1, 1], [1, 1], [0, 1], [1, 0], [1, 1], [2, 1]].into();
+        let tree = RangeTree::new(points);
+
+        let p = Point::from2d(1, 1);
+        assert_eq!(sorted(tree.range(&p, &p)), vec![0, 1, 4]);
+        assert_eq!(tree.count(&Point::from2d(0, 1), &Point::from2d(2, 1)), 5);
+        assert_eq!(tree.count(&Point::from2d(1, 0), &Point::from2d(1, 2)), 4);
+    }
+
+    #[test]
+    fn empty_range_tree() {
+        let tree: RangeTree = RangeTree::new(Points::new());
+        assert!(tree.is_empty());
+        let (min, max) = (Point::from2d(0.0, 0.0), Point::from2d(1.0, 1.0));
+        assert!(tree.range(&min, &max).is_empty());
+        assert_eq!(tree.count(&min, &max), 0);
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use crate::error::{GalmetryError, Result};
+
+/// An exact fraction num / den, always kept reduced with a positive denominator.
+///
+/// The arithmetic panics if a result does not fit in i128, which for segment
+/// intersections means input coordinates larger than about 2^30.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "Rational with zero denominator");
+
+        let sign = den.signum();
+        let gcd = gcd(num, den);
+        Self {
+            num: sign * num / gcd,
+            den: sign * den / gcd,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.den
+    }
+
+    /// the value of v, exact when `try_from_f64` succeeds. Otherwise, unless
+    /// it is too large, its denominator is above 2^126 and it is rounded to the
+    /// nearest multiple of 2^-126
+    ///
+    /// Panics if v is not finite or if |v| ≥ 2^127
+    pub fn from_f64(v: f64) -> Self {
+        assert!(v.is_finite(), "Rational from a non finite value");
+        if let Ok(exact) = Self::try_from_f64(v) {
+            return exact;
+        }
+
+        let (num, exp) = decompose(v);
+        if exp >= 0 {
+            checked(None);
+        }
+        // ties away from zero
+        let shift = (-126 - exp).min(127) as u32;
+        let half = 1i128 << (shift - 1);
+        let rounded = (num.abs() + half).checked_shr(shift).unwrap_or(0);
+        Self::new(num.signum() * rounded, 1 << 126)
+    }
+
+    /// the exact value of v, as every finite f64 is a fraction with a power of
+    /// two as denominator. Fails if v is not finite, if |v| ≥ 2^127 or if the
+    /// denominator is above 2^126, as for the values closer to zero than 2^-126
+    pub fn try_from_f64(v: f64) -> Result<Self> {
+        if !v.is_finite() {
+            return Err(GalmetryError::NumericFailure("the value is not finite"));
+        }
+
+        let (num, exp) = decompose(v);
+        let exact = match exp {
+            0.. => (num.unsigned_abs().leading_zeros() > exp as u32).then(|| num << exp),
+            -126.. => Some(num),
+            _ => None,
+        };
+        match exact {
+            Some(num) => Ok(Self::new(num, 1 << (-exp).max(0))),
+            None => Err(GalmetryError::NumericFailure(
+                "the value does not fit in a Rational",
+            )),
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+/// the finite v as num * 2^exp, with num odd or zero
+fn decompose(v: f64) -> (i128, i32) {
+    let bits = v.to_bits();
+    let exp = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = (bits & ((1 << 52) - 1)) as i128;
+    let (mut num, mut exp) = match exp {
+        0 => (fraction, -1074),
+        _ => (fraction | 1 << 52, exp - 1075),
+    };
+    if num == 0 {
+        return (0, 0);
+    }
+
+    let zeros = num.trailing_zeros();
+    num >>= zeros;
+    exp += zeros as i32;
+    match v.is_sign_negative() {
+        true => (-num, exp),
+        false => (num, exp),
+    }
+}
+
+fn checked(value: Option<i128>) -> i128 {
+    value.expect("Rational overflow")
+}
+
+impl From<i64> for Rational {
+    fn from(v: i64) -> Self {
+        Self {
+            num: v as i128,
+            den: 1,
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let gcd = gcd(self.den, rhs.den);
+        let den = checked((self.den / gcd).checked_mul(rhs.den));
+        let num = checked(
+            checked(self.num.checked_mul(rhs.den / gcd))
+                .checked_add(checked(rhs.num.checked_mul(self.den / gcd))),
+        );
+        Self::new(num, den)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        // cross reduce first to keep the intermediate values small
+        let g1 = gcd(self.num, rhs.den);
+        let g2 = gcd(rhs.num, self.den);
+        let num = checked((self.num / g1).checked_mul(rhs.num / g2));
+        let den = checked((self.den / g2).checked_mul(rhs.den / g1));
+        Self::new(num, den)
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(rhs.num != 0, "Rational division by zero");
+        self * Self::new(rhs.den, rhs.num)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Ord for Rational {
+    /// by sign, then by the cross products. When they overflow the integer
+    /// parts are compared, and then the reciprocals of the fractional parts,
+    /// so that ordering never panics
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.num.signum().cmp(&other.num.signum()) {
+            Ordering::Equal => (),
+            ordering => return ordering,
+        }
+
+        let (mut a, mut b) = ((self.num, self.den), (other.num, other.den));
+        let mut reversed = false;
+        let ordering = loop {
+            if let (Some(x), Some(y)) = (a.0.checked_mul(b.1), b.0.checked_mul(a.1)) {
+                break x.cmp(&y);
+            }
+
+            let (qa, qb) = (a.0.div_euclid(a.1), b.0.div_euclid(b.1));
+            if qa != qb {
+                break qa.cmp(&qb);
+            }
+            let (ra, rb) = (a.0.rem_euclid(a.1), b.0.rem_euclid(b.1));
+            if ra == 0 || rb == 0 {
+                break ra.cmp(&rb);
+            }
+            // ra / a.den < rb / b.den when a.den / ra > b.den / rb
+            (a, b) = ((a.1, ra), (b.1, rb));
+            reversed = !reversed;
+        };
+        match reversed {
+            true => ordering.reverse(),
+            false => ordering,
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    /// with a precision the value is written as a decimal number
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}", precision, self.to_f64()),
+            None if self.den == 1 => write!(f, "{}", self.num),
+            None => write!(f, "{}/{}", self.num, self.den),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rational;
+    use crate::error::GalmetryError;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn rational_is_reduced() {
+        let r = Rational::new(4, -6);
+
+        assert_eq!(r.numerator(), -2);
+        assert_eq!(r.denominator(), 3);
+        assert_eq!(r, Rational::new(-2, 3));
+    }
+
+    #[test]
+    fn rational_arithmetic() {
+        let a = Rational::new(1, 3);
+        let b = Rational::new(1, 6);
+
+        assert_eq!(a + b, Rational::new(1, 2));
+        assert_eq!(a - b, Rational::new(1, 6));
+        assert_eq!(a * b, Rational::new(1, 18));
+        assert_eq!(a / b, Rational::from(2));
+        assert_eq!(-a, Rational::new(-1, 3));
+    }
+
+    #[test]
+    fn rational_order() {
+        assert!(Rational::new(1, 3) < Rational::new(1, 2));
+        assert!(Rational::new(-1, 2) < Rational::new(-1, 3));
+    }
+
+    #[test]
+    fn rational_from_f64_is_exact() {
+        assert_eq!(Rational::from_f64(0.75), Rational::new(3, 4));
+        assert_eq!(Rational::from_f64(-3.0), Rational::from(-3));
+        assert_eq!(Rational::from_f64(0.1).to_f64(), 0.1);
+    }
+
+    #[test]
+    fn rational_from_f64_out_of_range() {
+        let large = 2f64.powi(126) * 1.5;
+        assert_eq!(Rational::try_from_f64(large).unwrap().to_f64(), large);
+        assert_eq!(Rational::from_f64(-large).to_f64(), -large);
+        assert_eq!(
+            Rational::try_from_f64(2f64.powi(127)),
+            Err(GalmetryError::NumericFailure(
+                "the value does not fit in a Rational"
+            ))
+        );
+        assert!(Rational::try_from_f64(f64::NAN).is_err());
+
+        let small = 2f64.powi(-126);
+        assert_eq!(
+            Rational::try_from_f64(small),
+            Ok(Rational::new(1, 1 << 126))
+        );
+        assert!(Rational::try_from_f64(small * 0.75).is_err());
+        // rounded to a multiple of 2^-126
+        assert_eq!(
+            Rational::from_f64(-small * 0.75),
+            Rational::new(-1, 1 << 126)
+        );
+        assert_eq!(Rational::from_f64(small * 0.25), Rational::from(0));
+        assert_eq!(Rational::from_f64(f64::MIN_POSITIVE), Rational::from(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Rational overflow")]
+    fn rational_from_f64_too_large() {
+        Rational::from_f64(1e40);
+    }
+
+    #[test]
+    fn rational_order_never_overflows() {
+        // the cross products of these overflow
+        let large = Rational::new(i128::MAX, 2);
+        let smaller = Rational::new(i128::MAX - 2, 2);
+        assert!(smaller < large);
+        assert!(-large < -smaller);
+        assert!(-large < Rational::new(1, i128::MAX));
+        assert_eq!(large.cmp(&large), Ordering::Equal);
+
+        // same integer parts: 1 + 1 / (MAX - 1) and 1 + 1 / (MAX - 2)
+        let max = i128::MAX;
+        assert!(Rational::new(max, max - 1) < Rational::new(max - 1, max - 2));
+        assert!(Rational::new(max - 2, max - 1) < Rational::new(max - 1, max));
+        assert!(-Rational::new(max, max - 1) > -Rational::new(max - 1, max - 2));
+    }
+
+    #[test]
+    fn display_rational() {
+        assert_eq!(format!("{}", Rational::new(1, 3)), "1/3");
+        assert_eq!(format!("{}", Rational::from(2)), "2");
+        assert_eq!(format!("{:.1}", Rational::new(1, 4)), "0.2");
+    }
+}
@@ -0,0 +1,335 @@
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display},
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use super::{
+    point::Point,
+    predicates::{self, Orientation},
+    rational::Rational,
+};
+
+/// A numeric type usable as a coordinate.
+///
+/// Every scalar provides an orientation test that never gives inconsistent
+/// answers: exact for integers and rationals, adaptive for floating points.
+pub trait Scalar:
+    Copy
+    + Debug
+    + Display
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    /// the closest value to v, integers are rounded
+    fn from_f64(v: f64) -> Self;
+
+    fn to_f64(self) -> f64;
+
+    /// the relative rounding error of the arithmetic, zero for exact types
+    fn epsilon() -> Self;
+
+    /// orientation of c with respect to the line going from a to b
+    fn orientation(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>) -> Orientation;
+
+    /// Greater if d lies inside the circle passing through a, b and c given in
+    /// counterclockwise order, Less if it lies outside and Equal if cocircular
+    fn incircle(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>, d: &Point<Self>) -> Ordering;
+
+    /// false for NaN and infinite values, always true for exact types
+    fn is_finite(self) -> bool {
+        true
+    }
+
+    fn abs(self) -> Self {
+        match self < Self::zero() {
+            true => -self,
+            false => self,
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        match other < self {
+            true => other,
+            false => self,
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        match other > self {
+            true => other,
+            false => self,
+        }
+    }
+}
+
+/// A scalar closed under division, needed to compute intersection points
+pub trait Field: Scalar + Div<Output = Self> {}
+
+impl Scalar for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn from_f64(v: f64) -> Self {
+        v
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn epsilon() -> Self {
+        f64::EPSILON
+    }
+
+    fn orientation(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>) -> Orientation {
+        Orientation::from_det(predicates::orient2d(a, b, c))
+    }
+
+    fn incircle(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>, d: &Point<Self>) -> Ordering {
+        let det = predicates::incircle(a, b, c, d);
+        det.partial_cmp(&0.0).unwrap_or(Ordering::Equal)
+    }
+
+    fn is_finite(self) -> bool {
+        f64::is_finite(self)
+    }
+}
+
+impl Field for f64 {}
+
+impl Scalar for f32 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn from_f64(v: f64) -> Self {
+        v as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn epsilon() -> Self {
+        f32::EPSILON
+    }
+
+    /// every f32 is exactly representable as f64, so the f64 predicate is exact
+    fn orientation(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>) -> Orientation {
+        let widen = |p: &Point<f32>| Point::new(p.x as f64, p.y as f64, p.z as f64);
+        f64::orientation(&widen(a), &widen(b), &widen(c))
+    }
+
+    fn incircle(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>, d: &Point<Self>) -> Ordering {
+        let widen = |p: &Point<f32>| p.cast::<f64>();
+        f64::incircle(&widen(a), &widen(b), &widen(c), &widen(d))
+    }
+
+    fn is_finite(self) -> bool {
+        f32::is_finite(self)
+    }
+}
+
+impl Field for f32 {}
+
+impl Scalar for i32 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn from_f64(v: f64) -> Self {
+        v.round() as i32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn epsilon() -> Self {
+        0
+    }
+
+    fn orientation(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>) -> Orientation {
+        let det = (a.x as i128 - c.x as i128) * (b.y as i128 - c.y as i128)
+            - (a.y as i128 - c.y as i128) * (b.x as i128 - c.x as i128);
+        Orientation::from_ordering(det.cmp(&0))
+    }
+
+    /// every i32 is exactly representable as f64, so the f64 predicate is exact
+    fn incircle(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>, d: &Point<Self>) -> Ordering {
+        let widen = |p: &Point<i32>| p.cast::<f64>();
+        f64::incircle(&widen(a), &widen(b), &widen(c), &widen(d))
+    }
+}
+
+/// The orientation test is exact for coordinates in the range ±2^62 and the
+/// incircle test for coordinates in the range ±2^53
+impl Scalar for i64 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn from_f64(v: f64) -> Self {
+        v.round() as i64
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn epsilon() -> Self {
+        0
+    }
+
+    fn orientation(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>) -> Orientation {
+        let det = (a.x as i128 - c.x as i128) * (b.y as i128 - c.y as i128)
+            - (a.y as i128 - c.y as i128) * (b.x as i128 - c.x as i128);
+        Orientation::from_ordering(det.cmp(&0))
+    }
+
+    fn incircle(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>, d: &Point<Self>) -> Ordering {
+        let widen = |p: &Point<i64>| p.cast::<f64>();
+        f64::incircle(&widen(a), &widen(b), &widen(c), &widen(d))
+    }
+}
+
+impl Scalar for Rational {
+    fn zero() -> Self {
+        Rational::from(0)
+    }
+
+    fn one() -> Self {
+        Rational::from(1)
+    }
+
+    fn from_f64(v: f64) -> Self {
+        Rational::from_f64(v)
+    }
+
+    fn to_f64(self) -> f64 {
+        Rational::to_f64(&self)
+    }
+
+    fn epsilon() -> Self {
+        Rational::from(0)
+    }
+
+    fn orientation(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>) -> Orientation {
+        let det = (a.x - c.x) * (b.y - c.y) - (a.y - c.y) * (b.x - c.x);
+        Orientation::from_ordering(det.cmp(&Rational::zero()))
+    }
+
+    fn incircle(a: &Point<Self>, b: &Point<Self>, c: &Point<Self>, d: &Point<Self>) -> Ordering {
+        let lift = |p: &Point<Rational>| {
+            let (x, y) = (p.x - d.x, p.y - d.y);
+            (x, y, x * x + y * y)
+        };
+        let (ax, ay, al) = lift(a);
+        let (bx, by, bl) = lift(b);
+        let (cx, cy, cl) = lift(c);
+
+        let det = al * (bx * cy - cx * by) + bl * (cx * ay - ax * cy) + cl * (ax * by - bx * ay);
+        det.cmp(&Rational::zero())
+    }
+}
+
+impl Field for Rational {}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::Scalar;
+    use crate::geometry::{point::Point, predicates::Orientation, rational::Rational};
+
+    #[test]
+    fn orientation_i32_extremes() {
+        let a = Point::from2d(i32::MIN, i32::MIN);
+        let b = Point::from2d(i32::MAX, i32::MAX);
+        let c = Point::from2d(i32::MAX - 1, i32::MAX);
+
+        assert_eq!(i32::orientation(&a, &b, &c), Orientation::Left);
+    }
+
+    #[test]
+    fn orientation_i64_large() {
+        let a = Point::from2d(0i64, 0);
+        let b = Point::from2d(1i64 << 61, (1i64 << 61) + 1);
+        let c = Point::from2d(1i64 << 62, (1i64 << 62) + 2);
+
+        assert_eq!(i64::orientation(&a, &b, &c), Orientation::Collinear);
+    }
+
+    #[test]
+    fn orientation_f32() {
+        let a = Point::from2d(0.5f32, 0.5);
+        let b = Point::from2d(12.0f32, 12.0);
+        let c = Point::from2d(24.0f32, 24.0 + f32::EPSILON * 16.0);
+
+        assert_eq!(f32::orientation(&a, &b, &c), Orientation::Left);
+    }
+
+    #[test]
+    fn orientation_rational() {
+        let third = Rational::new(1, 3);
+        let a = Point::from2d(Rational::from(0), Rational::from(0));
+        let b = Point::from2d(third, third);
+        let c = Point::from2d(Rational::from(1), Rational::from(1));
+
+        assert_eq!(Rational::orientation(&a, &b, &c), Orientation::Collinear);
+    }
+
+    #[test]
+    fn incircle_exact_types() {
+        let (a, b, c) = (
+            Point::from2d(0, 0),
+            Point::from2d(2, 0),
+            Point::from2d(0, 2),
+        );
+        assert_eq!(
+            i32::incircle(&a, &b, &c, &Point::from2d(2, 2)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            i32::incircle(&a, &b, &c, &Point::from2d(1, 1)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            i32::incircle(&a, &b, &c, &Point::from2d(3, 3)),
+            Ordering::Less
+        );
+
+        let r = |x: i64, y: i64| Point::from2d(Rational::from(x), Rational::from(y));
+        let inside = Point::from2d(Rational::new(199, 100), Rational::from(1));
+        assert_eq!(
+            Rational::incircle(&r(0, 0), &r(2, 0), &r(0, 2), &inside),
+            Ordering::Greater
+        );
+    }
+}
@@ -1,16 +1,42 @@
 use super::point::Point;
 
-#[derive(Clone)]
-pub struct Segment {
-    pub start: Point,
-    pub end: Point,
+/// A segment whose start comes first in the sweep plane order.
+/// The coordinates can be any `Scalar`, f64 by default
+#[derive(Clone, Debug)]
+pub struct Segment<T = f64> {
+    pub start: Point<T>,
+    pub end: Point<T>,
 }
 
+/// Position of a point with respect to a segment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Position {
     Start,
     Contains,
     End,
+    /// on the supporting line, but not on the segment
     Outside,
+    /// on the left of the supporting line
+    Left,
+    /// on the right of the supporting line
+    Right,
+}
+
+/// The maximum distance allowed between a point and a segment
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tolerance {
+    Absolute(f64),
+    /// scaled by the largest coordinate involved, at least 1
+    Relative(f64),
+}
+
+/// The result of intersecting two segments
+#[derive(Clone, Debug, PartialEq)]
+pub enum SegmentIntersection<T = f64> {
+    Disjoint,
+    Point(Point<T>),
+    /// the segments are collinear and share more than one point
+    Overlap(Segment<T>),
 }
 
 pub mod segment_impl;
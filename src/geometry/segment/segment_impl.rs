@@ -1,12 +1,27 @@
-use super::{Position, Segment};
-use crate::geometry::point::Point;
+use super::{Position, Segment, SegmentIntersection, Tolerance};
+use crate::{
+    error::{check_finite, Result},
+    geometry::{
+        point::Point,
+        predicates::{orientation, Orientation},
+        scalar::{Field, Scalar},
+    },
+};
 use std::{cmp::Ordering, fmt::Display, ops::Range};
 
-impl Segment {
+impl Segment<f64> {
+    pub fn random(r: Range<f64>) -> Self {
+        let p1 = Point::random(r.clone());
+        let p2 = Point::random(r.clone());
+        Self::new(p1, p2)
+    }
+}
+
+impl<T: Scalar> Segment<T> {
     /// It is not important the order of p1 and p2
-    pub fn new(p1: impl Into<Point>, p2: impl Into<Point>) -> Self {
-        let start: Point = p1.into();
-        let end: Point = p2.into();
+    pub fn new(p1: impl Into<Point<T>>, p2: impl Into<Point<T>>) -> Self {
+        let start: Point<T> = p1.into();
+        let end: Point<T> = p2.into();
 
         match start.sweep_plane_cmp(&end) {
             Ordering::Less | Ordering::Equal => Self { start, end },
@@ -17,28 +32,73 @@ impl Segment {
         }
     }
 
-    pub fn random(r: Range<f64>) -> Self {
-        let p1 = Point::random(r.clone());
-        let p2 = Point::random(r.clone());
-        Self::new(p1, p2)
+    /// like `Segment::new`, but rejects NaN and infinite coordinates,
+    /// reporting the index of the offending point
+    pub fn try_new(p1: impl Into<Point<T>>, p2: impl Into<Point<T>>) -> Result<Self> {
+        let (p1, p2) = (p1.into(), p2.into());
+        check_finite([p1.is_finite(), p2.is_finite()])?;
+        Ok(Self::new(p1, p2))
+    }
+
+    /// true if no endpoint has a NaN or infinite coordinate
+    pub fn is_finite(&self) -> bool {
+        self.start.is_finite() && self.end.is_finite()
+    }
+
+    pub fn upper_limit(&self, p: &Point<T>) -> bool {
+        *p == self.start
     }
 
-    pub fn upper_limit(&self, p: &Point) -> bool {
-        return *p == self.start;
+    pub fn lower_limit(&self, p: &Point<T>) -> bool {
+        *p == self.end
     }
 
-    pub fn lower_limit(&self, p: &Point) -> bool {
-        return *p == self.end;
+    /// true if p lies on the segment, endpoints included. The test is exact
+    pub fn contains(&self, p: &Point<T>) -> bool {
+        orientation(&self.start, &self.end, p) == Orientation::Collinear
+            && self.bounding_box_contains(p)
     }
 
-    pub fn contains(&self, p: &Point) -> bool {
-        f64::min(self.start.x, self.end.x) <= p.x
-            && p.x <= f64::max(self.start.x, self.end.x)
-            && f64::min(self.start.y, self.end.y) <= p.y
-            && p.y <= f64::max(self.start.y, self.end.y)
+    /// true if the distance of p from the segment is within the tolerance
+    pub fn contains_with_tolerance(&self, p: &Point<T>, tolerance: Tolerance) -> bool {
+        let epsilon = match tolerance {
+            Tolerance::Absolute(epsilon) => epsilon,
+            Tolerance::Relative(epsilon) => {
+                let magnitude = [self.start, self.end, *p]
+                    .iter()
+                    .map(|q| q.x.to_f64().abs().max(q.y.to_f64().abs()))
+                    .fold(1.0, f64::max);
+                epsilon * magnitude
+            }
+        };
+        self.distance_from(p) <= epsilon
     }
 
-    pub fn point_position(&self, p: &Point) -> Position {
+    /// distance of p from the closest point of the segment, on the xy plane
+    pub fn distance_from(&self, p: &Point<T>) -> f64 {
+        let (start, end, p) = (
+            self.start.cast::<f64>(),
+            self.end.cast::<f64>(),
+            p.cast::<f64>(),
+        );
+        let dx = end.x - start.x;
+        let dy = end.y - start.y;
+        let squared_length = dx * dx + dy * dy;
+
+        let t = match squared_length == 0.0 {
+            true => 0.0,
+            false => {
+                let t = ((p.x - start.x) * dx + (p.y - start.y) * dy) / squared_length;
+                t.clamp(0.0, 1.0)
+            }
+        };
+
+        (start.x + t * dx - p.x).hypot(start.y + t * dy - p.y)
+    }
+
+    /// Position of p with respect to the segment. Left and Right refer to the
+    /// supporting line going from start to end, that is downward
+    pub fn point_position(&self, p: &Point<T>) -> Position {
         if *p == self.start {
             return Position::Start;
         }
@@ -47,14 +107,107 @@ impl Segment {
             return Position::End;
         }
 
-        if self.contains(p) {
-            return Position::Contains;
+        match orientation(&self.start, &self.end, p) {
+            Orientation::Left => Position::Left,
+            Orientation::Right => Position::Right,
+            Orientation::Collinear if self.bounding_box_contains(p) => Position::Contains,
+            Orientation::Collinear => Position::Outside,
+        }
+    }
+
+    /// true if the two segments share at least one point
+    pub fn intersects(&self, other: &Segment<T>) -> bool {
+        if !self.bounding_box_overlaps(other) {
+            return false;
         }
 
-        Position::Outside
+        let o1 = orientation(&self.start, &self.end, &other.start);
+        let o2 = orientation(&self.start, &self.end, &other.end);
+        let o3 = orientation(&other.start, &other.end, &self.start);
+        let o4 = orientation(&other.start, &other.end, &self.end);
+
+        // with overlapping bounding boxes, collinear segments always share a point
+        (o1 != o2 || o1 == Orientation::Collinear) && (o3 != o4 || o3 == Orientation::Collinear)
     }
 
-    pub fn find_intersection(s1: &Segment, s2: &Segment) -> Option<Point> {
+    /// both segments lie on the same line: start and end follow the sweep plane
+    /// order along it, so the common part goes from the last start to the first end
+    fn collinear_intersection(s1: &Segment<T>, s2: &Segment<T>) -> SegmentIntersection<T> {
+        // `Ord::max` and `Ord::min` would use the lexicographic `PartialOrd` of Point
+        let start = std::cmp::max_by(s1.start, s2.start, Point::sweep_plane_cmp);
+        let end = std::cmp::min_by(s1.end, s2.end, Point::sweep_plane_cmp);
+
+        match start.sweep_plane_cmp(&end) {
+            Ordering::Less => SegmentIntersection::Overlap(Segment::new(start, end)),
+            Ordering::Equal => SegmentIntersection::Point(start),
+            Ordering::Greater => SegmentIntersection::Disjoint,
+        }
+    }
+
+    fn bounding_box_contains(&self, p: &Point<T>) -> bool {
+        Scalar::min(self.start.x, self.end.x) <= p.x
+            && p.x <= Scalar::max(self.start.x, self.end.x)
+            && Scalar::min(self.start.y, self.end.y) <= p.y
+            && p.y <= Scalar::max(self.start.y, self.end.y)
+    }
+
+    fn bounding_box_overlaps(&self, other: &Segment<T>) -> bool {
+        Scalar::min(self.start.x, self.end.x) <= Scalar::max(other.start.x, other.end.x)
+            && Scalar::min(other.start.x, other.end.x) <= Scalar::max(self.start.x, self.end.x)
+            && Scalar::min(self.start.y, self.end.y) <= Scalar::max(other.start.y, other.end.y)
+            && Scalar::min(other.start.y, other.end.y) <= Scalar::max(self.start.y, self.end.y)
+    }
+}
+
+/// Computing the crossing point of two segments needs a division
+impl<T: Field> Segment<T> {
+    /// the intersection point of two segments, `None` if they are disjoint or
+    /// if they overlap. See `Segment::intersection` for the overlapping case
+    pub fn find_intersection(s1: &Segment<T>, s2: &Segment<T>) -> Option<Point<T>> {
+        match s1.intersection(s2) {
+            SegmentIntersection::Point(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    pub fn intersection(&self, other: &Segment<T>) -> SegmentIntersection<T> {
+        let (s1, s2) = (self, other);
+        if !s1.bounding_box_overlaps(s2) {
+            return SegmentIntersection::Disjoint;
+        }
+
+        let o1 = orientation(&s1.start, &s1.end, &s2.start);
+        let o2 = orientation(&s1.start, &s1.end, &s2.end);
+        let o3 = orientation(&s2.start, &s2.end, &s1.start);
+        let o4 = orientation(&s2.start, &s2.end, &s1.end);
+
+        if [o1, o2, o3, o4]
+            .iter()
+            .all(|o| *o == Orientation::Collinear)
+        {
+            return Segment::collinear_intersection(s1, s2);
+        }
+
+        // both endpoints on the same side of the other segment
+        if (o1 == o2 && o1 != Orientation::Collinear) || (o3 == o4 && o3 != Orientation::Collinear)
+        {
+            return SegmentIntersection::Disjoint;
+        }
+
+        // an endpoint touches the other segment: return it exactly
+        if o1 == Orientation::Collinear {
+            return SegmentIntersection::Point(s2.start);
+        }
+        if o2 == Orientation::Collinear {
+            return SegmentIntersection::Point(s2.end);
+        }
+        if o3 == Orientation::Collinear {
+            return SegmentIntersection::Point(s1.start);
+        }
+        if o4 == Orientation::Collinear {
+            return SegmentIntersection::Point(s1.end);
+        }
+
         let a1 = s1.end.y - s1.start.y;
         let b1 = s1.start.x - s1.end.x;
         let c1 = a1 * s1.start.x + b1 * s1.start.y;
@@ -64,48 +217,35 @@ impl Segment {
         let c2 = a2 * s2.start.x + b2 * s2.start.y;
 
         let denominator = a1 * b2 - a2 * b1;
-        if denominator == 0.0 {
-            return None;
-        }
 
         let x = (b2 * c1 - b1 * c2) / denominator;
         let y = (a1 * c2 - a2 * c1) / denominator;
 
-        let interpolation_point = Point::from2d(x, y);
-        if s1.contains(&interpolation_point) == false || s2.contains(&interpolation_point) == false
-        {
-            return None;
-        }
-
-        Some(interpolation_point)
+        SegmentIntersection::Point(Point::from2d(x, y))
     }
 }
 
-impl Display for Segment {
+impl<T: Scalar> Display for Segment<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "[{}, {}]", self.start, self.end)
     }
 }
 
-impl PartialEq for Segment {
+impl<T: PartialEq> PartialEq for Segment<T> {
     fn eq(&self, other: &Self) -> bool {
         self.start == other.start && self.end == other.end
     }
 }
 
-impl PartialOrd for Segment {
+impl<T: Scalar> PartialOrd for Segment<T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match self.start.sweep_plane_cmp(&other.start) {
-            core::cmp::Ordering::Equal => {}
-            ord => return Some(ord),
-        }
-        Some(self.end.sweep_plane_cmp(&other.end))
+        Some(self.cmp(other))
     }
 }
 
-impl Eq for Segment {}
+impl<T: Scalar> Eq for Segment<T> {}
 
-impl Ord for Segment {
+impl<T: Scalar> Ord for Segment<T> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match self.start.sweep_plane_cmp(&other.start) {
             core::cmp::Ordering::Equal => {}
@@ -115,8 +255,8 @@ impl Ord for Segment {
     }
 }
 
-impl From<Segment> for [Point; 2] {
-    fn from(s: Segment) -> Self {
+impl<T: Scalar> From<Segment<T>> for [Point<T>; 2] {
+    fn from(s: Segment<T>) -> Self {
         [s.start, s.end]
     }
 }
@@ -1,8 +1,9 @@
 #[cfg(test)]
+#[cfg(test)]
 use crate::geometry::point::Point;
 
 #[cfg(test)]
-use crate::geometry::segment::Segment;
+use crate::geometry::segment::{Position, Segment, SegmentIntersection, Tolerance};
 
 #[test]
 fn new_segment() {
@@ -46,7 +47,70 @@ fn segment_doesnt_contains_point() {
     let s = Segment::new([0.0, 0.0], [2.0, 2.0]);
     let p = Point::from2d(3.0, 3.0);
 
-    assert_eq!(s.contains(&p), false);
+    assert!(!s.contains(&p));
+}
+
+#[test]
+fn segment_doesnt_contains_point_in_bounding_box() {
+    let s = Segment::new([0.0, 0.0], [4.0, 4.0]);
+    let p = Point::from2d(3.0, 1.0);
+
+    assert!(!s.contains(&p));
+}
+
+#[test]
+fn segment_contains_point_nearly_collinear() {
+    let s = Segment::new([0.0, 0.0], [3.0, 3.0]);
+    let p = Point::from2d(0.1 + 0.2, 0.3);
+
+    // 0.1 + 0.2 is not exactly 0.3
+    assert!(!s.contains(&p));
+    assert!(s.contains_with_tolerance(&p, Tolerance::Absolute(1e-12)));
+    assert!(s.contains_with_tolerance(&p, Tolerance::Relative(1e-12)));
+}
+
+#[test]
+fn segment_contains_with_tolerance_beyond_end() {
+    let s = Segment::new([0.0, 0.0], [4.0, 0.0]);
+
+    assert!(s.contains_with_tolerance(&Point::from2d(4.05, 0.0), Tolerance::Absolute(0.1)));
+    assert!(!s.contains_with_tolerance(&Point::from2d(4.5, 0.0), Tolerance::Absolute(0.1)));
+}
+
+#[test]
+fn segment_contains_with_relative_tolerance() {
+    let s = Segment::new([1e6, 1e6], [2e6, 1e6]);
+    let p = Point::from2d(1.5e6, 1e6 + 1e-4);
+
+    assert!(!s.contains_with_tolerance(&p, Tolerance::Absolute(1e-6)));
+    assert!(s.contains_with_tolerance(&p, Tolerance::Relative(1e-9)));
+}
+
+#[test]
+fn segment_distance_from_point() {
+    let s = Segment::new([0.0, 0.0], [4.0, 0.0]);
+
+    assert_eq!(s.distance_from(&Point::from2d(2.0, 3.0)), 3.0);
+    assert_eq!(s.distance_from(&Point::from2d(7.0, 4.0)), 5.0);
+}
+
+#[test]
+fn point_position() {
+    // the segment goes downward, from (0, 4) to (0, 0)
+    let s = Segment::new([0.0, 0.0], [0.0, 4.0]);
+
+    assert_eq!(s.point_position(&Point::from2d(0.0, 4.0)), Position::Start);
+    assert_eq!(s.point_position(&Point::from2d(0.0, 0.0)), Position::End);
+    assert_eq!(
+        s.point_position(&Point::from2d(0.0, 2.0)),
+        Position::Contains
+    );
+    assert_eq!(
+        s.point_position(&Point::from2d(0.0, 6.0)),
+        Position::Outside
+    );
+    assert_eq!(s.point_position(&Point::from2d(1.0, 2.0)), Position::Left);
+    assert_eq!(s.point_position(&Point::from2d(-1.0, 2.0)), Position::Right);
 }
 
 #[test]
@@ -90,6 +154,108 @@ fn find_interpolation_incident() {
     );
 }
 
+#[test]
+fn intersection_crossing() {
+    let s1 = Segment::new([0.0, 0.0], [4.0, 4.0]);
+    let s2 = Segment::new([0.0, 4.0], [4.0, 0.0]);
+
+    assert_eq!(
+        s1.intersection(&s2),
+        SegmentIntersection::Point(Point::from2d(2.0, 2.0))
+    );
+    assert!(s1.intersects(&s2));
+}
+
+#[test]
+fn intersection_disjoint() {
+    let s1 = Segment::new([0.0, 0.0], [4.0, 4.0]);
+    let s2 = Segment::new([0.0, 4.0], [1.0, 3.0]);
+
+    assert_eq!(s1.intersection(&s2), SegmentIntersection::Disjoint);
+    assert!(!s1.intersects(&s2));
+}
+
+#[test]
+fn intersection_parallel() {
+    let s1 = Segment::new([0.0, 0.0], [4.0, 0.0]);
+    let s2 = Segment::new([0.0, 1.0], [4.0, 1.0]);
+
+    assert_eq!(s1.intersection(&s2), SegmentIntersection::Disjoint);
+    assert!(!s1.intersects(&s2));
+}
+
+#[test]
+fn intersection_overlap() {
+    let s1 = Segment::new([0.0, 0.0], [4.0, 0.0]);
+    let s2 = Segment::new([2.0, 0.0], [6.0, 0.0]);
+
+    assert_eq!(
+        s1.intersection(&s2),
+        SegmentIntersection::Overlap(Segment::new([2.0, 0.0], [4.0, 0.0]))
+    );
+    assert!(s1.intersects(&s2));
+}
+
+#[test]
+fn intersection_overlap_contained() {
+    let s1 = Segment::new([0.0, 0.0], [6.0, 6.0]);
+    let s2 = Segment::new([4.0, 4.0], [1.0, 1.0]);
+
+    assert_eq!(
+        s1.intersection(&s2),
+        SegmentIntersection::Overlap(s2.clone())
+    );
+    assert_eq!(
+        s2.intersection(&s1),
+        SegmentIntersection::Overlap(s2.clone())
+    );
+}
+
+#[test]
+fn intersection_collinear_touching() {
+    let s1 = Segment::new([0.0, 0.0], [2.0, 2.0]);
+    let s2 = Segment::new([2.0, 2.0], [4.0, 4.0]);
+
+    assert_eq!(
+        s1.intersection(&s2),
+        SegmentIntersection::Point(Point::from2d(2.0, 2.0))
+    );
+}
+
+#[test]
+fn intersection_collinear_disjoint() {
+    let s1 = Segment::new([0.0, 0.0], [1.0, 1.0]);
+    let s2 = Segment::new([2.0, 2.0], [4.0, 4.0]);
+
+    assert_eq!(s1.intersection(&s2), SegmentIntersection::Disjoint);
+    assert!(!s1.intersects(&s2));
+}
+
+#[test]
+fn intersection_endpoint_touch() {
+    let s1 = Segment::new([0.0, 0.0], [4.0, 0.0]);
+    let s2 = Segment::new([2.0, 0.0], [2.0, 3.0]);
+
+    assert_eq!(
+        s1.intersection(&s2),
+        SegmentIntersection::Point(Point::from2d(2.0, 0.0))
+    );
+    assert!(s2.intersects(&s1));
+}
+
+#[test]
+fn intersection_degenerate_segment() {
+    let s1 = Segment::new([0.0, 0.0], [4.0, 4.0]);
+    let on = Segment::new([1.0, 1.0], [1.0, 1.0]);
+    let off = Segment::new([1.0, 2.0], [1.0, 2.0]);
+
+    assert_eq!(
+        s1.intersection(&on),
+        SegmentIntersection::Point(Point::from2d(1.0, 1.0))
+    );
+    assert_eq!(s1.intersection(&off), SegmentIntersection::Disjoint);
+}
+
 #[test]
 fn display_segment() {
     let s = Segment::new([0.0, 0.0], [4.0, 4.0]);
@@ -101,7 +267,13 @@ fn equals() {
     let s1 = Segment::new([4.2, 7.2], [4.0, 4.0]);
     let s2 = Segment::new([4.2, 7.2], [4.0, 4.0]);
 
-    assert_eq!(s1.eq(&s2), true);
+    assert!(s1.eq(&s2));
+}
+
+#[test]
+fn try_new_segment() {
+    assert!(Segment::try_new([2.0, 2.0], [4.0, 4.0]).is_ok());
+    assert!(Segment::try_new([2.0, 2.0], [f64::NAN, 4.0]).is_err());
 }
 
 #[test]
@@ -0,0 +1,307 @@
+use std::cmp::Ordering;
+
+use super::{point::Point, predicates::Orientation, scalar::Scalar, segment::Segment};
+use crate::error::{check_finite, Result};
+
+/// A segment tree over segments that do not cross, though they may share
+/// endpoints (de Berg et al. chapter 10.3).
+///
+/// The x of the endpoints split the line in elementary intervals, the points
+/// themselves and the open intervals between them, which are the leaves of a
+/// balanced tree. A segment is stored in the nodes whose interval it covers
+/// but not the one of their parent, at most two for each level. The segments
+/// of a node span its whole interval without crossing, so they are kept
+/// sorted from the bottom up, and the ones meeting a vertical query segment
+/// are found by binary search. Building the tree takes O(n log² n) time and
+/// O(n log n) memory, and a query O(log² n + k) time, where k is the number of
+/// segments reported.
+///
+/// Segments are referred by their index in the input
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentTree<T = f64> {
+    // the segments, start on the left, or at the bottom for vertical ones
+    segments: Vec<Segment<T>>,
+
+    // the distinct x of the endpoints, sorted. The leaf 2i is xs[i] and the
+    // leaf 2i + 1 is the open interval between xs[i] and xs[i + 1]
+    xs: Vec<T>,
+
+    // the nodes, the root first
+    nodes: Vec<SegmentNode>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct SegmentNode {
+    // the leaves below, end excluded
+    leaves: (usize, usize),
+
+    // the segments covering the leaves but not the parent, from the bottom up
+    segments: Vec<usize>,
+
+    children: Option<(usize, usize)>,
+}
+
+impl<T: Scalar> SegmentTree<T> {
+    pub fn new(segments: Vec<Segment<T>>) -> Self {
+        let segments: Vec<Segment<T>> = segments
+            .into_iter()
+            .map(|s| {
+                let (a, b) = (
+                    Point::from2d(s.start.x, s.start.y),
+                    Point::from2d(s.end.x, s.end.y),
+                );
+                match (a.x, a.y).partial_cmp(&(b.x, b.y)) {
+                    Some(Ordering::Greater) => Segment { start: b, end: a },
+                    _ => Segment { start: a, end: b },
+                }
+            })
+            .collect();
+
+        let mut xs: Vec<T> = segments.iter().flat_map(|s| [s.start.x, s.end.x]).collect();
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        xs.dedup();
+
+        let mut tree = Self {
+            segments,
+            xs,
+            nodes: vec![],
+        };
+        if !tree.xs.is_empty() {
+            tree.build(0, 2 * tree.xs.len() - 1);
+            for s in 0..tree.segments.len() {
+                let leaves = (
+                    tree.leaf(tree.segments[s].start.x),
+                    tree.leaf(tree.segments[s].end.x) + 1,
+                );
+                tree.insert(0, s, leaves);
+            }
+            for node in 0..tree.nodes.len() {
+                tree.sort(node);
+            }
+        }
+        tree
+    }
+
+    /// like `new`, but rejects NaN and infinite coordinates. The segments are
+    /// not checked for crossings
+    pub fn try_new(segments: Vec<Segment<T>>) -> Result<Self> {
+        check_finite(segments.iter().map(|s| s.is_finite()))?;
+        Ok(Self::new(segments))
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// the segments crossing the vertical line through x
+    pub fn stabbing(&self, x: T) -> Vec<usize> {
+        let mut found = vec![];
+        self.visit(x, |node| found.extend_from_slice(&node.segments));
+        found
+    }
+
+    /// the segments crossing the vertical segment from (x, min_y) to (x, max_y)
+    pub fn crossing(&self, x: T, min_y: T, max_y: T) -> Vec<usize> {
+        let (bottom, top) = (Point::from2d(x, min_y), Point::from2d(x, max_y));
+        let mut found = vec![];
+        self.visit(x, |node| {
+            let start = node
+                .segments
+                .partition_point(|&s| self.side(s, &bottom) == Ordering::Less);
+            found.extend(
+                node.segments[start..]
+                    .iter()
+                    .take_while(|&&s| self.side(s, &top) != Ordering::Greater),
+            );
+        });
+        found
+    }
+
+    /// calls f with the nodes from the root to the leaf of x
+    fn visit<'a>(&'a self, x: T, mut f: impl FnMut(&'a SegmentNode)) {
+        match (self.xs.first(), self.xs.last()) {
+            (Some(&first), Some(&last)) if first <= x && x <= last => (),
+            _ => return,
+        }
+        let i = self.xs.partition_point(|&v| v < x);
+        let leaf = match self.xs[i] == x {
+            true => 2 * i,
+            false => 2 * i - 1,
+        };
+
+        let mut node = 0;
+        loop {
+            let n = &self.nodes[node];
+            f(n);
+            node = match n.children {
+                Some((left, _)) if leaf < self.nodes[left].leaves.1 => left,
+                Some((_, right)) => right,
+                None => return,
+            };
+        }
+    }
+
+    /// Less if the segment passes below the point, Greater if above, Equal if
+    /// through it. The point has an x in the range of the segment
+    fn side(&self, s: usize, p: &Point<T>) -> Ordering {
+        let s = &self.segments[s];
+        if s.start.x == s.end.x {
+            return match (s.end.y < p.y, p.y < s.start.y) {
+                (true, _) => Ordering::Less,
+                (_, true) => Ordering::Greater,
+                _ => Ordering::Equal,
+            };
+        }
+        match T::orientation(&s.start, &s.end, p) {
+            Orientation::Left => Ordering::Less,
+            Orientation::Right => Ordering::Greater,
+            Orientation::Collinear => Ordering::Equal,
+        }
+    }
+
+    /// the leaf of an x of an endpoint
+    fn leaf(&self, x: T) -> usize {
+        2 * self.xs.partition_point(|&v| v < x)
+    }
+
+    /// builds the subtree of the leaves, returning its node
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let node = self.nodes.len();
+        self.nodes.push(SegmentNode {
+            leaves: (start, end),
+            segments: vec![],
+            children: None,
+        });
+        if end - start > 1 {
+            let mid = (start + end) / 2;
+            let left = self.build(start, mid);
+            let right = self.build(mid, end);
+            self.nodes[node].children = Some((left, right));
+        }
+        node
+    }
+
+    fn insert(&mut self, node: usize, s: usize, (start, end): (usize, usize)) {
+        let n = &self.nodes[node];
+        let (lo, hi) = n.leaves;
+        if end <= lo || hi <= start {
+            return;
+        }
+        if start <= lo && hi <= end {
+            self.nodes[node].segments.push(s);
+            return;
+        }
+        if let Some((left, right)) = n.children {
+            self.insert(left, s, (start, end));
+            self.insert(right, s, (start, end));
+        }
+    }
+
+    /// sorts the segments of the node from the bottom up. Those of a single
+    /// point have an endpoint there, or are vertical, and are sorted by their
+    /// lowest and then their highest point on the line. The others span an
+    /// open interval without crossing, and are compared where one of them
+    /// starts
+    fn sort(&mut self, node: usize) {
+        let mut segments = std::mem::take(&mut self.nodes[node].segments);
+        let (lo, hi) = self.nodes[node].leaves;
+        match hi - lo == 1 && lo % 2 == 0 {
+            true => {
+                let x = self.xs[lo / 2];
+                // the lowest and the highest point of the segment on the line
+                let span = |s: usize| {
+                    let s = &self.segments[s];
+                    match (s.start.x == x, s.end.x == x) {
+                        (true, true) => (s.start.y, s.end.y),
+                        (true, false) => (s.start.y, s.start.y),
+                        _ => (s.end.y, s.end.y),
+                    }
+                };
+                segments.sort_by(|&a, &b| {
+                    span(a)
+                        .partial_cmp(&span(b))
+                        .unwrap_or(Ordering::Equal)
+                        .then(a.cmp(&b))
+                });
+            }
+            false => segments.sort_by(|&a, &b| self.below(a, b).then(a.cmp(&b))),
+        }
+        self.nodes[node].segments = segments;
+    }
+
+    /// Less if the segment a is below b where both are defined, neither being
+    /// vertical. The one starting last is compared with the other, and if it
+    /// starts on it, the one ending first
+    fn below(&self, a: usize, b: usize) -> Ordering {
+        let (sa, sb) = (&self.segments[a], &self.segments[b]);
+        let start = match sa.start.x < sb.start.x {
+            true => self.side(a, &sb.start),
+            false => self.side(b, &sa.start).reverse(),
+        };
+        match (start, sa.end.x < sb.end.x) {
+            (Ordering::Equal, true) => self.side(b, &sa.end).reverse(),
+            (Ordering::Equal, false) => self.side(a, &sb.end),
+            (start, _) => start,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SegmentTree;
+    use crate::geometry::{
+        segment::Segment,
+        test_utils::{non_crossing, sorted},
+    };
+    use rand::Rng;
+
+    #[test]
+    fn segment_tree_queries() {
+        let segments = vec![
+            Segment::new([0.0, 0.0], [4.0, 2.0]),
+            Segment::new([4.0, 2.0], [8.0, 0.0]),
+            Segment::new([4.0, 2.0], [4.0, 6.0]),
+            Segment::new([1.0, 4.0], [7.0, 5.0]),
+            Segment::new([2.0, 8.0], [3.0, 7.0]),
+        ];
+        let tree = SegmentTree::new(segments);
+
+        assert_eq!(sorted(tree.stabbing(4.0)), vec![0, 1, 2, 3]);
+        assert_eq!(sorted(tree.stabbing(2.5)), vec![0, 3, 4]);
+        assert_eq!(tree.stabbing(9.0), vec![]);
+        assert_eq!(sorted(tree.crossing(4.0, 0.0, 3.0)), vec![0, 1, 2]);
+        assert_eq!(sorted(tree.crossing(4.0, 3.0, 4.0)), vec![2]);
+        assert_eq!(sorted(tree.crossing(2.0, 0.0, 1.0)), vec![0]);
+        assert_eq!(sorted(tree.crossing(2.0, 1.5, 8.0)), vec![3, 4]);
+        assert_eq!(tree.crossing(6.0, 5.5, 9.0), vec![]);
+    }
+
+    #[test]
+    fn same_answers_as_brute_force() {
+        let mut rng = rand::thread_rng();
+        let segments = non_crossing(60, false);
+        let tree = SegmentTree::new(segments.clone());
+
+        for _ in 0..300 {
+            let x = rng.gen_range(-2..42) as f64 / 2.0;
+            let (a, b) = (
+                rng.gen_range(-2..42) as f64 / 2.0,
+                rng.gen_range(-2..42) as f64 / 2.0,
+            );
+            let query = Segment::new([x, a.min(b)], [x, a.max(b)]);
+            let expected: Vec<usize> = (0..segments.len())
+                .filter(|&i| segments[i].intersects(&query))
+                .collect();
+            assert_eq!(sorted(tree.crossing(x, a.min(b), a.max(b))), expected);
+        }
+    }
+
+    #[test]
+    fn empty_segment_tree() {
+        assert!(SegmentTree::<f64>::new(vec![]).stabbing(0.0).is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+//! Helpers shared by the tests of the geometric structures
+
+use rand::Rng;
+
+use super::{predicates::Orientation, scalar::Scalar, segment::Segment};
+
+pub fn sorted(mut v: Vec<usize>) -> Vec<usize> {
+    v.sort();
+    v
+}
+
+/// up to n segments on a grid, meeting the others at most in a common
+/// endpoint. With axis_parallel a third of them are horizontal or vertical
+pub fn non_crossing(n: usize, axis_parallel: bool) -> Vec<Segment> {
+    let mut rng = rand::thread_rng();
+    let mut segments: Vec<Segment> = vec![];
+    for i in 0..n * 20 {
+        let mut point = || [rng.gen_range(0..20) as f64, rng.gen_range(0..20) as f64];
+        let (a, mut b) = (point(), point());
+        match i % 6 {
+            0 if axis_parallel => b[0] = a[0],
+            1 if axis_parallel => b[1] = a[1],
+            _ => (),
+        }
+        let s = Segment::new(a, b);
+        let ends = |t: &Segment| [t.start, t.end];
+        let valid = s.start != s.end
+            && segments.iter().all(|t| {
+                let collinear = ends(t)
+                    .iter()
+                    .all(|p| f64::orientation(&s.start, &s.end, p) == Orientation::Collinear);
+                let shared = ends(&s).iter().any(|p| ends(t).contains(p));
+                !s.intersects(t) || (shared && !collinear)
+            });
+        if valid && segments.len() < n {
+            segments.push(s);
+        }
+    }
+    segments
+}
@@ -0,0 +1,226 @@
+use super::{
+    interval_tree::IntervalTree, point::Point, point_index::PointIndex, points::Points,
+    range_tree::RangeTree, scalar::Scalar, segment::Segment, segment_tree::SegmentTree,
+};
+use crate::error::{check_finite, Result};
+
+/// Windowing queries over segments that do not cross, though they may share
+/// endpoints: the segments meeting an axis-parallel rectangle (de Berg et al.
+/// chapter 10).
+///
+/// A segment meets the window if it has an endpoint inside, found with a
+/// range tree over the endpoints, or else if it crosses the boundary. A
+/// horizontal segment crossing the window crosses its left edge, and a
+/// vertical one its bottom edge: they are found with interval trees. The
+/// other segments are found with segment trees, one for the left and right
+/// edges and one, with x and y swapped, for the bottom and top edges. A query
+/// takes O(log² n + k log k) time, where k is the number of segments reported:
+/// each one is found at most six times, then sorted.
+///
+/// Segments are referred by their index in the input
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowIndex<T = f64> {
+    segments: Vec<Segment<T>>,
+
+    // the start of the segment i is the point 2i, the end 2i + 1
+    endpoints: RangeTree<T>,
+
+    // the trees with x and y swapped are over the segments transposed. Each
+    // tree comes with the indexes in the input of its segments
+    horizontal: (IntervalTree<T>, Vec<usize>),
+    vertical: (IntervalTree<T>, Vec<usize>),
+    slanted: (SegmentTree<T>, SegmentTree<T>, Vec<usize>),
+}
+
+impl<T: Scalar> WindowIndex<T> {
+    pub fn new(segments: Vec<Segment<T>>) -> Self {
+        let endpoints = Points::from(
+            segments
+                .iter()
+                .flat_map(|s| [s.start, s.end])
+                .collect::<Vec<_>>(),
+        );
+
+        let (mut horizontal, mut vertical, mut slanted) = (vec![], vec![], vec![]);
+        for (i, s) in segments.iter().enumerate() {
+            match (s.start.y == s.end.y, s.start.x == s.end.x) {
+                (true, _) => horizontal.push(i),
+                (_, true) => vertical.push(i),
+                _ => slanted.push(i),
+            }
+        }
+        let pick = |indexes: &[usize]| indexes.iter().map(|&i| segments[i].clone()).collect();
+        let transposed =
+            |indexes: &[usize]| indexes.iter().map(|&i| transpose(&segments[i])).collect();
+
+        Self {
+            endpoints: RangeTree::new(endpoints),
+            horizontal: (IntervalTree::new(pick(&horizontal)), horizontal.clone()),
+            vertical: (IntervalTree::new(transposed(&vertical)), vertical.clone()),
+            slanted: (
+                SegmentTree::new(pick(&slanted)),
+                SegmentTree::new(transposed(&slanted)),
+                slanted.clone(),
+            ),
+            segments,
+        }
+    }
+
+    /// like `new`, but rejects NaN and infinite coordinates. The segments are
+    /// not checked for crossings
+    pub fn try_new(segments: Vec<Segment<T>>) -> Result<Self> {
+        check_finite(segments.iter().map(|s| s.is_finite()))?;
+        Ok(Self::new(segments))
+    }
+
+    pub fn segments(&self) -> &[Segment<T>] {
+        &self.segments
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// the segments meeting the rectangle of the corners min and max, its
+    /// boundary included, sorted by index
+    pub fn query(&self, min: &Point<T>, max: &Point<T>) -> Vec<usize> {
+        if max.x < min.x || max.y < min.y {
+            return vec![];
+        }
+
+        let mut found: Vec<usize> = self
+            .endpoints
+            .range(min, max)
+            .into_iter()
+            .map(|p| p / 2)
+            .collect();
+
+        let (tree, indexes) = &self.horizontal;
+        found.extend(
+            tree.crossing(min.x, min.y, max.y)
+                .into_iter()
+                .map(|s| indexes[s]),
+        );
+        let (tree, indexes) = &self.vertical;
+        found.extend(
+            tree.crossing(min.y, min.x, max.x)
+                .into_iter()
+                .map(|s| indexes[s]),
+        );
+
+        let (by_x, by_y, indexes) = &self.slanted;
+        for x in [min.x, max.x] {
+            found.extend(
+                by_x.crossing(x, min.y, max.y)
+                    .into_iter()
+                    .map(|s| indexes[s]),
+            );
+        }
+        for y in [min.y, max.y] {
+            found.extend(
+                by_y.crossing(y, min.x, max.x)
+                    .into_iter()
+                    .map(|s| indexes[s]),
+            );
+        }
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+}
+
+/// the segment mirrored on the line x = y
+fn transpose<T: Scalar>(s: &Segment<T>) -> Segment<T> {
+    Segment::new(
+        Point::from2d(s.start.y, s.start.x),
+        Point::from2d(s.end.y, s.end.x),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WindowIndex;
+    use crate::geometry::{point::Point, segment::Segment, test_utils::non_crossing};
+    use rand::Rng;
+
+    /// true if the segment meets the rectangle, by brute force
+    fn meets(s: &Segment, min: &Point, max: &Point) -> bool {
+        let inside = |p: &Point| min.x <= p.x && p.x <= max.x && min.y <= p.y && p.y <= max.y;
+        let corners = [
+            [min.x, min.y],
+            [max.x, min.y],
+            [max.x, max.y],
+            [min.x, max.y],
+        ];
+        inside(&s.start)
+            || inside(&s.end)
+            || (0..4).any(|i| s.intersects(&Segment::new(corners[i], corners[(i + 1) % 4])))
+    }
+
+    #[test]
+    fn window_queries() {
+        let segments = vec![
+            // a room with a door on the right
+            Segment::new([0.0, 0.0], [6.0, 0.0]),
+            Segment::new([6.0, 0.0], [6.0, 2.0]),
+            Segment::new([6.0, 4.0], [6.0, 6.0]),
+            Segment::new([0.0, 6.0], [6.0, 6.0]),
+            Segment::new([0.0, 0.0], [0.0, 6.0]),
+            // a ramp and a long corridor wall
+            Segment::new([7.0, 1.0], [12.0, 5.0]),
+            Segment::new([-5.0, 8.0], [15.0, 8.0]),
+        ];
+        let index = WindowIndex::new(segments);
+
+        let query = |min: [f64; 2], max: [f64; 2]| index.query(&min.into(), &max.into());
+        // inside the room
+        assert_eq!(query([1.0, 1.0], [5.0, 5.0]), vec![]);
+        // across the corridor wall, no endpoint inside
+        assert_eq!(query([1.0, 7.0], [2.0, 9.0]), vec![6]);
+        // across the ramp, no endpoint inside
+        assert_eq!(query([9.0, 2.0], [10.0, 4.0]), vec![5]);
+        assert_eq!(query([9.5, 0.0], [9.6, 9.0]), vec![5, 6]);
+        // across the door posts and the floor
+        assert_eq!(query([5.0, -1.0], [7.0, 5.0]), vec![0, 1, 2, 5]);
+        assert_eq!(query([-1.0, -1.0], [20.0, 20.0]), vec![0, 1, 2, 3, 4, 5, 6]);
+        // touching the boundary counts
+        assert_eq!(query([6.0, 2.5], [6.5, 3.5]), vec![]);
+        assert_eq!(query([6.0, 2.0], [6.5, 3.5]), vec![1]);
+        // an empty window
+        assert_eq!(query([5.0, 5.0], [1.0, 1.0]), vec![]);
+    }
+
+    #[test]
+    fn same_answers_as_brute_force() {
+        let mut rng = rand::thread_rng();
+        let segments = non_crossing(80, true);
+        let index = WindowIndex::new(segments.clone());
+
+        for _ in 0..300 {
+            let mut coordinate = || rng.gen_range(-2..42) as f64 / 2.0;
+            let (a, b) = (
+                Point::from2d(coordinate(), coordinate()),
+                Point::from2d(coordinate(), coordinate()),
+            );
+            let min = Point::from2d(a.x.min(b.x), a.y.min(b.y));
+            let max = Point::from2d(a.x.max(b.x), a.y.max(b.y));
+            let expected: Vec<usize> = (0..segments.len())
+                .filter(|&i| meets(&segments[i], &min, &max))
+                .collect();
+            assert_eq!(index.query(&min, &max), expected);
+        }
+    }
+
+    #[test]
+    fn empty_window_index() {
+        let empty: WindowIndex = WindowIndex::new(vec![]);
+        assert!(empty.is_empty());
+        assert!(empty
+            .query(&Point::from2d(0.0, 0.0), &Point::from2d(1.0, 1.0))
+            .is_empty());
+    }
+}